@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+# A stub external signer for dfx that signs with a local ed25519 PEM file.
+# Usage: stub-signer.bash <identity.pem>
+set -euo pipefail
+
+key="$1"
+request="$(cat)"
+echo "$request" >> "${STUB_SIGNER_LOG:-/dev/null}"
+
+case "$(jq -r .operation <<< "$request")" in
+  get-public-key)
+    public_key="$(openssl pkey -in "$key" -pubout -outform DER | xxd -p -c 1000)"
+    jq -n --arg public_key "$public_key" '{public_key: $public_key}'
+    ;;
+  sign)
+    content="$(mktemp)"
+    jq -r .content <<< "$request" | xxd -r -p > "$content"
+    signature="$(openssl pkeyutl -sign -inkey "$key" -rawin -in "$content" | xxd -p -c 1000)"
+    rm "$content"
+    jq -n --arg signature "$signature" '{signature: $signature}'
+    ;;
+  *)
+    jq -n '{error: "unsupported operation"}'
+    ;;
+esac
@@ -154,6 +154,30 @@ teardown() {
   assert_contains "Finished building canisters." "$output"
 }
 
+@test "build --report reports the composition of the wasm module" {
+  dfx_start
+  dfx canister create --all
+  assert_command dfx build e2e_project_backend --report
+  assert_contains "Wasm report for canister 'e2e_project_backend':"
+  assert_contains "Largest functions:"
+  assert_contains "candid:service (public)"
+  assert_contains "query            greet"
+
+  assert_command dfx canister wasm-report e2e_project_backend --format json
+  assert_eq "greet" "$(echo "$stdout" | jq -r '.[0].exported_methods[] | select(.kind == "query") | .name')"
+  assert_eq "false" "$(echo "$stdout" | jq '.[0].gzip_size > .[0].wasm_size')"
+
+  assert_command dfx build --all --report --report-format json
+  assert_eq "e2e_project_backend" "$(echo "$stdout" | jq -r '.[].canister')"
+  assert_command dfx canister wasm-report --all --format json
+  assert_eq "e2e_project_backend" "$(echo "$stdout" | jq -r '.[].canister')"
+
+  assert_command_fail dfx build e2e_project_backend --report --budget 1KiB
+  assert_contains "exceeds the budget of 1.00 KiB" "$stderr"
+  assert_command_fail dfx canister wasm-report e2e_project_backend --budget 1KiB
+  assert_contains "exceeds the budget" "$stderr"
+}
+
 @test "build succeeds if enable optimize" {
   jq '.canisters.e2e_project_backend.optimize="cycles"' dfx.json | sponge dfx.json
   dfx_start
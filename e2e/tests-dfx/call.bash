@@ -35,7 +35,7 @@ teardown() {
   assert_command dfx canister call hello_backend returns_int '(67)' --output json
   assert_eq '"67"'
   assert_command dfx canister call hello_backend returns_int '(111222333444555666777888999 : int)' --output json
-  assert_eq '"111_222_333_444_555_666_777_888_999"'
+  assert_eq '"111222333444555666777888999"'
 
   assert_command dfx canister call hello_backend returns_int32 '(67)' --output json
   assert_eq '67'
@@ -72,11 +72,9 @@ teardown() {
 }'
 
   assert_command dfx canister call hello_backend returns_blob '("abd")' --output json
-  assert_eq '[
-  97,
-  98,
-  100
-]'
+  assert_eq '"616264"'
+  assert_command dfx canister call hello_backend returns_blob '("abd")' --output json --blob-format base64
+  assert_eq '"YWJk"'
 
   assert_command dfx canister call hello_backend returns_tuple '()' --output json
   assert_eq '"the first element"
@@ -88,6 +86,46 @@ teardown() {
   assert_eq '"the only element"'
 }
 
+@test "call --type json" {
+  install_asset method_signatures
+
+  dfx_start
+  dfx deploy
+
+  assert_command dfx canister call hello_backend returns_string '"you"' --type json
+  assert_eq '("Hello, you!")'
+
+  assert_command dfx canister call hello_backend returns_transfer --type json '{
+    "to": { "owner": "aaaaa-aa", "subaccount": "0102" },
+    "amount": "18446744073709551616",
+    "memo": "18446744073709551615",
+    "status": { "baz": { "a": 51 } }
+  }'
+  assert_contains 'owner = principal "aaaaa-aa"'
+  assert_contains 'subaccount = opt blob "\01\02"'
+  assert_contains 'amount = 18_446_744_073_709_551_616'
+  assert_contains 'memo = opt (18_446_744_073_709_551_615 : nat64)'
+  assert_contains 'status = variant { baz = record { a = 51 : int32 } }'
+
+  # opt fields can be left out, and variant cases without a value are strings
+  echo '{ "to": { "owner": "aaaaa-aa" }, "amount": 1, "status": "foo" }' > transfer.json
+  assert_command dfx canister call hello_backend returns_transfer --argument-file transfer.json
+  assert_contains 'subaccount = null'
+  assert_contains 'memo = null'
+  assert_contains 'status = variant { foo }'
+
+  assert_command dfx canister call hello_backend returns_pair --type json '[-5, [1, "2"]]'
+  assert_contains '-5 : int'
+  assert_contains 'vec { 1 : int16; 2 : int16 }'
+
+  assert_command_fail dfx canister call hello_backend returns_pair --type json '[-5]'
+  assert_contains "The method takes 2 arguments, so the JSON argument must be an array of 2 values." "$stderr"
+  assert_command_fail dfx canister call hello_backend returns_transfer --type json '{ "to": { "owner": "aaaaa-aa" }, "amount": 1, "status": "foo", "extra": 1 }'
+  assert_contains "Unknown field 'extra' at \$" "$stderr"
+  assert_command_fail dfx canister call hello_backend returns_pair --type json '[-5, [1, 100000]]'
+  assert_contains "Invalid int16 '100000' at \$[1][1]" "$stderr"
+}
+
 @test "call --candid <path to candid file>" {
   install_asset call
 
@@ -104,9 +142,20 @@ teardown() {
   # shellcheck disable=SC2154
   assert_eq '(record { 99 = "A"; 100 = "B" })'
 
+  assert_command dfx canister call "$CANISTER_ID" make_struct2 '("A", "B")' --output json
+  assert_eq '{
+  "100": "B",
+  "99": "A"
+}'
+
   # if passing the candid file, field names available
   assert_command dfx canister call --candid full.did "$CANISTER_ID" make_struct2 '("A", "B")'
   assert_eq '(record { c = "A"; d = "B" })'
+  assert_command dfx canister call --candid full.did "$CANISTER_ID" make_struct2 '("A", "B")' --output json
+  assert_eq '{
+  "c": "A",
+  "d": "B"
+}'
 
   # given a canister id, fetch the did file from metadata
   assert_command dfx canister call "$CANISTER_ID" make_struct '("A", "B")'
@@ -25,6 +25,7 @@ teardown() {
 
     # Create the replaced canister on the fiduciary subnet.
     dfx canister create replaced --subnet-type fiduciary
+    REPLACED_ID=$(dfx canister id replaced)
 
     dfx canister stop migrated
     dfx canister stop replaced
@@ -38,4 +39,39 @@ teardown() {
 
     assert_command dfx canister status migrated
     assert_command_fail dfx canister status replaced
+
+    # The migrated canister now has the canister ID of the replaced canister.
+    assert_command dfx canister id migrated
+    assert_eq "$REPLACED_ID"
+}
+
+@test "canister migrate-id --check reports and fixes problems" {
+    dfx_start --system-canisters
+    install_asset counter
+
+    jq '.canisters.migrated = .canisters.hello_backend | del(.canisters.hello_backend)' dfx.json | sponge dfx.json
+    jq '.canisters.replaced = { "main": "counter.mo", "type": "motoko" }' dfx.json | sponge dfx.json
+
+    dfx deploy migrated
+    dfx canister create replaced --subnet-type fiduciary
+
+    # Declining the first fix aborts after the problems are reported.
+    assert_command_fail dfx canister migrate-id migrated --replace replaced --check <<< "no"
+    assert_contains "Canister 'migrated' is running. Fix: Stop canister 'migrated'."
+    assert_contains "Canister 'replaced' is running. Fix: Stop canister 'replaced'."
+    assert_contains "but the migration requires at least 10.000 TC"
+    assert_contains "is not a controller of canister 'migrated'"
+    assert_contains "is not a controller of canister 'replaced'"
+
+    dfx ledger fabricate-cycles --canister migrated --cycles 10000000000000
+
+    assert_command dfx canister migrate-id migrated --replace replaced --check --yes
+    assert_contains "All pre-flight checks passed."
+    assert_contains "Canister 'migrated' is ready to replace canister 'replaced'."
+
+    assert_command dfx canister status migrated
+    assert_contains "Status: Stopped"
+    assert_contains "sbzkb-zqaaa-aaaaa-aaaiq-cai"
+    assert_command dfx canister status replaced
+    assert_contains "Status: Stopped"
 }
@@ -43,3 +43,32 @@ dfx_canister_status_grep_balance() {
   assert_not_contains "Memory allocation: "
   assert_contains "Balance: "
 }
+
+@test "canister settings diff reports and applies drift from dfx.json" {
+  dfx_start
+  assert_command dfx deploy e2e_project_backend --no-wallet
+
+  assert_command dfx canister settings diff e2e_project_backend
+  assert_contains "Settings of canister 'e2e_project_backend' match dfx.json."
+
+  jq '.canisters.e2e_project_backend.initialization_values={
+    "freezing_threshold": "7days",
+    "log_visibility": "public"
+  }' dfx.json | sponge dfx.json
+
+  assert_command dfx canister settings diff --all
+  assert_contains "Settings of canister 'e2e_project_backend' differ from dfx.json:"
+  assert_contains "Freezing threshold: deployed 2_592_000 Seconds, dfx.json 604_800 Seconds"
+  assert_contains "Log visibility: deployed controllers, dfx.json public"
+  assert_contains "Canister 'e2e_project_frontend' is not created."
+
+  assert_command dfx canister settings diff e2e_project_backend --apply
+  assert_contains "Updated settings of canister 'e2e_project_backend' to match dfx.json."
+
+  assert_command dfx canister status e2e_project_backend
+  assert_contains "Freezing threshold: 604_800"
+  assert_contains "Log visibility: public"
+
+  assert_command dfx canister settings diff e2e_project_backend
+  assert_contains "Settings of canister 'e2e_project_backend' match dfx.json."
+}
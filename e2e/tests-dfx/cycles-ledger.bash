@@ -512,6 +512,43 @@ current_time_nanoseconds() {
   assert_eq "35197700000000 cycles."
 }
 
+@test "auto top-up" {
+  dfx_start --system-canisters
+
+  dfx_new
+
+  assert_command dfx canister create --all
+
+  # Get some cycles (converted from ICP) for the default identity
+  assert_command dfx --identity anonymous ledger transfer --memo 1234 --amount 100 "$(dfx ledger account-id --of-principal "$(dfx identity get-principal)")"
+  assert_command dfx cycles convert --amount 10
+
+  assert_command_fail dfx cycles auto-top-up e2e_project_backend
+  assert_contains "Canister 'e2e_project_backend' has no auto_top_up configuration in dfx.json." "$stderr"
+
+  jq '.canisters.e2e_project_backend.auto_top_up={"min": 5000000000000, "target": 6000000000000}' dfx.json | sponge dfx.json
+
+  assert_command dfx cycles auto-top-up --all --dry-run
+  assert_contains "Would top up canister 'e2e_project_backend'"
+  assert_not_contains "e2e_project_frontend"
+
+  # spend cap limits the top-up
+  assert_command dfx cycles auto-top-up --all --max-spend 1T
+  assert_contains "Topped up canister 'e2e_project_backend'"
+  assert_contains "Spent 1.000 TC in total, including fees."
+  assert_contains "Spend cap reached" "$stderr"
+
+  assert_command dfx cycles auto-top-up --all
+  assert_contains "Topped up canister 'e2e_project_backend'"
+  assert_command dfx canister status e2e_project_backend
+  assert_contains "Balance: 5_9"
+
+  # balance is above the minimum now
+  assert_command dfx cycles auto-top-up --all
+  assert_contains "no top-up needed" "$stderr"
+  assert_contains "Spent 0"
+}
+
 @test "canister creation" {
   dfx_start --system-canisters
   # shellcheck disable=SC2030,SC2031
@@ -792,3 +829,30 @@ current_time_nanoseconds() {
   assert_contains "$SUBNET1" "$output"
   stop_and_delete e2e_project_backend
 }
+
+@test "forecast cycles runway" {
+  dfx_start
+
+  dfx_new
+
+  assert_command dfx canister create --all --no-wallet
+
+  assert_command dfx cycles forecast e2e_project_backend
+  assert_contains "Canister 'e2e_project_backend'"
+  assert_contains "Daily burn:"
+  assert_contains "idle burn only"
+  assert_contains "Time until frozen:"
+  assert_file_exists .dfx/local/cycles_samples.json
+
+  # A runway long enough to exceed any balance produces a top-up plan
+  assert_command dfx cycles forecast --all --runway 36500000d
+  assert_contains "Top-up plan:"
+  assert_contains "dfx cycles top-up e2e_project_backend"
+  assert_contains "--network local"
+
+  # canisters that are not created are skipped
+  jq '.canisters.uncreated=.canisters.e2e_project_backend' dfx.json | sponge dfx.json
+  assert_command dfx cycles forecast --all
+  assert_contains "Skipping canister 'uncreated' because it is not created." "$stderr"
+  assert_contains "Canister 'e2e_project_backend'" "$stdout"
+}
@@ -200,6 +200,129 @@ Failed to download from url: http://httpbin.org/status/404." "$output"
   assert_contains "Failed to download from url:" "$output"
 }
 
+@test "dfx deps pull --offline uses the canisters vendored by dfx deps vendor" {
+  use_test_specific_cache_root # dfx deps pull will download files to cache
+
+  PULLED_DIR="$DFX_CACHE_ROOT/.cache/dfinity/pulled/"
+
+  dfx_start
+  setup_onchain
+
+  cd app
+  assert_command_fail dfx deps pull --offline
+  assert_contains "deps/vendor does not exist. Run \`dfx deps vendor\` while online to create it." "$stderr"
+
+  assert_command dfx deps pull --network local
+  assert_command dfx deps vendor
+  assert_file_exists "deps/vendor/pulled.json"
+  assert_file_exists "deps/vendor/$CANISTER_ID_A/canister.wasm"
+  assert_file_exists "deps/vendor/$CANISTER_ID_B/canister.wasm.gz"
+  assert_file_exists "deps/vendor/$CANISTER_ID_C/service.did"
+  cp deps/pulled.json pulled.json.online
+
+  # canisters that are no longer pulled are removed from the mirror
+  mkdir -p deps/vendor/aaaaa-aa
+  echo "stale" > deps/vendor/aaaaa-aa/service.did
+  assert_command dfx deps vendor
+  assert_file_not_exists deps/vendor/aaaaa-aa/service.did
+
+  # neither the replica nor the web server is needed anymore
+  dfx_stop
+  stop_webserver
+  rm -r "${PULLED_DIR:?}/"
+  rm deps/pulled.json
+
+  assert_command dfx deps pull --offline
+  assert_contains "Pulling canister $CANISTER_ID_A from the mirror..." "$stderr"
+  assert_file_exists "$PULLED_DIR/$CANISTER_ID_B/canister.wasm.gz"
+  assert_file_exists "$PULLED_DIR/$CANISTER_ID_C/service.did"
+  assert_command diff pulled.json.online deps/pulled.json
+
+  mv deps/vendor ../mirror
+  assert_command dfx deps pull --mirror ../mirror
+
+  # the wasm in the mirror must match its hash in the project's pulled.json,
+  # even if the mirror's pulled.json was changed to match it
+  cp ../mirror/"$CANISTER_ID_C"/canister.wasm ../mirror/"$CANISTER_ID_A"/canister.wasm
+  TAMPERED_HASH="$(sha256sum ../mirror/"$CANISTER_ID_A"/canister.wasm | cut -d " " -f 1)"
+  jq '.canisters."'"$CANISTER_ID_A"'".wasm_hash_download="'"$TAMPERED_HASH"'"' ../mirror/pulled.json | sponge ../mirror/pulled.json
+  assert_command_fail dfx deps pull --mirror ../mirror
+  assert_contains "The wasm of $CANISTER_ID_A at" "$stderr"
+  assert_contains "but the project's pulled.json expects" "$stderr"
+
+  # without a pulled.json in the project, the wasm must match its hash in the mirror's pulled.json
+  rm deps/pulled.json
+  cp pulled.json.online ../mirror/pulled.json
+  assert_command_fail dfx deps pull --mirror ../mirror
+  assert_contains "but the mirror's pulled.json expects" "$stderr"
+}
+
+@test "dfx deps outdated and dfx deps update pull the new version of a dependency" {
+  use_test_specific_cache_root # dfx deps pull will download files to cache
+
+  dfx_start
+  setup_onchain
+
+  cd app
+  assert_command dfx deps pull --network local
+  assert_command dfx deps init
+  assert_command dfx deps init "$CANISTER_ID_A" --argument 11
+
+  assert_command dfx deps outdated --network local
+  assert_contains "$CANISTER_ID_C (dep_c): up to date"
+  assert_not_contains "outdated"
+
+  # upgrade canister c with a new method
+  cd ../onchain
+  cat > src/c.mo <<EOF
+import a "canister:a";
+
+persistent actor class c(num : ?Nat) {
+    stable var NUM : Nat = switch (num) {
+        case (?n) { n };
+        case (null) { 3 };
+    };
+
+    public query func name() : async Text {
+        return "c";
+    };
+
+    public query func get() : async Nat {
+        return NUM;
+    };
+
+    public func times_a() : async Nat {
+        let res = NUM * (await a.get());
+        return res;
+    };
+};
+EOF
+  dfx build c
+  dfx canister install c --mode upgrade --argument "(opt 3)"
+  cp .dfx/local/canisters/c/c.wasm ../www/c.wasm
+  WASM_HASH_C="$(sha256sum .dfx/local/canisters/c/c.wasm | cut -d " " -f 1)"
+
+  cd ../app
+  assert_command dfx deps outdated --network local
+  assert_contains "$CANISTER_ID_C (dep_c): outdated"
+  assert_contains "current wasm_hash: $WASM_HASH_C"
+  assert_contains "$CANISTER_ID_A: up to date"
+  assert_contains "1 pulled canister(s) have a newer version." "$stderr"
+
+  assert_command dfx deps update dep_c --network local
+  assert_contains "Updated canister $CANISTER_ID_C (dep_c)" "$stderr"
+  assert_contains "+  name: () -> (text) query;"
+  assert_not_contains "is not compatible" "$stderr"
+  assert_command jq -r '.canisters."'"$CANISTER_ID_C"'".wasm_hash' deps/pulled.json
+  assert_eq "$WASM_HASH_C"
+  assert_command jq -r '.canisters."'"$CANISTER_ID_C"'".name' deps/pulled.json
+  assert_eq "dep_c"
+  assert_command grep "name" "deps/candid/$CANISTER_ID_C.did"
+
+  assert_command dfx deps outdated --network local
+  assert_not_contains "outdated"
+}
+
 @test "dfx deps pull works when wasm_hash or wasm_hash_url specified" {
   use_test_specific_cache_root # dfx deps pull will download files to cache
 
@@ -309,6 +432,16 @@ $CANISTER_ID_A" "$output"
   echo "(opt 44)" > arg.txt
   assert_command dfx deps init dep_c --argument-file arg.txt
 
+  # JSON arguments are converted using the Candid types of the init argument
+  assert_command dfx deps init dep_c --argument '"55"' --argument-type json
+  assert_command jq -r '.canisters."'"$CANISTER_ID_C"'".arg_str' deps/init.json
+  assert_contains "opt"
+  assert_contains "55"
+  echo "66" > arg.json
+  assert_command dfx deps init dep_c --argument-file arg.json
+  assert_command jq -r '.canisters."'"$CANISTER_ID_C"'".arg_str' deps/init.json
+  assert_contains "66"
+
   # The argument is the hex string of '("abc")' which doesn't type check
   # However, passing raw argument will bypass the type check so following command succeed
   assert_command dfx deps init "$CANISTER_ID_A" --argument "4449444c00017103616263" --argument-type raw
@@ -644,6 +644,83 @@ EOF
   assert_command dfx extension install "$EXTENSION_URL"
 }
 
+@test "install verifies the extension archive and pins it in the lock file" {
+  start_webserver --directory www
+  EXTENSION_URL="http://localhost:$E2E_WEB_SERVER_PORT/arbitrary/extension.json"
+  mkdir -p  www/arbitrary/downloads/an-extension-v0.1.0
+
+  cat > www/arbitrary/extension.json <<EOF
+{
+  "name": "an-extension",
+  "version": "0.1.0",
+  "homepage": "https://github.com/dfinity/dfx-extensions",
+  "authors": "DFINITY",
+  "summary": "Test extension for e2e purposes.",
+  "categories": [],
+  "keywords": [],
+  "download_url_template": "http://localhost:$E2E_WEB_SERVER_PORT/arbitrary/downloads/{{tag}}/{{basename}}.{{archive-format}}"
+}
+EOF
+  cat > www/arbitrary/an-extension <<EOF
+#!/usr/bin/env bash
+
+echo "an extension output"
+EOF
+  chmod +x www/arbitrary/an-extension
+
+  arch=$(get_extension_architecture)
+
+  if [ "$(uname)" == "Darwin" ]; then
+    ARCHIVE_BASENAME="an-extension-$arch-apple-darwin"
+  else
+    ARCHIVE_BASENAME="an-extension-$arch-unknown-linux-gnu"
+  fi
+
+  mkdir "$ARCHIVE_BASENAME"
+  cp www/arbitrary/extension.json "$ARCHIVE_BASENAME"
+  cp www/arbitrary/an-extension "$ARCHIVE_BASENAME"
+  tar -czf "$ARCHIVE_BASENAME".tar.gz "$ARCHIVE_BASENAME"
+  mv "$ARCHIVE_BASENAME".tar.gz www/arbitrary/downloads/an-extension-v0.1.0/
+  SHA256=$(shasum -a 256 www/arbitrary/downloads/an-extension-v0.1.0/"$ARCHIVE_BASENAME".tar.gz | cut -d ' ' -f 1)
+
+  cat > www/arbitrary/dependencies.json <<EOF
+{
+  "0.1.0": {
+    "archives": {
+      "$ARCHIVE_BASENAME.tar.gz": { "sha256": "0000000000000000000000000000000000000000000000000000000000000000" }
+    },
+    "dfx": {
+      "version": ">=0.8.0"
+    }
+  }
+}
+EOF
+  echo '{"canisters":{}}' > dfx.json
+
+  assert_command_fail dfx extension install "$EXTENSION_URL"
+  assert_contains "but dependencies.json expects 0000000000000000000000000000000000000000000000000000000000000000" "$stderr"
+  assert_command_fail dfx extension run an-extension
+
+  jq '."0.1.0".archives[].sha256="'"$SHA256"'"' www/arbitrary/dependencies.json | sponge www/arbitrary/dependencies.json
+  assert_command dfx extension install "$EXTENSION_URL"
+  assert_command jq -r '.extensions."an-extension".archives."'"$ARCHIVE_BASENAME"'.tar.gz"' extensions.lock.json
+  assert_eq "$SHA256"
+
+  # a different archive is rejected by the lock file, even if dependencies.json is updated too
+  assert_command dfx extension uninstall an-extension
+  echo 'echo "changed output"' >> "$ARCHIVE_BASENAME"/an-extension
+  tar -czf www/arbitrary/downloads/an-extension-v0.1.0/"$ARCHIVE_BASENAME".tar.gz "$ARCHIVE_BASENAME"
+  CHANGED_SHA256=$(shasum -a 256 www/arbitrary/downloads/an-extension-v0.1.0/"$ARCHIVE_BASENAME".tar.gz | cut -d ' ' -f 1)
+  jq '."0.1.0".archives[].sha256="'"$CHANGED_SHA256"'"' www/arbitrary/dependencies.json | sponge www/arbitrary/dependencies.json
+  assert_command_fail dfx extension install "$EXTENSION_URL"
+  assert_contains "but extensions.lock.json expects $SHA256" "$stderr"
+
+  # without a readable dependencies.json, an extension pinned in the lock file is not installed
+  echo "not json" > www/arbitrary/dependencies.json
+  assert_command_fail dfx extension install "$EXTENSION_URL"
+  assert_command_fail dfx extension run an-extension
+}
+
 @test "install extension with non-platform-specific archive" {
   start_webserver --directory www
   EXTENSION_URL="http://localhost:$E2E_WEB_SERVER_PORT/arbitrary/extension.json"
@@ -718,7 +795,129 @@ EOF
   # shellcheck disable=SC2154
   assert_contains "ERROR: Extension 'nns' is already installed at version 0.3.1" "$stderr"
   # shellcheck disable=SC2154
-  assert_contains 'ERROR: To upgrade, run "dfx extension uninstall nns" and then re-run the dfx extension install command' "$stderr"
+  assert_contains 'ERROR: To install another version, run "dfx extension update nns --version <version>"' "$stderr"
+}
+
+@test "update replaces the installed version" {
+  assert_command dfx extension install nns --version 0.3.1
+  assert_command dfx extension update nns --version 0.4.1
+  assert_contains "Extension 'nns' updated from version 0.3.1 to version 0.4.1" "$stderr"
+  assert_command dfx extension update nns --version 0.4.1
+  assert_contains "Extension 'nns' version 0.4.1 is up to date" "$stderr"
+  assert_command dfx nns --help
+}
+
+@test "update --all updates the other extensions if one fails" {
+  assert_command dfx extension install nns --version 0.3.1
+  CACHE_DIR=$(dfx cache show)
+  mkdir -p "$CACHE_DIR"/extensions/not_in_catalog
+  cat > "$CACHE_DIR"/extensions/not_in_catalog/extension.json <<EOF
+  {
+    "name": "not_in_catalog",
+    "version": "0.1.0",
+    "homepage": "https://github.com/dfinity/dfx-extensions",
+    "authors": "DFINITY",
+    "summary": "Test extension for e2e purposes.",
+    "categories": [],
+    "keywords": []
+  }
+EOF
+
+  assert_command_fail dfx extension update --all
+  assert_contains "Failed to update extension 'not_in_catalog'" "$stderr"
+  assert_contains "Extension 'nns' updated from version 0.3.1" "$stderr"
+  assert_contains "Failed to update extensions: not_in_catalog" "$stderr"
+}
+
+@test "update reports an extension that is not installed" {
+  assert_command_fail dfx extension update nns
+  assert_contains "Extension 'nns' is not installed." "$stderr"
+}
+
+@test "extensions declared in dfx.json are installed on first use" {
+  dfx_new
+  jq '.extensions.nns.version="0.4.1"' dfx.json | sponge dfx.json
+
+  # commands that don't use project extensions don't install them
+  assert_command dfx identity whoami
+  assert_not_contains "Installed extension 'nns'" "$stderr"
+  assert_command dfx extension list
+  assert_match 'No extensions installed' "$output"
+
+  assert_command dfx nns import --help
+  assert_contains "Installed extension 'nns' version 0.4.1" "$stderr"
+  assert_command jq -r '.extensions.nns.version' extensions.lock.json
+  assert_eq "0.4.1"
+
+  jq '.extensions.nns.version="0.3.1"' dfx.json | sponge dfx.json
+  assert_command dfx build --check
+  assert_contains "Extension 'nns' version 0.4.1 is installed, but dfx.json requires version 0.3.1" "$stderr"
+  assert_command dfx extension update nns
+  assert_contains "Extension 'nns' updated from version 0.4.1 to version 0.3.1" "$stderr"
+  assert_command jq -r '.extensions.nns.version' extensions.lock.json
+  assert_eq "0.3.1"
+}
+
+@test "extension hooks run during build and deploy" {
+  CACHE_DIR=$(dfx cache show)
+  mkdir -p "$CACHE_DIR"/extensions/hooks
+  cat > "$CACHE_DIR"/extensions/hooks/hooks <<'EOF'
+#!/usr/bin/env bash
+mkdir -p hooks
+cat > "hooks/$DFX_HOOK-${DFX_CANISTER_NAME:-all}.json"
+echo "$DFX_WASM_PATH" > "hooks/$DFX_HOOK-${DFX_CANISTER_NAME:-all}.wasm_path"
+echo "output of the $DFX_HOOK hook"
+EOF
+  chmod +x "$CACHE_DIR"/extensions/hooks/hooks
+  cat > "$CACHE_DIR"/extensions/hooks/extension.json <<EOF
+{
+  "name": "hooks",
+  "version": "0.1.0",
+  "homepage": "https://github.com/dfinity/dfx-extensions",
+  "authors": "DFINITY",
+  "summary": "Test extension for e2e purposes.",
+  "categories": [],
+  "keywords": [],
+  "hooks": {
+    "pre-build": { "args": ["hook"] },
+    "post-build": { "args": ["hook"] },
+    "pre-install": { "args": ["hook"] },
+    "post-deploy": { "args": ["hook"] }
+  }
+}
+EOF
+
+  dfx_new hello
+  dfx_start
+
+  # hooks only run for extensions declared in dfx.json
+  assert_command dfx deploy hello_backend
+  assert_file_not_exists hooks
+
+  jq '.extensions.hooks.version="0.1.0"' dfx.json | sponge dfx.json
+  assert_command dfx deploy hello_backend
+  assert_contains "Running the post-build hook of extension 'hooks'." "$stderr"
+  # the output of hooks goes to stderr
+  assert_contains "output of the post-build hook" "$stderr"
+  assert_not_contains "output of the post-build hook" "$stdout"
+
+  assert_command jq -r '.hook + " " + .network + " " + .canister.name + " " + .canister.type' hooks/pre-build-hello_backend.json
+  assert_eq "pre-build local hello_backend motoko"
+  assert_command jq -r '.canister.wasm_path' hooks/pre-build-hello_backend.json
+  assert_eq "null"
+  assert_command jq -r '.canister.wasm_path' hooks/post-build-hello_backend.json
+  assert_contains ".dfx/local/canisters/hello_backend/hello_backend.wasm"
+  assert_command cat hooks/post-build-hello_backend.wasm_path
+  assert_contains ".dfx/local/canisters/hello_backend/hello_backend.wasm"
+  assert_command jq -r '.canister.id' hooks/pre-install-hello_backend.json
+  assert_eq "$(dfx canister id hello_backend)"
+  assert_command jq -r '.canisters[].name' hooks/post-deploy-all.json
+  assert_eq "hello_backend"
+
+  # a failing hook fails the build
+  echo 'exit 3' >> "$CACHE_DIR"/extensions/hooks/hooks
+  assert_command_fail dfx build hello_backend
+  assert_contains "The pre-build hook of extension 'hooks' failed" "$stderr"
 }
 
 @test "manually create extension" {
@@ -216,3 +216,128 @@ teardown() {
   assert_command dfx identity get-principal
   assert_eq "2nor3-keehi-duuup-d7jcn-onggn-3atzm-gejtl-5tlzn-k4g6c-nnbf7-7qe"
 }
+
+@test "can call a canister using an external signer identity" {
+  install_asset ed25519
+  install_asset external_signer
+  signer_dir="$(pwd)"
+  export STUB_SIGNER_LOG="$signer_dir/signer.log"
+  assert_command dfx identity new external --external-signer "$signer_dir/stub-signer.bash" --external-signer-arg "$signer_dir/identity.pem"
+  assert_command jq -r .external.command "$DFX_CONFIG_ROOT/.config/dfx/identity/external/identity.json"
+  assert_eq "$signer_dir/stub-signer.bash"
+  assert_command dfx identity get-principal --identity external
+  assert_eq "2nor3-keehi-duuup-d7jcn-onggn-3atzm-gejtl-5tlzn-k4g6c-nnbf7-7qe"
+  assert_command_fail dfx identity export external
+  assert_contains "uses an external signer and has no PEM file" "$stderr"
+
+  dfx_new # This installs replica and other binaries
+  install_asset whoami
+  dfx_start
+  dfx canister create whoami
+  dfx build
+  dfx canister install whoami
+  assert_command dfx canister call whoami whoami --identity external
+  assert_eq '(principal "2nor3-keehi-duuup-d7jcn-onggn-3atzm-gejtl-5tlzn-k4g6c-nnbf7-7qe")'
+  assert_command jq -se 'map(select(.operation == "sign" and .identity == "external")) | length > 0' "$STUB_SIGNER_LOG"
+
+  # failures of the signer are reported
+  assert_command dfx identity new broken --external-signer "$signer_dir/stub-signer.bash" --external-signer-arg "$signer_dir/missing.pem"
+  assert_command_fail dfx identity get-principal --identity broken
+  assert_contains "Failed to instantiate external identity 'broken'" "$stderr"
+  assert_contains "External signer '$signer_dir/stub-signer.bash' failed" "$stderr"
+}
+
+@test "can call a canister using a delegated identity" {
+  install_asset ed25519
+  session_pem="$(pwd)/identity.pem"
+  dfx_new # This installs replica and other binaries
+  install_asset whoami
+  dfx_start
+  dfx canister create whoami
+  dfx build
+  dfx canister install whoami
+  root="$(dfx identity get-principal)"
+
+  assert_command dfx identity delegate session --to new --expires 8h --targets whoami --storage-mode plaintext
+  assert_contains 'Created identity "session", delegated by "default"' "$stderr"
+  assert_command jq -e '.delegation.delegations | length == 1' "$DFX_CONFIG_ROOT/.config/dfx/identity/session/identity.json"
+  assert_command dfx identity get-principal --identity session
+  assert_eq "$root"
+  assert_command dfx canister call whoami whoami --identity session
+  assert_eq "(principal \"$root\")"
+  assert_command dfx identity whoami --identity session
+  assert_eq "session" "$stdout"
+  assert_contains "Delegated by $root, expires at" "$stderr"
+
+  # the targets restrict the canisters that can be called
+  assert_command dfx identity delegate restricted --to new --expires 8h --targets ryjl3-tyaaa-aaaaa-aaaba-cai --storage-mode plaintext
+  assert_command_fail dfx canister call whoami whoami --identity restricted
+
+  # a delegation to an existing key is written out and imported with the key
+  public_key="$(openssl pkey -in "$session_pem" -pubout -outform DER | xxd -p -c 1000)"
+  assert_command dfx identity delegate --to "$public_key" --expires 1h --outfile chain.json
+  assert_command jq -r .publicKey chain.json
+  assert_command dfx identity import teammate "$session_pem" --delegation-chain chain.json --storage-mode plaintext
+  assert_command dfx canister call whoami whoami --identity teammate
+  assert_eq "(principal \"$root\")"
+  dfx identity new other --storage-mode plaintext
+  dfx identity export other > other.pem
+  assert_command_fail dfx identity import mismatch other.pem --delegation-chain chain.json --storage-mode plaintext
+  assert_contains "The delegation chain does not delegate to the key of the identity." "$stderr"
+
+  # expired delegations cannot be used
+  assert_command dfx identity delegate short --to new --expires 1s --storage-mode plaintext
+  sleep 2
+  assert_command_fail dfx identity get-principal --identity short
+  assert_contains "The delegation of identity 'short' expired at" "$stderr"
+  assert_command dfx identity whoami --identity short
+  assert_contains "expired at" "$stderr"
+}
+
+@test "a project can select the identity per network" {
+  dfx_new # This installs replica and other binaries
+  install_asset whoami
+  dfx identity new alice --storage-mode plaintext
+  dfx identity new bob --storage-mode plaintext
+  alice="$(dfx identity get-principal --identity alice)"
+  bob="$(dfx identity get-principal --identity bob)"
+  jq '.identities.local="alice"' dfx.json | sponge dfx.json
+  dfx_start
+  dfx canister create whoami
+  dfx build
+  dfx canister install whoami
+
+  assert_command dfx canister call whoami whoami
+  assert_eq "(principal \"$alice\")" "$stdout"
+  assert_contains "Using identity 'alice' for network 'local' as configured by the project, instead of the global identity 'default'." "$stderr"
+  assert_command dfx identity whoami
+  assert_eq "alice" "$stdout"
+
+  # --identity and DFX_IDENTITY take precedence
+  assert_command dfx canister call whoami whoami --identity bob
+  assert_eq "(principal \"$bob\")" "$stdout"
+  assert_not_contains "as configured by the project" "$stderr"
+  assert_command env DFX_IDENTITY=bob dfx canister call whoami whoami
+  assert_eq "(principal \"$bob\")" "$stdout"
+
+  # .dfx-identity takes precedence over dfx.json
+  echo "local = bob" > .dfx-identity
+  assert_command dfx canister call whoami whoami
+  assert_eq "(principal \"$bob\")" "$stdout"
+  dfx identity use bob
+  assert_command dfx canister call whoami whoami
+  assert_not_contains "as configured by the project" "$stderr"
+  dfx identity use default
+
+  # networks that the project does not configure use the global identity
+  jq 'del(.identities)' dfx.json | sponge dfx.json
+  printf '# deployment identities\nic = alice\n' > .dfx-identity
+  assert_command dfx identity whoami
+  assert_eq "default" "$stdout"
+  assert_command dfx identity whoami --network ic
+  assert_eq "alice" "$stdout"
+
+  echo "ic =" > .dfx-identity
+  assert_command_fail dfx identity whoami
+  assert_contains ".dfx-identity line 1 must be either '<network> = <identity>' or '<identity>'" "$stderr"
+}
@@ -570,3 +570,36 @@ EOF
   assert_command dfx identity get-principal --identity alice
   assert_eq "qimd7-lqrvx-kdvsm-7zeqn-bgoix-ukjfi-hgmfg-ur2he-odgb2-joms4-nae"
 }
+
+@test "identity: ephemeral identities are read from the environment, a file descriptor or stdin" {
+  install_asset ed25519
+  dfx identity get-principal # initialize the identity directory
+  identities_before="$(ls "$DFX_CONFIG_ROOT/.config/dfx/identity")"
+
+  assert_command env CI_IDENTITY_PEM="$(cat identity.pem)" dfx identity get-principal --identity env:CI_IDENTITY_PEM
+  assert_eq "2nor3-keehi-duuup-d7jcn-onggn-3atzm-gejtl-5tlzn-k4g6c-nnbf7-7qe"
+
+  assert_command bash -c 'DFX_IDENTITY=stdin: dfx identity get-principal < identity.pem'
+  assert_eq "2nor3-keehi-duuup-d7jcn-onggn-3atzm-gejtl-5tlzn-k4g6c-nnbf7-7qe"
+
+  assert_command bash -c 'dfx identity get-principal --identity fd:3 3< identity.pem'
+  assert_eq "2nor3-keehi-duuup-d7jcn-onggn-3atzm-gejtl-5tlzn-k4g6c-nnbf7-7qe"
+
+  seed="display dawn estate night naive stomach receive lock expose boring square boy deposit mistake volume soldier coil rocket match diamond repair opinion action paddle"
+  assert_command env CI_SEED="$seed" dfx identity get-principal --identity env:CI_SEED
+  assert_eq "qimd7-lqrvx-kdvsm-7zeqn-bgoix-ukjfi-hgmfg-ur2he-odgb2-joms4-nae"
+
+  assert_command_fail env CI_SEED="not a seed phrase" dfx identity get-principal --identity env:CI_SEED
+  assert_contains "Failed to load ephemeral identity 'env:CI_SEED'" "$stderr"
+  assert_command_fail dfx identity get-principal --identity env:UNSET_VARIABLE
+  assert_contains "Failed to read environment variable 'UNSET_VARIABLE'" "$stderr"
+
+  # nothing is stored
+  assert_command_fail dfx identity use env:CI_SEED
+  assert_eq "$identities_before" "$(ls "$DFX_CONFIG_ROOT/.config/dfx/identity")"
+
+  # reported as a distinct identity type in telemetry
+  log=$(DFX_TELEMETRY=local dfx info telemetry-log-path)
+  DFX_TELEMETRY=local CI_SEED="$seed" dfx cycles balance --ic --identity env:CI_SEED || true
+  assert_command jq -se 'last | .identity_type == "ephemeral"' "$log"
+}
@@ -116,6 +116,43 @@ current_time_nanoseconds() {
   assert_not_contains "Transfer sent at block height $block_height" "$stdout"
 }
 
+@test "ledger transfer --sign-only" {
+  dfx_start --system-canisters
+  prepare_accounts
+
+  dfx identity use alice
+  assert_command dfx ledger transfer --icp 1 --memo 1 "$BOB_ACCOUNT_ID" --sign-only transfer.json
+  assert_contains "Signed transfer message generated at [transfer.json]" "$stderr"
+  assert_contains "If you sign this operation again, use --created-at-time" "$stderr"
+  assert_command jq -r .method_name transfer.json
+  assert_eq "transfer"
+
+  # Signing only does not send the transfer.
+  assert_command dfx ledger balance
+  assert_eq "1000000.00000000 ICP"
+
+  assert_command_fail dfx ledger transfer --icp 1 --memo 1 "$BOB_ACCOUNT_ID" --sign-only transfer.json
+  assert_contains "already exists"
+
+  echo y | assert_command dfx canister send transfer.json
+  assert_command dfx ledger balance
+  assert_eq "999998.99990000 ICP"
+
+  # Sending the message again does not transfer twice.
+  echo y | assert_command dfx canister send transfer.json
+  assert_command dfx ledger balance
+  assert_eq "999998.99990000 ICP"
+
+  assert_command dfx ledger approve "$(dfx identity get-principal --identity bob)" --amount 10 --sign-only approve.json
+  assert_command jq -r .method_name approve.json
+  assert_eq "icrc2_approve"
+
+  assert_command dfx ledger top-up --icp 1 "$(dfx identity get-principal --identity bob)" --sign-only top-up.json
+  assert_contains "dfx ledger notify top-up <block height>" "$stderr"
+  assert_command jq -r .canister_id top-up.json
+  assert_eq "ryjl3-tyaaa-aaaaa-aaaba-cai"
+}
+
 @test "ledger icrc functions" {
   dfx_start --system-canisters
   prepare_accounts
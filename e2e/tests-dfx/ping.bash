@@ -86,3 +86,25 @@ teardown() {
   assert_command dfx ping "http://127.0.0.1:$webserver_port"
   assert_match "\"root_key\""
 }
+
+@test "dfx ping fails over to a healthy provider" {
+  dfx_start
+  webserver_port=$(get_webserver_port)
+  jq '.networks.multi.providers=["http://127.0.0.1:22113", "http://127.0.0.1:'"$webserver_port"'"]' dfx.json | sponge dfx.json
+
+  assert_command dfx ping multi
+  assert_match "\"root_key\""
+  assert_contains "http://127.0.0.1:22113: unreachable" "$stderr"
+  assert_contains "http://127.0.0.1:$webserver_port: healthy" "$stderr"
+
+  assert_command dfx info provider-health --network multi
+  assert_contains "http://127.0.0.1:22113: unreachable"
+  assert_contains "http://127.0.0.1:$webserver_port: healthy"
+
+  # Commands that don't call the network don't check the providers.
+  assert_command dfx info default-effective-canister-id --network multi
+  assert_not_contains "unreachable" "$stderr"
+
+  # Requests that fail to connect to the unreachable provider are retried with the other one.
+  assert_command dfx canister create --all --network multi
+}
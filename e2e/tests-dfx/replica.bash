@@ -0,0 +1,85 @@
+#!/usr/bin/env bats
+
+load ../utils/_
+
+setup() {
+  standard_setup
+
+  dfx_new hello
+}
+
+teardown() {
+  dfx_stop
+
+  standard_teardown
+}
+
+@test "replica time requires a running local network" {
+  assert_command_fail dfx replica time get
+  assert_contains "No running local PocketIC instance found for network 'local'." "$stderr"
+}
+
+@test "replica time can be frozen, set and advanced" {
+  dfx_start
+
+  assert_command dfx replica time freeze
+  assert_contains "Time is frozen."
+
+  assert_command dfx replica time set 2030-01-01T00:00:00Z
+  assert_contains "Current time: 2030-01-01T00:00:00"
+
+  assert_command dfx replica time advance 8h
+  assert_contains "Current time: 2030-01-01T08:00:00"
+
+  assert_command dfx replica time get
+  assert_contains "Current time: 2030-01-01T08:00:00"
+
+  assert_command_fail dfx replica time set 2029-01-01T00:00:00Z
+  assert_contains "which is before the current time" "$stderr"
+
+  assert_command dfx replica time resume
+  assert_contains "Time progresses with the wall clock."
+}
+
+@test "replica checkpoint save and restore" {
+  install_asset counter
+  dfx_start
+  dfx deploy hello_backend
+
+  assert_command dfx canister call hello_backend inc_read
+  assert_contains '(1 : nat)'
+
+  assert_command_fail dfx replica checkpoint save one
+  assert_contains "The local network is running. Stop it with \`dfx stop\` first." "$stderr"
+
+  dfx_stop
+  assert_command dfx replica checkpoint save one
+  assert_contains "Saved checkpoint 'one'."
+  assert_command_fail dfx replica checkpoint save one
+  assert_contains "Checkpoint 'one' already exists." "$stderr"
+
+  dfx_start
+  assert_command dfx canister call hello_backend inc_read
+  assert_contains '(2 : nat)'
+  dfx_stop
+
+  assert_command dfx replica checkpoint restore one
+  assert_contains "Restored checkpoint 'one'."
+  dfx_start
+  assert_command dfx canister call hello_backend inc_read
+  assert_contains '(2 : nat)'
+
+  dfx_stop
+
+  # checkpoints survive cleaning the state
+  dfx_start --clean
+  dfx_stop
+  assert_command dfx replica checkpoint restore one
+  dfx_start
+  assert_command dfx canister call hello_backend inc_read
+  assert_contains '(2 : nat)'
+
+  dfx_stop
+  assert_command_fail dfx replica checkpoint restore two
+  assert_contains "Checkpoint 'two' does not exist." "$stderr"
+}
@@ -30,6 +30,25 @@ teardown() {
 
 }
 
+@test "request-status output json" {
+  install_asset greet
+  dfx_start --artificial-delay 10000
+  dfx canister create hello_backend
+  dfx build hello_backend
+
+  dfx canister install hello_backend
+
+  assert_command dfx canister call --async hello_backend greet Bob
+  # shellcheck disable=SC2154
+  REQUEST_ID="$stdout"
+
+  assert_command dfx canister request-status --output json "$REQUEST_ID" "$(dfx canister id hello_backend)" --method greet
+  assert_eq '"Hello, Bob!"' "$stdout"
+
+  assert_command dfx canister request-status --output json "$REQUEST_ID" "$(dfx canister id hello_backend)"
+  assert_eq '"Hello, Bob!"' "$stdout"
+}
+
 @test "request-status requires same identity" {
   install_asset greet
   dfx_start --artificial-delay 10000
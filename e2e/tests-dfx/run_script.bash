@@ -0,0 +1,87 @@
+#!/usr/bin/env bats
+
+load ../utils/_
+
+setup() {
+  standard_setup
+
+  dfx_new hello
+}
+
+teardown() {
+  dfx_stop
+
+  standard_teardown
+}
+
+@test "run-script runs the steps of a scenario" {
+  install_asset counter
+  dfx_start
+  dfx deploy
+  dfx identity new alice --storage-mode plaintext
+
+  cat > scenario.yaml <<'SCENARIO'
+name: counter
+variables:
+  start: 5
+steps:
+  - canister: hello_backend
+    method: write
+    args: (${start})
+  - name: increment
+    canister: hello_backend
+    method: inc_read
+    expect: (6)
+    capture:
+      count: /0
+  - name: read
+    canister: hello_backend
+    method: read
+    assert:
+      /0: 6
+  - name: write as alice
+    canister: hello_backend
+    method: write
+    identity: alice
+    args: 10
+  - name: read the new value
+    canister: hello_backend
+    method: read
+    expect: (10)
+SCENARIO
+
+  assert_command dfx canister run-script scenario.yaml --junit report.xml
+  assert_contains "Running step 'hello_backend.write'." "$stderr"
+  assert_contains "All 5 steps passed." "$stderr"
+  assert_contains "(6 : nat)" "$stdout"
+  assert_file_exists report.xml
+  assert_command cat report.xml
+  assert_contains '<testsuite name="counter" tests="5" failures="0" skipped="0"' "$stdout"
+  assert_contains '<testcase name="write as alice" classname="counter"' "$stdout"
+}
+
+@test "run-script reports failed steps" {
+  install_asset counter
+  dfx_start
+  dfx deploy
+
+  cat > smoke.json <<'SCENARIO'
+{
+  "steps": [
+    { "name": "read", "canister": "hello_backend", "method": "read", "expect": "(1)" },
+    { "name": "inc", "canister": "hello_backend", "method": "inc" }
+  ]
+}
+SCENARIO
+
+  assert_command_fail dfx canister run-script smoke.json --junit report.xml --fail-fast
+  assert_contains "Step 'read' failed: Expected" "$stderr"
+  assert_contains "1 of 2 steps failed." "$stderr"
+  assert_command cat report.xml
+  assert_contains '<testsuite name="smoke" tests="2" failures="1" skipped="1"' "$stdout"
+
+  # without --fail-fast, the remaining steps still run
+  assert_command_fail dfx canister run-script smoke.json
+  assert_command dfx canister call hello_backend read
+  assert_eq "(1 : nat)" "$stdout"
+}
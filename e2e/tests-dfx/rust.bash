@@ -54,6 +54,65 @@ teardown() {
   assert_command dfx canister call e2e_project_backend greet dfinity
 }
 
+@test "rust canister can be built with a custom profile, features and cargo args" {
+  dfx_new_rust
+  cat >>Cargo.toml <<EOF
+
+[profile.canister-release]
+inherits = "release"
+opt-level = "z"
+EOF
+  jq '.canisters.e2e_project_backend.profile="canister-release"' dfx.json | sponge dfx.json
+  jq '.canisters.e2e_project_backend.cargo_args=["--verbose"]' dfx.json | sponge dfx.json
+  dfx_start
+  dfx canister create --all
+
+  assert_command dfx build e2e_project_backend
+  assert_contains "cargo build --target wasm32-unknown-unknown --profile canister-release -p e2e_project_backend --locked --verbose" "$stderr"
+  assert_file_exists target/wasm32-unknown-unknown/canister-release/e2e_project_backend.wasm
+
+  # per-network overrides replace the options configured for all networks
+  jq '.canisters.e2e_project_backend.network_overrides.local={"profile":"dev","features":["unknown-feature"]}' dfx.json | sponge dfx.json
+  assert_command_fail dfx build e2e_project_backend
+  assert_contains "cargo build --target wasm32-unknown-unknown --profile dev -p e2e_project_backend --locked --features unknown-feature --verbose" "$stderr"
+  assert_contains "unknown-feature" "$stderr"
+
+  jq 'del(.canisters.e2e_project_backend.network_overrides.local.features)' dfx.json | sponge dfx.json
+  assert_command dfx deploy e2e_project_backend
+  assert_file_exists target/wasm32-unknown-unknown/debug/e2e_project_backend.wasm
+  assert_command dfx canister call e2e_project_backend greet dfinity
+  assert_contains '("Hello, dfinity!")'
+}
+
+@test "rust canister can extract its candid interface from the built wasm" {
+  if ! command -v candid-extractor >/dev/null; then
+    skip "candid-extractor is not installed"
+  fi
+  dfx_new_rust
+  echo 'ic_cdk::export_candid!();' >>src/e2e_project_backend/src/lib.rs
+  jq '.canisters.e2e_project_backend.candid="auto"' dfx.json | sponge dfx.json
+  jq '.canisters.e2e_project_backend.candid_check="src/e2e_project_backend/e2e_project_backend.did"' dfx.json | sponge dfx.json
+  dfx_start
+  dfx canister create --all
+
+  assert_command dfx build e2e_project_backend
+  assert_contains "candid-extractor" "$stderr"
+  assert_file_exists .dfx/local/canisters/e2e_project_backend/e2e_project_backend.did
+  assert_command dfx canister install e2e_project_backend
+  assert_command dfx canister metadata e2e_project_backend candid:service
+  assert_contains '"greet": (text) -> (text) query;'
+
+  # the build fails if the checked-in candid file is out of date
+  cat >src/e2e_project_backend/e2e_project_backend.did <<EOF
+service : {
+    "greet": (text) -> (text) query;
+    "farewell": (text) -> (text) query;
+}
+EOF
+  assert_command_fail dfx build e2e_project_backend
+  assert_contains "differs from" "$stderr"
+}
+
 @test "rust canister fails to build with missing lockfile" {
   dfx_new_rust
   rm -f ./Cargo.lock
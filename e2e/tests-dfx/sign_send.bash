@@ -82,3 +82,26 @@ teardown() {
 
   rm "$TMP_NAME_FILE"
 }
+
+@test "sign --inspect decodes and verifies a signed message" {
+  install_asset greet
+  dfx_start
+  dfx deploy
+
+  assert_command dfx canister sign --query hello_backend greet '("Bob")'
+  assert_command dfx canister sign --inspect message.json
+  assert_contains "Call type:  query" "$stdout"
+  assert_contains "Sender:     $(dfx identity get-principal)" "$stdout"
+  assert_contains "Canister:   $(dfx canister id hello_backend)" "$stdout"
+  assert_contains "Method:     greet" "$stdout"
+  assert_contains "Signature:  valid" "$stdout"
+  assert_contains 'Argument:   ("Bob")' "$stdout"
+
+  # the signature is the end of the signed content
+  CONTENT="$(jq -r .content message.json)"
+  if [ "${CONTENT: -1}" = "0" ]; then LAST=1; else LAST=0; fi
+  jq --arg content "${CONTENT%?}$LAST" '.content = $content' message.json | sponge message.json
+  assert_command_fail dfx canister sign --inspect message.json
+  assert_contains "Signature:  INVALID: The signature does not match the public key." "$stdout"
+  assert_contains "The signature of the message is invalid." "$stderr"
+}
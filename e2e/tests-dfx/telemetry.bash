@@ -162,3 +162,35 @@ teardown() {
     dfx cycles balance --ic --identity anonymous
     assert_command jq -se 'last | .cycles_host == "cycles-ledger" and .identity_type == "anonymous"' "$log"
 }
+
+@test "telemetry records can be inspected, exported and cleared" {
+    dfx identity get-principal
+    assert_command dfx telemetry show
+    assert_contains '"command": "identity get-principal"'
+    assert_contains "Batches waiting to be sent:"
+    assert_command dfx telemetry export --outfile export.json
+    assert_command jq -e '.log | any(.command == "identity get-principal")' export.json
+    assert_command jq -e '.pending_batches == [] and .sent_batches == []' export.json
+    assert_command dfx telemetry status
+    assert_contains "Telemetry: local"
+    assert_contains "Next send time: never, telemetry is not sent"
+    assert_command dfx telemetry clear
+    assert_command dfx telemetry export
+    assert_command jq -e '.log | map(.command) == ["telemetry clear"]' <<< "$output"
+}
+
+@test "telemetry endpoint can be configured" {
+    assert_command dfx config telemetry-endpoint
+    assert_eq "https://sdk.dfinity.network/telemetry"
+    assert_command dfx config telemetry-endpoint https://telemetry.example.com/ingest
+    assert_command dfx config telemetry-endpoint
+    assert_eq "https://telemetry.example.com/ingest"
+    cfg=$(dfx info config-json-path)
+    assert_command jq -r .telemetry_endpoint "$cfg"
+    assert_eq "https://telemetry.example.com/ingest"
+    assert_command dfx telemetry status
+    assert_contains "Endpoint: https://telemetry.example.com/ingest"
+    assert_command dfx config telemetry-endpoint --unset
+    assert_command dfx config telemetry-endpoint
+    assert_eq "https://sdk.dfinity.network/telemetry"
+}
@@ -0,0 +1,69 @@
+#!/usr/bin/env bats
+
+load ../utils/_
+
+setup() {
+  standard_setup
+
+  dfx_new hello
+}
+
+teardown() {
+  dfx_stop
+
+  standard_teardown
+}
+
+@test "canister verify matches the deployed module" {
+  dfx_start
+  assert_command dfx deploy hello_backend
+  assert_command dfx canister verify hello_backend
+  assert_contains "Canister 'hello_backend' is verified: the deployed module matches the module built from the working tree."
+
+  # a metadata section that was not deployed makes the metadata step diverge
+  jq '.canisters.hello_backend.metadata=[{"name":"custom","content":"not deployed"}]' dfx.json | sponge dfx.json
+  WASM_HASH="$(sha256sum .dfx/local/canisters/hello_backend/hello_backend.wasm | cut -d " " -f 1)"
+  assert_command_fail dfx canister verify hello_backend
+  assert_contains "The code of the deployed module matches the rebuilt module, but the 'metadata' step diverged:" "$stderr"
+  assert_contains "Metadata section 'custom' is not in the deployed module." "$stderr"
+
+  # the rebuild does not touch the build artifacts of the project
+  assert_eq "$WASM_HASH" "$(sha256sum .dfx/local/canisters/hello_backend/hello_backend.wasm | cut -d " " -f 1)"
+}
+
+@test "canister verify reports metadata that is only in the deployed module" {
+  dfx_start
+  jq '.canisters.hello_backend.pullable={"wasm_url":"http://example.com/hello_backend.wasm","dependencies":[],"init_guide":"none"}' dfx.json | sponge dfx.json
+  assert_command dfx deploy hello_backend
+
+  jq 'del(.canisters.hello_backend.pullable)' dfx.json | sponge dfx.json
+  assert_command_fail dfx canister verify hello_backend
+  assert_contains "Metadata section 'dfx' is only in the deployed module." "$stderr"
+}
+
+@test "canister verify does not copy build artifacts or follow links" {
+  mkdir -p src/hello_backend/node_modules/pkg src/hello_backend/target
+  echo "ignored" > src/hello_backend/node_modules/pkg/index.js
+  ln -s "$(pwd)" src/hello_backend/loop
+
+  dfx_start
+  assert_command dfx deploy hello_backend
+  assert_command dfx canister verify hello_backend
+  assert_contains "Canister 'hello_backend' is verified"
+}
+
+@test "canister verify builds from a clean checkout of a git ref" {
+  git init --quiet
+  git add .
+  git -c user.name=test -c user.email=test@example.com commit --quiet -m "deployed version"
+
+  dfx_start
+  assert_command dfx deploy hello_backend
+
+  sed -i.bak 's/Hello, /Hi, /' src/hello_backend/main.mo
+  assert_command_fail dfx canister verify hello_backend
+  assert_contains "so the module compiled from the working tree already differs" "$stderr"
+
+  assert_command dfx canister verify hello_backend --git-ref HEAD
+  assert_contains "the deployed module matches the module built from git ref 'HEAD'"
+}
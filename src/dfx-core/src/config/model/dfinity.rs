@@ -4,7 +4,7 @@ use crate::config::directories::get_user_dfx_config_dir;
 use crate::config::model::bitcoin_adapter::BitcoinAdapterLogLevel;
 use crate::config::model::canister_http_adapter::HttpAdapterLogLevel;
 use crate::config::model::extension_canister_type::apply_extension_canister_types;
-use crate::error::config::{GetOutputEnvFileError, GetTempPathError};
+use crate::error::config::{GetOutputEnvFileError, GetProjectIdentityError, GetTempPathError};
 use crate::error::dfx_config::AddDependenciesError::CanisterCircularDependency;
 use crate::error::dfx_config::GetCanisterNamesWithDependenciesError::AddDependenciesFailed;
 use crate::error::dfx_config::GetComputeAllocationError::GetComputeAllocationFailed;
@@ -47,7 +47,7 @@ use crate::error::structured_file::StructuredFileError;
 use crate::error::structured_file::StructuredFileError::DeserializeJsonFileFailed;
 use crate::extension::manager::ExtensionManager;
 use crate::fs::create_dir_all;
-use crate::json::structure::{PossiblyStr, SerdeVec};
+use crate::json::structure::{PossiblyStr, SerdeVec, UrlWithJsonSchema, VersionWithJsonSchema};
 use crate::json::{load_json_file, save_json_file};
 use crate::util::ByteSchema;
 use byte_unit::Byte;
@@ -69,6 +69,10 @@ use super::network_descriptor::MOTOKO_PLAYGROUND_CANISTER_TIMEOUT_SECONDS;
 
 pub const CONFIG_FILE_NAME: &str = "dfx.json";
 
+/// A file next to dfx.json that selects the identities of the project, and takes precedence over dfx.json.
+/// Each line is either `<network> = <identity>`, or just `<identity>` for all other networks.
+pub const PROJECT_IDENTITY_FILE_NAME: &str = ".dfx-identity";
+
 pub const BUILTIN_CANISTER_TYPES: [&str; 5] = ["rust", "motoko", "assets", "custom", "pull"];
 
 const EMPTY_CONFIG_DEFAULTS: ConfigDefaults = ConfigDefaults {
@@ -210,6 +214,21 @@ pub struct Pullable {
     pub init_arg: Option<String>,
 }
 
+/// # Auto Top-Up Configuration
+/// When the canister's cycles balance is below `min`, `dfx cycles auto-top-up` tops it up to `target`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AutoTopUp {
+    /// # Minimum Balance
+    /// The canister is topped up when its cycles balance is below this amount.
+    #[schemars(with = "u128")]
+    pub min: u128,
+
+    /// # Target Balance
+    /// The cycles balance the canister is topped up to. Must not be lower than `min`.
+    #[schemars(with = "u128")]
+    pub target: u128,
+}
+
 pub type TechStackCategoryMap = BTreeMap<String, BTreeMap<String, String>>;
 
 /// # Tech Stack
@@ -327,6 +346,11 @@ pub struct ConfigCanistersCanister {
     /// Disabled by default.
     pub gzip: Option<bool>,
 
+    /// # Auto Top-Up
+    /// Defines the cycles balance that `dfx cycles auto-top-up` maintains for this canister.
+    #[serde(default)]
+    pub auto_top_up: Option<AutoTopUp>,
+
     /// # Specified Canister ID
     /// Attempts to create the canister with this Canister ID.
     /// This option only works with non-mainnet replica.
@@ -362,12 +386,49 @@ pub enum CanisterTypeProperties {
 
         /// # Candid File
         /// Path of this canister's candid interface declaration.
+        /// If set to `auto`, the interface is extracted from the built Wasm module with `candid-extractor`, which must be installed.
         candid: PathBuf,
 
+        /// # Checked-In Candid File
+        /// Only used if `candid` is `auto`.
+        /// If set, the build fails when the extracted interface differs from the one declared in this file.
+        candid_check: Option<PathBuf>,
+
         /// # `cargo-audit` check
         /// If set to true, does not run `cargo audit` before building.
         #[serde(default)]
         skip_cargo_audit: bool,
+
+        /// # Cargo Features
+        /// Cargo features to enable when building this canister.
+        #[serde(default)]
+        features: Vec<String>,
+
+        /// # No Default Features
+        /// If set to true, the default features of the package are not enabled.
+        #[serde(default)]
+        no_default_features: bool,
+
+        /// # Cargo Profile
+        /// Cargo profile to build with, e.g. a size-optimized profile defined in Cargo.toml.
+        /// If left unspecified, defaults to `release`.
+        profile: Option<String>,
+
+        /// # Compilation Target
+        /// The target to compile the canister for.
+        /// Canisters compiled for `wasm32-wasip1` are post-processed with `wasi2ic`, which must be installed.
+        #[serde(default)]
+        target: RustTarget,
+
+        /// # Extra Cargo Arguments
+        /// Additional arguments passed to `cargo build`.
+        #[serde(default)]
+        cargo_args: Vec<String>,
+
+        /// # Per-Network Build Options
+        /// Mappings from network names to build options that replace the ones above on that network.
+        #[serde(default)]
+        network_overrides: BTreeMap<String, RustBuildOptions>,
     },
     /// # Asset-Specific Properties
     Assets {
@@ -428,6 +489,54 @@ impl CanisterTypeProperties {
     }
 }
 
+/// The value of a Rust canister's `candid` field that enables extraction of the interface from the built Wasm module.
+pub const RUST_CANDID_AUTO: &str = "auto";
+
+/// # Rust Compilation Target
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum RustTarget {
+    #[default]
+    #[serde(rename = "wasm32-unknown-unknown")]
+    Wasm32UnknownUnknown,
+    #[serde(rename = "wasm32-wasip1")]
+    Wasm32Wasip1,
+}
+
+impl RustTarget {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Wasm32UnknownUnknown => "wasm32-unknown-unknown",
+            Self::Wasm32Wasip1 => "wasm32-wasip1",
+        }
+    }
+}
+
+/// # Rust Build Options
+/// Build options of a Rust canister for a specific network.
+/// Options that are left unspecified keep the value configured for all networks.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct RustBuildOptions {
+    /// # Cargo Features
+    /// Cargo features to enable when building this canister.
+    pub features: Option<Vec<String>>,
+
+    /// # No Default Features
+    /// If set to true, the default features of the package are not enabled.
+    pub no_default_features: Option<bool>,
+
+    /// # Cargo Profile
+    /// Cargo profile to build with.
+    pub profile: Option<String>,
+
+    /// # Compilation Target
+    /// The target to compile the canister for.
+    pub target: Option<RustTarget>,
+
+    /// # Extra Cargo Arguments
+    /// Additional arguments passed to `cargo build`.
+    pub cargo_args: Option<Vec<String>>,
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum CanisterLogVisibility {
@@ -886,6 +995,28 @@ pub struct ConfigInterface {
 
     /// If set, environment variables will be output to this file (without overwriting any user-defined variables, if the file already exists).
     pub output_env_file: Option<PathBuf>,
+
+    /// # Extensions
+    /// Mapping between the names of the extensions used by the project and the versions to use.
+    /// Missing extensions are installed by `dfx build`, `dfx deploy` and the commands of the extensions.
+    pub extensions: Option<BTreeMap<String, ConfigExtension>>,
+
+    /// # Identities
+    /// Mapping between network names and the identities that the project uses on them.
+    /// The `--identity` flag and the DFX_IDENTITY environment variable take precedence.
+    pub identities: Option<BTreeMap<String, String>>,
+}
+
+/// An extension used by the project.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigExtension {
+    /// # Version
+    /// The version of the extension that the project uses.
+    pub version: VersionWithJsonSchema,
+
+    /// # URL
+    /// URL of the extension's extension.json, for extensions that are not in the extension catalog.
+    pub url: Option<UrlWithJsonSchema>,
 }
 
 pub type TopLevelConfigNetworks = BTreeMap<String, ConfigNetwork>;
@@ -897,6 +1028,38 @@ pub struct NetworksConfigInterface {
 
 impl ConfigCanistersCanister {}
 
+fn parse_project_identity_file(
+    path: &Path,
+    content: &str,
+    network: &str,
+) -> Result<Option<String>, GetProjectIdentityError> {
+    let mut for_all_networks = None;
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (line_network, identity) = match line.split_once('=') {
+            Some((line_network, identity)) => (Some(line_network.trim()), identity.trim()),
+            None => (None, line),
+        };
+        if identity.is_empty() || line_network.is_some_and(str::is_empty) {
+            return Err(GetProjectIdentityError::MalformedProjectIdentityFile(
+                path.to_path_buf(),
+                index + 1,
+            ));
+        }
+        match line_network {
+            Some(line_network) if line_network == network => {
+                return Ok(Some(identity.to_string()));
+            }
+            Some(_) => {}
+            None => for_all_networks = Some(identity.to_string()),
+        }
+    }
+    Ok(for_all_networks)
+}
+
 pub fn to_socket_addr(s: &str) -> Result<SocketAddr, SocketAddrConversionError> {
     match s.to_socket_addrs() {
         Ok(mut a) => match a.next() {
@@ -1196,6 +1359,27 @@ impl Config {
         Config::from_slice(path.to_path_buf(), &content, extension_manager)
     }
 
+    /// Reads the extensions used by the project that contains `working_dir`, and returns them with the project root.
+    /// Only the `extensions` field of dfx.json is read, because the rest of it may depend on these extensions.
+    pub fn load_project_extensions(
+        working_dir: &Path,
+    ) -> Result<Option<(PathBuf, BTreeMap<String, ConfigExtension>)>, LoadDfxConfigError> {
+        #[derive(Deserialize)]
+        struct ExtensionsOnly {
+            extensions: Option<BTreeMap<String, ConfigExtension>>,
+        }
+
+        let Some(path) = Config::resolve_config_path(working_dir).map_err(ResolveConfigPath)?
+        else {
+            return Ok(None);
+        };
+        let content = crate::fs::read(&path)?;
+        let config: ExtensionsOnly = serde_json::from_slice(&content)
+            .map_err(|e| LoadDfxConfigError::DeserializeValueFailed(Box::new(path.clone()), e))?;
+        let project_root = path.parent().unwrap().to_path_buf();
+        Ok(Some((project_root, config.extensions.unwrap_or_default())))
+    }
+
     pub fn from_dir(
         working_dir: &Path,
         extension_manager: Option<&ExtensionManager>,
@@ -1294,6 +1478,27 @@ impl Config {
             .transpose()
     }
 
+    /// Returns the identity that the project uses on the network, if any.
+    /// The .dfx-identity file takes precedence over the `identities` field of dfx.json.
+    pub fn get_project_identity(
+        &self,
+        network: &str,
+    ) -> Result<Option<String>, GetProjectIdentityError> {
+        let path = self.get_project_root().join(PROJECT_IDENTITY_FILE_NAME);
+        if path.is_file() {
+            let content = crate::fs::read_to_string(&path)?;
+            if let Some(identity) = parse_project_identity_file(&path, &content, network)? {
+                return Ok(Some(identity));
+            }
+        }
+        Ok(self
+            .config
+            .identities
+            .as_ref()
+            .and_then(|identities| identities.get(network))
+            .cloned())
+    }
+
     pub fn save(&self) -> Result<(), StructuredFileError> {
         save_json_file(&self.path, &self.json)
     }
@@ -1331,6 +1536,13 @@ impl<'de> Visitor<'de> for PropertiesVisitor {
         let mut r#type = None;
         let mut id = None;
         let mut workspace = None;
+        let mut features = None;
+        let mut no_default_features = None;
+        let mut profile = None;
+        let mut target = None;
+        let mut cargo_args = None;
+        let mut network_overrides = None;
+        let mut candid_check = None;
         while let Some(key) = map.next_key::<String>()? {
             match &*key {
                 "package" => package = Some(map.next_value()?),
@@ -1343,6 +1555,13 @@ impl<'de> Visitor<'de> for PropertiesVisitor {
                 "id" => id = Some(map.next_value()?),
                 "workspace" => workspace = Some(map.next_value()?),
                 "skip_cargo_audit" => skip_cargo_audit = Some(map.next_value()?),
+                "features" => features = Some(map.next_value()?),
+                "no_default_features" => no_default_features = Some(map.next_value()?),
+                "profile" => profile = Some(map.next_value()?),
+                "target" => target = Some(map.next_value()?),
+                "cargo_args" => cargo_args = Some(map.next_value()?),
+                "network_overrides" => network_overrides = Some(map.next_value()?),
+                "candid_check" => candid_check = Some(map.next_value()?),
                 _ => continue,
             }
         }
@@ -1350,9 +1569,16 @@ impl<'de> Visitor<'de> for PropertiesVisitor {
             Some("motoko") | None => CanisterTypeProperties::Motoko,
             Some("rust") => CanisterTypeProperties::Rust {
                 candid: PathBuf::from(candid.ok_or_else(|| missing_field("candid"))?),
+                candid_check,
                 package: package.ok_or_else(|| missing_field("package"))?,
                 skip_cargo_audit: skip_cargo_audit.unwrap_or(false),
                 crate_name,
+                features: features.unwrap_or_default(),
+                no_default_features: no_default_features.unwrap_or(false),
+                profile,
+                target: target.unwrap_or_default(),
+                cargo_args: cargo_args.unwrap_or_default(),
+                network_overrides: network_overrides.unwrap_or_default(),
             },
             Some("assets") => CanisterTypeProperties::Assets {
                 source: source.ok_or_else(|| missing_field("source"))?,
@@ -1432,6 +1658,10 @@ pub struct ToolConfig {
 #[derive(Serialize, Deserialize, JsonSchema)]
 pub struct ToolConfigInterface {
     pub telemetry: TelemetryState,
+
+    /// The URL that telemetry is sent to. Defaults to the DFINITY endpoint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub telemetry_endpoint: Option<UrlWithJsonSchema>,
 }
 
 impl ToolConfig {
@@ -1458,6 +1688,7 @@ impl ToolConfig {
                 json: Default::default(),
                 tool_config: ToolConfigInterface {
                     telemetry: TelemetryState::On,
+                    telemetry_endpoint: None,
                 },
             };
             default.save()?;
@@ -1721,6 +1952,25 @@ mod tests {
         assert_eq!(None, memory_allocation);
     }
 
+    #[test]
+    fn project_identity_file_maps_networks() {
+        let path = Path::new(PROJECT_IDENTITY_FILE_NAME);
+        let content = "# deployment identities\ndefault\nstaging = ci-staging\nic=prod-hsm\n";
+        let identity = |network| parse_project_identity_file(path, content, network).unwrap();
+        assert_eq!(identity("ic").as_deref(), Some("prod-hsm"));
+        assert_eq!(identity("staging").as_deref(), Some("ci-staging"));
+        assert_eq!(identity("local").as_deref(), Some("default"));
+
+        assert_eq!(
+            parse_project_identity_file(path, "ic = prod-hsm", "local").unwrap(),
+            None
+        );
+        assert!(matches!(
+            parse_project_identity_file(path, "ic = prod-hsm\n= default", "local"),
+            Err(GetProjectIdentityError::MalformedProjectIdentityFile(_, 2))
+        ));
+    }
+
     #[test]
     fn tech_stack_category_deterministic_serialization() {
         let first = build_and_serialize();
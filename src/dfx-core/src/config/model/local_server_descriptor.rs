@@ -157,6 +157,21 @@ impl LocalServerDescriptor {
         self.data_dir_by_settings_digest().join("state")
     }
 
+    /// Checkpoints of the state, saved with `dfx replica checkpoint save`.
+    /// They are kept next to the data directory rather than in it, so that neither
+    /// `dfx start --clean` nor the removal of old state directories deletes them.
+    pub fn checkpoints_dir(&self) -> PathBuf {
+        let mut name = self
+            .data_directory
+            .file_name()
+            .unwrap_or_default()
+            .to_os_string();
+        name.push("-checkpoints");
+        self.data_directory
+            .with_file_name(name)
+            .join(self.settings_digest())
+    }
+
     /// The replicated state of the replica.
     pub fn replicated_state_dir(&self) -> PathBuf {
         self.state_dir().join("replicated_state")
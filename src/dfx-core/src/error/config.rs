@@ -1,6 +1,7 @@
 use crate::error::extension::LoadExtensionManifestError;
 use crate::error::fs::{
     CanonicalizePathError, CreateDirAllError, EnsureDirExistsError, NoParentPathError,
+    ReadToStringError,
 };
 use crate::error::get_user_home::GetUserHomeError;
 use crate::error::structured_file::StructuredFileError;
@@ -32,6 +33,15 @@ pub enum GetOutputEnvFileError {
     NoParentPath(#[from] NoParentPathError),
 }
 
+#[derive(Error, Debug)]
+pub enum GetProjectIdentityError {
+    #[error(transparent)]
+    ReadProjectIdentityFileFailed(#[from] ReadToStringError),
+
+    #[error("{} line {1} must be either '<network> = <identity>' or '<identity>'", .0.display())]
+    MalformedProjectIdentityFile(PathBuf, usize),
+}
+
 #[derive(Error, Debug)]
 pub enum GetTempPathError {
     #[error(transparent)]
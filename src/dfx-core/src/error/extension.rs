@@ -84,6 +84,34 @@ pub enum RunExtensionError {
     GetExtensionBinaryError(#[from] GetExtensionBinaryError),
 }
 
+#[derive(Error, Debug)]
+pub enum RunExtensionHookError {
+    #[error("Cannot find cache directory")]
+    FindCacheDirectoryFailed(#[from] EnsureCacheVersionsDirError),
+
+    #[error("Failed to serialize the hook input")]
+    SerializeInputFailed(#[from] serde_json::Error),
+
+    #[error("Failed to run extension '{0}'")]
+    FailedToLaunchExtension(String, #[source] std::io::Error),
+
+    #[error("Failed to pass the hook input to extension '{0}'")]
+    WriteInputFailed(String, #[source] std::io::Error),
+
+    #[error("Extension '{0}' never finished")]
+    ExtensionNeverFinishedExecuting(String, #[source] std::io::Error),
+
+    #[error("The {hook} hook of extension '{extension}' failed with {status}.")]
+    HookFailed {
+        extension: String,
+        hook: &'static str,
+        status: std::process::ExitStatus,
+    },
+
+    #[error(transparent)]
+    GetExtensionBinaryError(#[from] GetExtensionBinaryError),
+}
+
 #[derive(Error, Debug)]
 pub enum GetExtensionBinaryError {
     #[error("Extension '{0}' not installed.")]
@@ -148,6 +176,70 @@ pub enum InstallExtensionError {
 
     #[error(transparent)]
     LoadManifest(#[from] LoadExtensionManifestError),
+
+    #[error(transparent)]
+    GetDependencies(#[from] GetDependenciesError),
+
+    #[error(transparent)]
+    VerifyArchive(#[from] VerifyExtensionArchiveError),
+}
+
+#[derive(Error, Debug)]
+pub enum UpdateExtensionError {
+    #[error("extension '{0}' not found in catalog")]
+    ExtensionNotFound(String),
+
+    #[error("Cannot create temporary directory at '{0}'")]
+    CreateTemporaryDirectoryFailed(std::path::PathBuf, #[source] std::io::Error),
+
+    #[error(transparent)]
+    FetchCatalog(#[from] FetchCatalogError),
+
+    #[error(transparent)]
+    GetDependencies(#[from] GetDependenciesError),
+
+    #[error(transparent)]
+    GetHighestCompatibleVersion(#[from] GetHighestCompatibleVersionError),
+
+    #[error(transparent)]
+    InstallExtension(#[from] InstallExtensionError),
+
+    #[error(transparent)]
+    LoadManifest(#[from] LoadExtensionManifestError),
+
+    #[error(transparent)]
+    Rename(#[from] RenameError),
+}
+
+#[derive(Error, Debug)]
+pub enum VerifyExtensionArchiveError {
+    #[error(
+        "The sha256 of extension archive '{archive}' is {actual}, but {expected_by} expects {expected}."
+    )]
+    ChecksumMismatch {
+        archive: String,
+        actual: String,
+        expected: String,
+        expected_by: String,
+    },
+
+    #[error("Extension archive '{0}' is signed, but the extension manifest has no signing key.")]
+    MissingSigningKey(String),
+
+    #[error("Extension archive '{0}' is not signed, but the extension manifest has a signing key.")]
+    MissingSignature(String),
+
+    #[error("The signature of extension archive '{0}' is invalid.")]
+    InvalidSignature(String),
+
+    #[error(
+        "The signing key of extension '{name}' changed from {locked} to {manifest}. If this is expected, remove the extension from the extensions lock file."
+    )]
+    SigningKeyChanged {
+        name: String,
+        locked: String,
+        manifest: String,
+    },
 }
 
 #[derive(Error, Debug)]
@@ -13,8 +13,9 @@ use crate::error::{
     structured_file::StructuredFileError,
     wallet_config::{SaveWalletConfigError, WalletConfigError},
 };
+use candid::Principal;
 use candid::types::principal::PrincipalError;
-use ic_agent::identity::PemError;
+use ic_agent::identity::{DelegationError, PemError};
 use ic_identity_hsm::HardwareIdentityError;
 use std::path::PathBuf;
 use std::string::FromUtf8Error;
@@ -70,6 +71,9 @@ pub enum CreateNewIdentityError {
     #[error("Failed to create mnemonic from phrase: {0}")]
     CreateMnemonicFromPhraseFailed(String),
 
+    #[error("The delegation chain does not delegate to the key of the identity.")]
+    DelegationChainDoesNotMatchKey(),
+
     #[error("failed to create temporary identity directory")]
     CreateTemporaryIdentityDirectoryFailed(#[source] CreateDirAllError),
 
@@ -82,6 +86,9 @@ pub enum CreateNewIdentityError {
     #[error("Failed to load pem file")]
     LoadPemFromFileFailed(#[source] LoadPemFromFileError),
 
+    #[error("Failed to load identity from PEM content")]
+    LoadPemIdentityFailed(#[source] LoadPemIdentityError),
+
     #[error("Failed to remove identity")]
     RemoveIdentityFailed(#[source] RemoveIdentityError),
 
@@ -104,6 +111,15 @@ pub enum CreateNewIdentityError {
     ValidatePemFileFailed(#[source] ValidatePemFileError),
 }
 
+#[derive(Error, Debug)]
+pub enum DelegateError {
+    #[error("The identity cannot sign delegations.")]
+    CannotSignDelegations(),
+
+    #[error("Failed to sign the delegation: {0}")]
+    SignDelegationFailed(String),
+}
+
 #[derive(Error, Debug)]
 pub enum ExportIdentityError {
     #[error("Failed to get identity config")]
@@ -172,10 +188,48 @@ pub enum InstantiateIdentityFromNameError {
     #[error("Failed to load identity")]
     LoadIdentityFailed(#[source] LoadIdentityError),
 
+    #[error("Failed to load ephemeral identity '{0}'")]
+    LoadEphemeralIdentityFailed(String, #[source] LoadEphemeralIdentityError),
+
     #[error("Identity must exist")]
     RequireIdentityExistsFailed(#[source] RequireIdentityExistsError),
 }
 
+#[derive(Error, Debug)]
+pub enum LoadEphemeralIdentityError {
+    #[error("Convert secret key to sec1 Pem failed")]
+    ConvertSecretKeyToSec1PemFailed(#[source] Box<sec1::Error>),
+
+    #[error("Failed to convert seed phrase to key")]
+    ConvertMnemonicToKeyFailed(#[source] ConvertMnemonicToKeyError),
+
+    #[error(
+        "Cannot read an identity from file descriptor {0}. Use 0 for stdin, or a file descriptor of 3 or above"
+    )]
+    InvalidFd(i32),
+
+    #[error("The content is neither a PEM file nor a seed phrase: {0}")]
+    InvalidSeedPhrase(String),
+
+    #[error("Failed to load identity from PEM content")]
+    LoadPemIdentityFailed(#[source] LoadPemIdentityError),
+
+    #[error("Failed to read environment variable '{0}'")]
+    ReadEnvFailed(String, #[source] std::env::VarError),
+
+    #[error("Failed to read file descriptor {0}")]
+    ReadFdFailed(i32, #[source] std::io::Error),
+
+    #[error("Failed to read stdin")]
+    ReadStdinFailed(#[source] std::io::Error),
+
+    #[error("Reading an identity from file descriptor {0} is not supported on this platform")]
+    UnsupportedFd(i32),
+
+    #[error("Failed to validate PEM content")]
+    ValidatePemFileFailed(#[source] ValidatePemFileError),
+}
+
 #[derive(Error, Debug)]
 pub enum LoadIdentityError {
     #[error("Failed to get identity config")]
@@ -192,6 +246,9 @@ pub enum LoadPemError {
 
     #[error("Failed to load PEM file from keyring for identity '{0}'")]
     LoadFromKeyringFailed(Box<String>, #[source] KeyringError),
+
+    #[error("Identity '{0}' uses an external signer and has no PEM file")]
+    NoPemForExternalIdentity(String),
 }
 
 #[derive(Error, Debug)]
@@ -224,6 +281,32 @@ pub enum MapWalletsToRenamedIdentityError {
     RenameWalletGlobalConfigKeyFailed(#[source] RenameWalletGlobalConfigKeyError),
 }
 
+#[derive(Error, Debug)]
+pub enum ExternalSignerError {
+    #[error(
+        "External signer '{0}' returned a signature that is neither 64 bytes nor a DER-encoded ECDSA signature"
+    )]
+    InvalidSignature(String),
+
+    #[error("Failed to parse the response of external signer '{0}'")]
+    ParseResponseFailed(String, #[source] serde_json::Error),
+
+    #[error("Failed to read the response of external signer '{0}'")]
+    ReadResponseFailed(String, #[source] std::io::Error),
+
+    #[error("External signer '{0}' failed: {1}")]
+    SignerFailed(String, std::process::ExitStatus),
+
+    #[error("External signer '{0}' returned an error: {1}")]
+    SignerReturnedError(String, String),
+
+    #[error("Failed to run external signer '{0}'")]
+    SpawnFailed(String, #[source] std::io::Error),
+
+    #[error("Failed to write the request to external signer '{0}'")]
+    WriteRequestFailed(String, #[source] std::io::Error),
+}
+
 #[derive(Error, Debug)]
 pub enum NewHardwareIdentityError {
     #[error("Failed to instantiate hardware identity for identity '{0}'")]
@@ -240,6 +323,17 @@ pub enum NewIdentityError {
 
     #[error("Failed to instantiate hardware identity")]
     NewHardwareIdentityFailed(#[source] NewHardwareIdentityError),
+
+    #[error("Failed to instantiate external identity '{0}'")]
+    NewExternalIdentityFailed(String, #[source] ExternalSignerError),
+
+    #[error(
+        "The delegation of identity '{0}' expired at {1}. Please run 'dfx identity delegate' to create a new one."
+    )]
+    DelegationExpired(String, String),
+
+    #[error("Failed to load the delegation chain of identity '{0}'")]
+    NewDelegatedIdentityFailed(String, #[source] DelegationError),
 }
 
 #[derive(Error, Debug)]
@@ -357,6 +451,9 @@ pub enum SavePemError {
     #[error("Cannot save PEM content for an HSM.")]
     CannotSavePemContentForHsm(),
 
+    #[error("Cannot save PEM content for an identity that uses an external signer.")]
+    CannotSavePemContentForExternalIdentity(),
+
     #[error("Failed to write PEM to file")]
     WritePemToFileFailed(#[source] WritePemToFileError),
 
@@ -387,6 +484,33 @@ pub enum ValidatePemFileError {
     ValidatePemContentFailed(#[source] Box<PemError>),
 }
 
+#[derive(Error, Debug)]
+pub enum VerifySignatureError {
+    #[error("Canister signatures, e.g. of Internet Identity, cannot be verified offline.")]
+    CanisterSignature(),
+
+    #[error("Delegation {0} of the delegation chain is invalid")]
+    InvalidDelegation(usize, #[source] Box<VerifySignatureError>),
+
+    #[error("The public key is invalid.")]
+    InvalidPublicKey(),
+
+    #[error("The signature does not match the public key.")]
+    InvalidSignature(),
+
+    #[error("The delegation chain does not allow calls to canister {0}.")]
+    NotADelegationTarget(Principal),
+
+    #[error("The sender {0} is not the anonymous principal, but the message is not signed.")]
+    MissingSignature(Principal),
+
+    #[error("The sender {0} does not match the public key of the signature, which belongs to {1}.")]
+    SenderMismatch(Principal, Principal),
+
+    #[error("The public key type is not supported.")]
+    UnsupportedPublicKey(),
+}
+
 #[derive(Error, Debug)]
 pub enum WriteDefaultIdentityError {
     #[error("Failed to save identity manager configuration")]
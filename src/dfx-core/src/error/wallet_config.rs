@@ -11,6 +11,11 @@ pub enum WalletConfigError {
     #[error("Failed to get wallet configuration path")]
     GetWalletConfigPathFailed(Box<String>, Box<String>, #[source] ConfigError),
 
+    #[error(
+        "Ephemeral identity '{0}' cannot be connected to a wallet on a persistent network, because it is never stored"
+    )]
+    EphemeralIdentityWallet(String),
+
     #[error("Failed to load wallet configuration")]
     LoadWalletConfigFailed(#[source] StructuredFileError),
 
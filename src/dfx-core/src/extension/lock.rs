@@ -0,0 +1,44 @@
+use crate::error::structured_file::StructuredFileError;
+use crate::json::{load_json_file, save_json_file};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub static EXTENSIONS_LOCK_FILE_NAME: &str = "extensions.lock.json";
+
+/// Pins the versions and archive hashes of the extensions used by a project.
+/// Stored next to dfx.json, and meant to be committed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ExtensionsLock {
+    #[serde(default)]
+    pub extensions: BTreeMap<String, LockedExtension>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LockedExtension {
+    pub version: Version,
+
+    /// Hex-encoded ed25519 public key that signed the archives, if they are signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<String>,
+
+    /// Hex-encoded sha256 of the release archives, by archive name.
+    /// Each platform uses a different archive, so the hashes are added as the extension is installed on them.
+    #[serde(default)]
+    pub archives: BTreeMap<String, String>,
+}
+
+impl ExtensionsLock {
+    pub fn load(path: &Path) -> Result<Self, StructuredFileError> {
+        if path.exists() {
+            load_json_file(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StructuredFileError> {
+        save_json_file(path, self)
+    }
+}
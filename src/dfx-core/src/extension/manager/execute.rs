@@ -1,8 +1,12 @@
 use super::ExtensionManager;
 use crate::config::cache::get_bin_cache;
-use crate::error::extension::RunExtensionError;
+use crate::error::extension::{RunExtensionError, RunExtensionHookError};
+use crate::extension::manifest::extension::{ExtensionHook, ExtensionHookKind};
+use serde::Serialize;
 use std::ffi::OsString;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 
 impl ExtensionManager {
     pub fn run_extension(
@@ -42,4 +46,60 @@ impl ExtensionManager {
             Ok(())
         }
     }
+
+    /// Runs an extension for a hook. `input` is passed as JSON on stdin,
+    /// and `env` as environment variables in addition to `DFX_HOOK`.
+    /// The stdout of the extension is redirected to stderr.
+    pub fn run_extension_hook(
+        &self,
+        extension_name: &str,
+        kind: ExtensionHookKind,
+        hook: &ExtensionHook,
+        input: &impl Serialize,
+        env: &[(&str, String)],
+        project_root: &Path,
+    ) -> Result<(), RunExtensionHookError> {
+        let input = serde_json::to_vec(input)?;
+        let dfx_cache = get_bin_cache(self.dfx_version.to_string().as_str())?;
+        let mut child = self
+            .get_extension_binary(extension_name)?
+            .args(&hook.args)
+            .arg("--dfx-cache-path")
+            .arg(dfx_cache)
+            .current_dir(project_root)
+            .env("DFX_HOOK", kind.name())
+            .envs(env.iter().cloned())
+            .stdin(Stdio::piped())
+            // The output of dfx, e.g. of `dfx deploy`, is kept free of hook output.
+            .stdout(std::io::stderr())
+            .spawn()
+            .map_err(|e| {
+                RunExtensionHookError::FailedToLaunchExtension(extension_name.to_string(), e)
+            })?;
+
+        // The extension may not read its input, which closes the pipe early.
+        if let Some(mut stdin) = child.stdin.take() {
+            if let Err(e) = stdin.write_all(&input) {
+                if e.kind() != std::io::ErrorKind::BrokenPipe {
+                    return Err(RunExtensionHookError::WriteInputFailed(
+                        extension_name.to_string(),
+                        e,
+                    ));
+                }
+            }
+        }
+
+        let status = child.wait().map_err(|e| {
+            RunExtensionHookError::ExtensionNeverFinishedExecuting(extension_name.to_string(), e)
+        })?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(RunExtensionHookError::HookFailed {
+                extension: extension_name.to_string(),
+                hook: kind.name(),
+                status,
+            })
+        }
+    }
 }
@@ -1,19 +1,26 @@
 use crate::error::extension::{
-    DownloadAndInstallExtensionToTempdirError, FinalizeInstallationError,
+    DownloadAndInstallExtensionToTempdirError, FinalizeInstallationError, GetDependenciesError,
     GetExtensionArchiveNameError, GetExtensionDownloadUrlError, GetExtensionManifestError,
     GetHighestCompatibleVersionError, GetTopLevelDirectoryError, InstallExtensionError,
+    VerifyExtensionArchiveError,
 };
 use crate::extension::{
     catalog::ExtensionCatalog,
+    lock::{EXTENSIONS_LOCK_FILE_NAME, ExtensionsLock, LockedExtension},
     manager::ExtensionManager,
-    manifest::{ExtensionDependencies, ExtensionManifest},
+    manifest::{ExtensionDependencies, ExtensionManifest, dependencies::ArchiveIntegrity},
     url::ExtensionJsonUrl,
 };
 use crate::http::get::get_with_retries;
 use backoff::exponential::ExponentialBackoff;
+use bytes::Bytes;
 use flate2::read::GzDecoder;
-use reqwest::Url;
+use reqwest::{StatusCode, Url};
+use ring::signature::{ED25519, UnparsedPublicKey};
 use semver::{BuildMetadata, Prerelease, Version};
+use sha2::{Digest, Sha256};
+use slog::{Logger, warn};
+use std::collections::BTreeMap;
 use std::io::Cursor;
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
@@ -23,17 +30,43 @@ use tar::Archive;
 use tempfile::{TempDir, tempdir_in};
 
 pub enum InstallOutcome {
-    Installed(String, Version),
+    Installed(String, Version, ArchiveVerification),
     AlreadyInstalled(String, Version),
 }
 
+/// Whether the archive of an installed extension was checked before it was unpacked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveVerification {
+    /// The archive matched a checksum or signature from the lock file or dependencies.json.
+    Verified,
+    /// There was no checksum or signature to check the archive against.
+    Unverified,
+}
+
+impl ArchiveVerification {
+    /// Warns that the archive of extension `name` was installed without being verified.
+    pub fn warn_if_unverified(self, logger: &Logger, name: &str) {
+        if self == ArchiveVerification::Unverified {
+            warn!(
+                logger,
+                "The archive of extension '{name}' was not verified, because no checksum was published for it"
+            );
+        }
+    }
+}
+
 impl ExtensionManager {
+    /// Installs an extension.
+    ///
+    /// If a lock is passed, the version pinned in it is installed unless `version` asks for another one,
+    /// and the archive must match the pinned hash. The lock is updated with the installed version and hash.
     pub async fn install_extension(
         &self,
         name: &str,
         catalog_url: Option<&Url>,
         install_as: Option<&str>,
         version: Option<&Version>,
+        lock: Option<&mut ExtensionsLock>,
     ) -> Result<InstallOutcome, InstallExtensionError> {
         let url = if let Ok(url) = Url::parse(name) {
             ExtensionJsonUrl::new(url)
@@ -49,6 +82,13 @@ impl ExtensionManager {
 
         let effective_extension_name = install_as.unwrap_or(extension_name);
 
+        let locked = lock
+            .as_ref()
+            .and_then(|lock| lock.extensions.get(extension_name))
+            .filter(|locked| version.is_none_or(|version| *version == locked.version))
+            .cloned();
+        let version = version.or(locked.as_ref().map(|locked| &locked.version));
+
         if self
             .get_extension_directory(effective_extension_name)
             .exists()
@@ -68,9 +108,31 @@ impl ExtensionManager {
             };
         }
 
-        let extension_version = match version {
-            Some(version) => version.clone(),
-            None => self.get_highest_compatible_version(&url).await?,
+        let (extension_version, dependencies) = match version {
+            Some(version) => {
+                let dependencies = match ExtensionDependencies::fetch(&url).await {
+                    Ok(dependencies) => Some(dependencies),
+                    // Extensions installed from a URL may not publish a dependencies.json.
+                    // Their archive can then not be checked, which is only accepted
+                    // if no checksum or signing key is known for the extension.
+                    Err(GetDependenciesError::Get(err))
+                        if err.status() == Some(StatusCode::NOT_FOUND)
+                            && locked.is_none()
+                            && manifest.signing_key.is_none() =>
+                    {
+                        None
+                    }
+                    Err(err) => return Err(err.into()),
+                };
+                (version.clone(), dependencies)
+            }
+            None => {
+                let dependencies = ExtensionDependencies::fetch(&url).await?;
+                (
+                    self.get_highest_compatible_version(&dependencies)?,
+                    Some(dependencies),
+                )
+            }
         };
         let github_release_tag = get_git_release_tag(extension_name, &extension_version);
         let extension_archive = get_extension_archive_name(extension_name)?;
@@ -80,15 +142,46 @@ impl ExtensionManager {
             &extension_archive,
         )?;
 
-        let temp_dir = self
-            .download_and_unpack_extension_to_tempdir(archive_url)
-            .await?;
+        let bytes = download_extension_archive(archive_url.clone()).await?;
+        let archive_file_name = format!("{extension_archive}.tar.gz");
+        let sha256 = hex::encode(Sha256::digest(&bytes));
+        let verification = verify_extension_archive(
+            extension_name,
+            &archive_file_name,
+            &bytes,
+            &sha256,
+            dependencies
+                .as_ref()
+                .and_then(|d| d.get_archive_integrity(&extension_version, &archive_file_name)),
+            manifest.signing_key.as_deref(),
+            locked.as_ref(),
+        )?;
+
+        let temp_dir = self.unpack_extension_to_tempdir(archive_url, bytes)?;
 
         self.finalize_installation(extension_name, effective_extension_name, temp_dir)?;
 
+        if let Some(lock) = lock {
+            let locked = lock
+                .extensions
+                .entry(extension_name.to_string())
+                .or_insert_with(|| LockedExtension {
+                    version: extension_version.clone(),
+                    signing_key: None,
+                    archives: BTreeMap::new(),
+                });
+            if locked.version != extension_version {
+                locked.version = extension_version.clone();
+                locked.archives.clear();
+            }
+            locked.signing_key = manifest.signing_key.clone();
+            locked.archives.insert(archive_file_name, sha256);
+        }
+
         Ok(InstallOutcome::Installed(
             extension_name.to_string(),
             extension_version,
+            verification,
         ))
     }
 
@@ -102,11 +195,10 @@ impl ExtensionManager {
         dfx_version
     }
 
-    async fn get_highest_compatible_version(
+    pub(super) fn get_highest_compatible_version(
         &self,
-        url: &ExtensionJsonUrl,
+        dependencies: &ExtensionDependencies,
     ) -> Result<Version, GetHighestCompatibleVersionError> {
-        let dependencies = ExtensionDependencies::fetch(url).await?;
         let dfx_version = self.dfx_version_strip_semver();
         dependencies
             .find_highest_compatible_version(&dfx_version)?
@@ -129,23 +221,11 @@ impl ExtensionManager {
             .map_err(GetExtensionManifestError::ParseJson)
     }
 
-    async fn download_and_unpack_extension_to_tempdir(
+    fn unpack_extension_to_tempdir(
         &self,
         download_url: Url,
+        bytes: Bytes,
     ) -> Result<TempDir, DownloadAndInstallExtensionToTempdirError> {
-        let retry_policy = ExponentialBackoff {
-            max_elapsed_time: Some(Duration::from_secs(60)),
-            ..Default::default()
-        };
-        let response = get_with_retries(download_url.clone(), retry_policy)
-            .await
-            .map_err(DownloadAndInstallExtensionToTempdirError::ExtensionDownloadFailed)?;
-
-        let bytes = response
-            .bytes()
-            .await
-            .map_err(DownloadAndInstallExtensionToTempdirError::ExtensionDownloadFailed)?;
-
         crate::fs::composite::ensure_dir_exists(&self.dir)
             .map_err(DownloadAndInstallExtensionToTempdirError::EnsureExtensionDirExistsFailed)?;
 
@@ -196,6 +276,108 @@ impl ExtensionManager {
     }
 }
 
+async fn download_extension_archive(
+    download_url: Url,
+) -> Result<Bytes, DownloadAndInstallExtensionToTempdirError> {
+    let retry_policy = ExponentialBackoff {
+        max_elapsed_time: Some(Duration::from_secs(60)),
+        ..Default::default()
+    };
+    let response = get_with_retries(download_url, retry_policy)
+        .await
+        .map_err(DownloadAndInstallExtensionToTempdirError::ExtensionDownloadFailed)?;
+
+    response
+        .bytes()
+        .await
+        .map_err(DownloadAndInstallExtensionToTempdirError::ExtensionDownloadFailed)
+}
+
+/// Checks the downloaded archive against the hash pinned in the lock file, and the hash and
+/// signature published in dependencies.json. Nothing is unpacked before this succeeds.
+///
+/// Returns `Unverified` if there was no hash to check the archive against.
+///
+/// The signing key and dependencies.json come from the same host as the archive, so this detects
+/// corrupted downloads, and a changed key or archive once they are pinned in the lock file,
+/// but not a host that serves a forged archive on the first install.
+fn verify_extension_archive(
+    extension_name: &str,
+    archive_name: &str,
+    bytes: &[u8],
+    sha256: &str,
+    integrity: Option<&ArchiveIntegrity>,
+    signing_key: Option<&str>,
+    locked: Option<&LockedExtension>,
+) -> Result<ArchiveVerification, VerifyExtensionArchiveError> {
+    let mut verification = ArchiveVerification::Unverified;
+    let check_checksum = |expected: &str, expected_by: &str| {
+        if expected.eq_ignore_ascii_case(sha256) {
+            Ok(())
+        } else {
+            Err(VerifyExtensionArchiveError::ChecksumMismatch {
+                archive: archive_name.to_string(),
+                actual: sha256.to_string(),
+                expected: expected.to_string(),
+                expected_by: expected_by.to_string(),
+            })
+        }
+    };
+
+    if let Some(locked) = locked {
+        if let Some(expected) = locked.archives.get(archive_name) {
+            check_checksum(expected, EXTENSIONS_LOCK_FILE_NAME)?;
+            verification = ArchiveVerification::Verified;
+        }
+        if let Some(locked_key) = &locked.signing_key {
+            if signing_key != Some(locked_key.as_str()) {
+                return Err(VerifyExtensionArchiveError::SigningKeyChanged {
+                    name: extension_name.to_string(),
+                    locked: locked_key.clone(),
+                    manifest: signing_key.unwrap_or("none").to_string(),
+                });
+            }
+        }
+    }
+
+    if let Some(integrity) = integrity {
+        check_checksum(&integrity.sha256, "dependencies.json")?;
+        verification = ArchiveVerification::Verified;
+    }
+
+    match (signing_key, integrity.and_then(|i| i.signature.as_deref())) {
+        (Some(key), Some(signature)) => {
+            if !verify_signature(key, signature, bytes) {
+                return Err(VerifyExtensionArchiveError::InvalidSignature(
+                    archive_name.to_string(),
+                ));
+            }
+            verification = ArchiveVerification::Verified;
+        }
+        (Some(_), None) => {
+            return Err(VerifyExtensionArchiveError::MissingSignature(
+                archive_name.to_string(),
+            ));
+        }
+        (None, Some(_)) => {
+            return Err(VerifyExtensionArchiveError::MissingSigningKey(
+                archive_name.to_string(),
+            ));
+        }
+        (None, None) => {}
+    }
+    Ok(verification)
+}
+
+fn verify_signature(public_key: &str, signature: &str, message: &[u8]) -> bool {
+    let (Ok(public_key), Ok(signature)) = (hex::decode(public_key), hex::decode(signature)) else {
+        return false;
+    };
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(message, &signature)
+        .is_ok()
+}
+
 fn get_top_level_directory(dir: &Path) -> Result<PathBuf, GetTopLevelDirectoryError> {
     // the archive will have a single top-level subdirectory
     // return that subdirectory
@@ -3,15 +3,17 @@ use crate::error::extension::{
     GetExtensionBinaryError, LoadExtensionManifestsError, NewExtensionManagerError,
 };
 use crate::extension::{installed::InstalledExtensionManifests, manifest::ExtensionManifest};
-pub use install::InstallOutcome;
+pub use install::{ArchiveVerification, InstallOutcome};
 use semver::Version;
 use std::collections::HashMap;
 use std::path::PathBuf;
+pub use update::UpdateOutcome;
 
 mod execute;
 mod install;
 mod list;
 mod uninstall;
+mod update;
 
 pub struct ExtensionManager {
     pub dir: PathBuf,
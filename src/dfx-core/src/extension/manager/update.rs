@@ -0,0 +1,86 @@
+use super::ExtensionManager;
+use super::{ArchiveVerification, InstallOutcome};
+use crate::error::extension::UpdateExtensionError;
+use crate::extension::{
+    catalog::ExtensionCatalog,
+    lock::ExtensionsLock,
+    manifest::{ExtensionDependencies, ExtensionManifest},
+    url::ExtensionJsonUrl,
+};
+use semver::Version;
+use tempfile::tempdir_in;
+use url::Url;
+
+pub enum UpdateOutcome {
+    Updated {
+        name: String,
+        from: Version,
+        to: Version,
+        verification: ArchiveVerification,
+    },
+    UpToDate(String, Version),
+}
+
+impl ExtensionManager {
+    /// Updates an installed extension to `version`, or to the highest version that is compatible with this dfx.
+    ///
+    /// The installed version is kept in place until the new version is installed.
+    pub async fn update_extension(
+        &self,
+        installed_name: &str,
+        catalog_url: Option<&Url>,
+        extension_url: Option<&Url>,
+        version: Option<&Version>,
+        lock: Option<&mut ExtensionsLock>,
+    ) -> Result<UpdateOutcome, UpdateExtensionError> {
+        let installed = ExtensionManifest::load(installed_name, &self.dir)?;
+        let url = match extension_url {
+            Some(url) => url.clone(),
+            None => ExtensionCatalog::fetch(catalog_url)
+                .await?
+                .lookup(&installed.name)
+                .ok_or_else(|| UpdateExtensionError::ExtensionNotFound(installed.name.clone()))?
+                .as_url()
+                .clone(),
+        };
+
+        let version = match version {
+            Some(version) => version.clone(),
+            None => {
+                let dependencies =
+                    ExtensionDependencies::fetch(&ExtensionJsonUrl::new(url.clone())).await?;
+                self.get_highest_compatible_version(&dependencies)?
+            }
+        };
+        if version == *installed.version {
+            return Ok(UpdateOutcome::UpToDate(installed.name, version));
+        }
+
+        let extension_dir = self.get_extension_directory(installed_name);
+        let backup_dir = tempdir_in(&self.dir).map_err(|e| {
+            UpdateExtensionError::CreateTemporaryDirectoryFailed(self.dir.clone(), e)
+        })?;
+        let backup = backup_dir.path().join(installed_name);
+        crate::fs::rename(&extension_dir, &backup)?;
+
+        let install_as = (installed_name != installed.name).then_some(installed_name);
+        let outcome = self
+            .install_extension(url.as_str(), None, install_as, Some(&version), lock)
+            .await;
+        match outcome {
+            Ok(InstallOutcome::Installed(name, to, verification)) => Ok(UpdateOutcome::Updated {
+                name,
+                from: installed.version.0,
+                to,
+                verification,
+            }),
+            Ok(InstallOutcome::AlreadyInstalled(name, version)) => {
+                Ok(UpdateOutcome::UpToDate(name, version))
+            }
+            Err(e) => {
+                crate::fs::rename(&backup, &extension_dir)?;
+                Err(e.into())
+            }
+        }
+    }
+}
@@ -19,10 +19,29 @@ pub enum DependencyRequirement {
     Version(VersionReqWithJsonSchema),
 }
 
+/// Integrity information of a release archive.
+#[derive(Clone, Debug, Deserialize, JsonSchema)]
+pub struct ArchiveIntegrity {
+    /// Hex-encoded sha256 of the archive.
+    pub sha256: String,
+
+    /// Hex-encoded ed25519 signature of the archive, made with the `signing_key` of the extension manifest.
+    pub signature: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ExtensionVersionDependencies {
+    /// Integrity information of the release archives of this version,
+    /// by archive name, for example "nns-x86_64-unknown-linux-gnu.tar.gz".
+    #[serde(default)]
+    pub archives: HashMap<String, ArchiveIntegrity>,
+
+    #[serde(flatten)]
+    pub dependencies: HashMap<DependencyName, DependencyRequirement>,
+}
+
 #[derive(Deserialize, Debug, JsonSchema)]
-pub struct ExtensionDependencies(
-    pub HashMap<ExtensionVersion, HashMap<DependencyName, DependencyRequirement>>,
-);
+pub struct ExtensionDependencies(pub HashMap<ExtensionVersion, ExtensionVersionDependencies>);
 
 impl ExtensionDependencies {
     pub async fn fetch(url: &ExtensionJsonUrl) -> Result<Self, GetDependenciesError> {
@@ -47,7 +66,7 @@ impl ExtensionDependencies {
         keys.reverse(); // check higher extension versions first
 
         for key in keys {
-            let dependencies = self.0.get(key).unwrap();
+            let dependencies = &self.0.get(key).unwrap().dependencies;
             for (dependency, requirements) in dependencies {
                 if dependency == "dfx" {
                     match requirements {
@@ -65,6 +84,14 @@ impl ExtensionDependencies {
 
         Ok(None)
     }
+
+    pub fn get_archive_integrity(
+        &self,
+        version: &Version,
+        archive_name: &str,
+    ) -> Option<&ArchiveIntegrity> {
+        self.0.get(version)?.archives.get(archive_name)
+    }
 }
 
 #[test]
@@ -84,6 +111,11 @@ fn parse_test_file() {
   "0.7.0": {
     "dfx": {
       "version": ">=0.9.9"
+    },
+    "archives": {
+      "nns-x86_64-unknown-linux-gnu.tar.gz": {
+        "sha256": "0a1b2c"
+      }
     }
   }
 }
@@ -98,13 +130,13 @@ fn parse_test_file() {
     assert!(versions.contains(&&Version::new(0, 6, 2)));
     assert!(versions.contains(&&Version::new(0, 7, 0)));
 
-    let v_3_4 = manifest.0.get(&Version::new(0, 3, 4)).unwrap();
+    let v_3_4 = &manifest.0.get(&Version::new(0, 3, 4)).unwrap().dependencies;
     let dfx = v_3_4.get("dfx").unwrap();
     let DependencyRequirement::Version(req) = dfx;
     assert!(req.matches(&semver::Version::new(0, 8, 5)));
     assert!(!req.matches(&semver::Version::new(0, 9, 0)));
 
-    let v_6_2 = manifest.0.get(&Version::new(0, 6, 2)).unwrap();
+    let v_6_2 = &manifest.0.get(&Version::new(0, 6, 2)).unwrap().dependencies;
     let dfx = v_6_2.get("dfx").unwrap();
     let DependencyRequirement::Version(req) = dfx;
     assert!(req.matches(&semver::Version::new(0, 9, 6)));
@@ -128,4 +160,22 @@ fn parse_test_file() {
             .unwrap(),
         Some(Version::new(0, 7, 0))
     );
+
+    let v_7_0 = Version::new(0, 7, 0);
+    // "archives" is not a dependency
+    assert_eq!(manifest.0.get(&v_7_0).unwrap().dependencies.len(), 1);
+    assert_eq!(
+        manifest
+            .get_archive_integrity(&v_7_0, "nns-x86_64-unknown-linux-gnu.tar.gz")
+            .map(|integrity| integrity.sha256.as_str()),
+        Some("0a1b2c")
+    );
+    assert!(
+        manifest
+            .get_archive_integrity(
+                &Version::new(0, 6, 2),
+                "nns-x86_64-unknown-linux-gnu.tar.gz"
+            )
+            .is_none()
+    );
 }
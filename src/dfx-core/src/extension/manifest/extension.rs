@@ -60,6 +60,49 @@ pub struct ExtensionManifest {
         skip_serializing_if = "Option::is_none"
     )]
     pub download_url_template: Option<String>,
+
+    /// Hex-encoded ed25519 public key that signs the release archives.
+    /// If set, the `archives` of every version in dependencies.json must have a signature.
+    /// The key is published on the same host as the archives, so it detects corrupted archives, and
+    /// changed keys once it is pinned in extensions.lock.json, but not a compromised host.
+    pub signing_key: Option<String>,
+
+    /// Hooks into `dfx build` and `dfx deploy`, for projects that declare the extension in dfx.json.
+    /// The extension binary receives a JSON description of the canister and network on stdin.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hooks: BTreeMap<ExtensionHookKind, ExtensionHook>,
+}
+
+/// # Extension Hook Kind
+/// `pre-build` and `post-build` run around the build of each canister,
+/// `pre-install` runs before each canister is installed by `dfx deploy`,
+/// and `post-deploy` runs once after `dfx deploy` installed all canisters.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExtensionHookKind {
+    PreBuild,
+    PostBuild,
+    PreInstall,
+    PostDeploy,
+}
+
+impl ExtensionHookKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PreBuild => "pre-build",
+            Self::PostBuild => "post-build",
+            Self::PreInstall => "pre-install",
+            Self::PostDeploy => "post-deploy",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ExtensionHook {
+    /// Arguments to run the extension binary with, for example `["hooks", "post-build"]`.
+    pub args: Vec<String>,
 }
 
 fn default_download_url_template() -> Option<String> {
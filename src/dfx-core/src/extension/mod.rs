@@ -1,5 +1,6 @@
 pub mod catalog;
 pub mod installed;
+pub mod lock;
 pub mod manager;
 pub mod manifest;
 pub mod url;
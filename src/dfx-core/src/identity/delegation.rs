@@ -0,0 +1,217 @@
+//! Delegation chains that let a session key act on behalf of a root identity.
+//!
+//! Chains are stored and exchanged in the JSON format that agent-js uses,
+//! so that chains created by other tools can be imported as well.
+use crate::error::identity::DelegateError;
+use crate::error::identity::DelegateError::{CannotSignDelegations, SignDelegationFailed};
+use candid::Principal;
+use ic_agent::identity::{Delegation, SignedDelegation};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::time::{SystemTime, UNIX_EPOCH};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+/// A chain of delegations from the key `public_key` to a session key.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationChain {
+    pub delegations: Vec<DelegationChainLink>,
+
+    /// The DER-encoded public key of the root identity.
+    #[serde(with = "hex")]
+    pub public_key: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DelegationChainLink {
+    pub delegation: DelegationJson,
+
+    #[serde(with = "hex")]
+    pub signature: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DelegationJson {
+    /// The DER-encoded public key that is delegated to.
+    #[serde(with = "hex")]
+    pub pubkey: Vec<u8>,
+
+    /// Nanoseconds since the UNIX epoch.
+    #[serde(with = "hex_u64")]
+    pub expiration: u64,
+
+    /// The canisters that the delegation is restricted to.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "hex_principals"
+    )]
+    pub targets: Option<Vec<Principal>>,
+}
+
+impl DelegationChain {
+    /// Signs a delegation from `signer` to `to_public_key`.
+    /// If the signer is a delegated identity itself, its chain is extended.
+    pub fn delegate(
+        signer: &dyn ic_agent::Identity,
+        to_public_key: Vec<u8>,
+        expiration: u64,
+        targets: Option<Vec<Principal>>,
+    ) -> Result<Self, DelegateError> {
+        let delegation = Delegation {
+            pubkey: to_public_key,
+            expiration,
+            targets,
+        };
+        let signature = signer
+            .sign_delegation(&delegation)
+            .map_err(SignDelegationFailed)?;
+        let (Some(public_key), Some(signature_bytes)) = (signature.public_key, signature.signature)
+        else {
+            return Err(CannotSignDelegations());
+        };
+        let mut delegations = signature.delegations.unwrap_or_default();
+        delegations.push(SignedDelegation {
+            delegation,
+            signature: signature_bytes,
+        });
+        Ok(Self {
+            delegations: delegations.iter().map(DelegationChainLink::from).collect(),
+            public_key,
+        })
+    }
+
+    /// The principal of the root identity, which the session key acts as.
+    pub fn root_principal(&self) -> Principal {
+        Principal::self_authenticating(&self.public_key)
+    }
+
+    /// The earliest expiration in the chain, in nanoseconds since the UNIX epoch.
+    pub fn expiration(&self) -> u64 {
+        self.delegations
+            .iter()
+            .map(|link| link.delegation.expiration)
+            .min()
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Whether the chain, and with it the identity, can no longer be used.
+    pub fn is_expired(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_nanos();
+        u128::from(self.expiration()) <= now
+    }
+
+    /// The expiration of the chain in RFC 3339 format.
+    pub fn expiration_text(&self) -> String {
+        OffsetDateTime::from_unix_timestamp_nanos(i128::from(self.expiration()))
+            .ok()
+            .and_then(|expiration| expiration.format(&Rfc3339).ok())
+            .unwrap_or_else(|| "never".to_string())
+    }
+
+    /// The public key that the last delegation delegates to.
+    pub fn session_public_key(&self) -> Option<&[u8]> {
+        self.delegations
+            .last()
+            .map(|link| link.delegation.pubkey.as_slice())
+    }
+
+    pub fn signed_delegations(&self) -> Vec<SignedDelegation> {
+        self.delegations
+            .iter()
+            .map(|link| SignedDelegation {
+                delegation: Delegation {
+                    pubkey: link.delegation.pubkey.clone(),
+                    expiration: link.delegation.expiration,
+                    targets: link.delegation.targets.clone(),
+                },
+                signature: link.signature.clone(),
+            })
+            .collect()
+    }
+}
+
+impl From<&SignedDelegation> for DelegationChainLink {
+    fn from(signed: &SignedDelegation) -> Self {
+        Self {
+            delegation: DelegationJson {
+                pubkey: signed.delegation.pubkey.clone(),
+                expiration: signed.delegation.expiration,
+                targets: signed.delegation.targets.clone(),
+            },
+            signature: signed.signature.clone(),
+        }
+    }
+}
+
+mod hex_u64 {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{value:x}"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        u64::from_str_radix(&s, 16).map_err(serde::de::Error::custom)
+    }
+}
+
+mod hex_principals {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Vec<Principal>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value
+            .as_ref()
+            .map(|principals| {
+                principals
+                    .iter()
+                    .map(|principal| hex::encode(principal.as_slice()))
+                    .collect::<Vec<_>>()
+            })
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<Principal>>, D::Error> {
+        let Some(targets) = Option::<Vec<String>>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        targets
+            .iter()
+            .map(|target| {
+                let bytes = hex::decode(target).map_err(serde::de::Error::custom)?;
+                Principal::try_from_slice(&bytes).map_err(serde::de::Error::custom)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_and_writes_the_agent_js_format() {
+        let json = r#"{"delegations":[{"delegation":{"pubkey":"0102","expiration":"17d1b1a2c3d4e5f6","targets":["00000000000000010101"]},"signature":"abcd"}],"publicKey":"0304"}"#;
+        let chain: DelegationChain = serde_json::from_str(json).unwrap();
+        assert_eq!(chain.public_key, vec![3, 4]);
+        assert_eq!(chain.expiration(), 0x17d1b1a2c3d4e5f6);
+        assert_eq!(chain.session_public_key(), Some([1u8, 2].as_slice()));
+        assert_eq!(
+            chain.delegations[0].delegation.targets,
+            Some(vec![
+                Principal::from_text("rrkah-fqaaa-aaaaa-aaaaq-cai").unwrap()
+            ])
+        );
+        assert_eq!(serde_json::to_string(&chain).unwrap(), json);
+    }
+}
@@ -0,0 +1,152 @@
+//! Identities whose key is read from the environment, a file descriptor or stdin.
+//!
+//! Ephemeral identities are selected like named identities, e.g. with `--identity env:CI_IDENTITY_PEM`,
+//! but nothing about them is written to the identity directory.
+use crate::error::identity::LoadEphemeralIdentityError;
+use crate::error::identity::LoadEphemeralIdentityError::{
+    ConvertMnemonicToKeyFailed, ConvertSecretKeyToSec1PemFailed, InvalidFd, InvalidSeedPhrase,
+    LoadPemIdentityFailed, ReadEnvFailed, ReadFdFailed, ReadStdinFailed, ValidatePemFileFailed,
+};
+use crate::identity::identity_manager::mnemonic_to_key;
+use crate::identity::{Identity, IdentityType, pem_utils};
+use bip39::{Language, Mnemonic};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::sync::Mutex;
+
+/// The contents read so far, so that stdin and file descriptors can be read only once per process.
+static CONTENTS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// Where the key of an ephemeral identity is read from.
+#[derive(Debug, PartialEq, Eq)]
+pub enum EphemeralIdentitySource {
+    /// `env:<VARIABLE>`
+    Env(String),
+    /// `fd:<N>`
+    Fd(i32),
+    /// `stdin:`
+    Stdin,
+}
+
+impl EphemeralIdentitySource {
+    /// Parses an identity name that refers to an ephemeral identity.
+    /// Returns `None` for the names of regular identities, which cannot contain `:`.
+    pub fn from_identity_name(name: &str) -> Option<Self> {
+        let (source, location) = name.split_once(':')?;
+        match source {
+            "env" if !location.is_empty() => Some(Self::Env(location.to_string())),
+            "fd" => match location.parse() {
+                Ok(0) => Some(Self::Stdin),
+                Ok(fd) => Some(Self::Fd(fd)),
+                Err(_) => None,
+            },
+            "stdin" if location.is_empty() => Some(Self::Stdin),
+            _ => None,
+        }
+    }
+
+    fn read(&self) -> Result<String, LoadEphemeralIdentityError> {
+        match self {
+            Self::Env(variable) => {
+                std::env::var(variable).map_err(|e| ReadEnvFailed(variable.clone(), e))
+            }
+            Self::Stdin => {
+                let mut content = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut content)
+                    .map_err(ReadStdinFailed)?;
+                Ok(content)
+            }
+            // stdout and stderr are used by dfx itself.
+            Self::Fd(fd) if *fd < 0 || *fd == 1 || *fd == 2 => Err(InvalidFd(*fd)),
+            #[cfg(unix)]
+            Self::Fd(fd) => {
+                use std::mem::ManuallyDrop;
+                use std::os::fd::FromRawFd;
+                // SAFETY: the file descriptor was handed to dfx to read the identity from.
+                // It is owned by the caller, so it is not closed when the file is dropped.
+                let mut file = ManuallyDrop::new(unsafe { std::fs::File::from_raw_fd(*fd) });
+                let mut content = String::new();
+                file.read_to_string(&mut content)
+                    .map_err(|e| ReadFdFailed(*fd, e))?;
+                Ok(content)
+            }
+            #[cfg(not(unix))]
+            Self::Fd(fd) => Err(LoadEphemeralIdentityError::UnsupportedFd(*fd)),
+        }
+    }
+}
+
+impl Identity {
+    /// Loads an identity from a PEM file or a seed phrase that is kept in memory only.
+    pub(crate) fn ephemeral(
+        name: &str,
+        source: &EphemeralIdentitySource,
+    ) -> Result<Self, LoadEphemeralIdentityError> {
+        let content = {
+            let mut contents = CONTENTS.lock().unwrap();
+            match contents.get(name) {
+                Some(content) => content.clone(),
+                None => {
+                    let content = source.read()?;
+                    contents.insert(name.to_string(), content.clone());
+                    content
+                }
+            }
+        };
+        let pem_content = if content.contains("-----BEGIN") {
+            content.trim().as_bytes().to_vec()
+        } else {
+            let mnemonic = Mnemonic::from_phrase(content.trim(), Language::English)
+                .map_err(|e| InvalidSeedPhrase(e.to_string()))?;
+            let key = mnemonic_to_key(&mnemonic).map_err(ConvertMnemonicToKeyFailed)?;
+            key.to_sec1_pem(k256::pkcs8::LineEnding::LF)
+                .map_err(|e| ConvertSecretKeyToSec1PemFailed(Box::new(e)))?
+                .as_bytes()
+                .to_vec()
+        };
+        pem_utils::validate_pem_file(&pem_content).map_err(ValidatePemFileFailed)?;
+        let identity_type = IdentityType::Ephemeral;
+        Identity::secp256k1(name, &pem_content, identity_type)
+            .or_else(|e| Identity::basic(name, &pem_content, identity_type).map_err(|_| e))
+            .map_err(LoadPemIdentityFailed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ephemeral_identity_names() {
+        assert_eq!(
+            EphemeralIdentitySource::from_identity_name("env:CI_PEM"),
+            Some(EphemeralIdentitySource::Env("CI_PEM".to_string()))
+        );
+        assert_eq!(
+            EphemeralIdentitySource::from_identity_name("fd:3"),
+            Some(EphemeralIdentitySource::Fd(3))
+        );
+        assert_eq!(
+            EphemeralIdentitySource::from_identity_name("fd:0"),
+            Some(EphemeralIdentitySource::Stdin)
+        );
+        assert_eq!(
+            EphemeralIdentitySource::from_identity_name("stdin:"),
+            Some(EphemeralIdentitySource::Stdin)
+        );
+        assert_eq!(EphemeralIdentitySource::from_identity_name("default"), None);
+        assert_eq!(EphemeralIdentitySource::from_identity_name("env:"), None);
+        assert_eq!(EphemeralIdentitySource::from_identity_name("fd:x"), None);
+    }
+
+    #[test]
+    fn rejects_file_descriptors_used_by_dfx() {
+        for fd in [-1, 1, 2] {
+            assert!(matches!(
+                EphemeralIdentitySource::Fd(fd).read(),
+                Err(InvalidFd(invalid)) if invalid == fd
+            ));
+        }
+    }
+}
@@ -0,0 +1,238 @@
+//! Identities whose key is held by an external signer program.
+//!
+//! Similar to git credential helpers, dfx runs the configured command once per operation,
+//! writes a single JSON request to its stdin and reads a single JSON response from its stdout.
+//! The signer's stderr is passed through, so that it can prompt the user, e.g. to touch a security key.
+//!
+//! The requests are:
+//! - `{"operation": "get-public-key", "identity": "<name>"}`, answered with
+//!   `{"public_key": "<hex-encoded DER public key>"}`
+//! - `{"operation": "sign", "identity": "<name>", "content": "<hex-encoded bytes>"}`, answered with
+//!   `{"signature": "<hex-encoded signature>"}`
+//!
+//! The content to sign already contains the domain separator.
+//! The signer signs it like any other message of its key type, e.g. ECDSA signers hash it with SHA-256 first.
+//! Ed25519 signatures are the 64 bytes defined by RFC 8032. ECDSA signatures (secp256k1 or P-256) are
+//! expected as the 64-byte concatenation of `r` and `s`, each a 32-byte big-endian integer,
+//! which is the encoding the IC verifies. DER-encoded ECDSA signatures, as returned by most
+//! KMS and HSM APIs, are converted to that encoding.
+//! A signer reports a failure with `{"error": "<message>"}` or a non-zero exit code.
+use crate::error::identity::ExternalSignerError;
+use crate::error::identity::ExternalSignerError::{
+    InvalidSignature, ParseResponseFailed, ReadResponseFailed, SignerFailed, SignerReturnedError,
+    SpawnFailed, WriteRequestFailed,
+};
+use crate::identity::identity_manager::ExternalIdentityConfiguration;
+use candid::Principal;
+use ic_agent::Signature;
+use ic_agent::agent::EnvelopeContent;
+use ic_agent::identity::{Delegation, SignedDelegation};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[derive(Serialize)]
+#[serde(tag = "operation", rename_all = "kebab-case")]
+enum ExternalSignerRequest<'a> {
+    GetPublicKey {
+        identity: &'a str,
+    },
+    Sign {
+        identity: &'a str,
+        #[serde(with = "hex")]
+        content: &'a [u8],
+    },
+}
+
+#[derive(Deserialize)]
+struct PublicKeyResponse {
+    #[serde(with = "hex")]
+    public_key: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct SignatureResponse {
+    #[serde(with = "hex")]
+    signature: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+pub struct ExternalIdentity {
+    name: String,
+    config: ExternalIdentityConfiguration,
+    public_key: Vec<u8>,
+}
+
+impl ExternalIdentity {
+    /// Asks the external signer for the public key of the identity.
+    pub fn new(
+        name: &str,
+        config: ExternalIdentityConfiguration,
+    ) -> Result<Self, ExternalSignerError> {
+        let request = ExternalSignerRequest::GetPublicKey { identity: name };
+        let response: PublicKeyResponse = run_signer(&config, &request)?;
+        Ok(Self {
+            name: name.to_string(),
+            config,
+            public_key: response.public_key,
+        })
+    }
+
+    fn sign_bytes(&self, content: &[u8]) -> Result<Vec<u8>, ExternalSignerError> {
+        let request = ExternalSignerRequest::Sign {
+            identity: &self.name,
+            content,
+        };
+        let response: SignatureResponse = run_signer(&self.config, &request)?;
+        if response.signature.len() == RAW_SIGNATURE_LEN {
+            return Ok(response.signature);
+        }
+        ecdsa_der_to_raw(&response.signature)
+            .ok_or_else(|| InvalidSignature(self.config.command.clone()))
+    }
+}
+
+/// The length of Ed25519 signatures, and of ECDSA signatures as `r‖s`.
+const RAW_SIGNATURE_LEN: usize = 64;
+
+/// Converts a DER-encoded ECDSA signature, `SEQUENCE { r INTEGER, s INTEGER }`,
+/// into the concatenation of `r` and `s`, each padded to 32 bytes.
+fn ecdsa_der_to_raw(der: &[u8]) -> Option<Vec<u8>> {
+    const SEQUENCE: u8 = 0x30;
+    const INTEGER: u8 = 0x02;
+    const SCALAR_LEN: usize = RAW_SIGNATURE_LEN / 2;
+
+    let (sequence, rest) = read_der(der, SEQUENCE)?;
+    let (r, sequence) = read_der(sequence, INTEGER)?;
+    let (s, sequence) = read_der(sequence, INTEGER)?;
+    if !rest.is_empty() || !sequence.is_empty() {
+        return None;
+    }
+    let mut raw = vec![0; RAW_SIGNATURE_LEN];
+    for (index, scalar) in [r, s].into_iter().enumerate() {
+        // Integers are signed, so a leading zero byte keeps them positive.
+        let leading_zeros = scalar.iter().take_while(|byte| **byte == 0).count();
+        let scalar = &scalar[leading_zeros..];
+        if scalar.len() > SCALAR_LEN {
+            return None;
+        }
+        let end = (index + 1) * SCALAR_LEN;
+        raw[end - scalar.len()..end].copy_from_slice(scalar);
+    }
+    Some(raw)
+}
+
+/// Splits a DER value with the given tag off `bytes`, returning its contents and the remaining bytes.
+/// The signatures of 256-bit curves are short enough that only the short form of the length is used.
+fn read_der(bytes: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    let (&actual_tag, bytes) = bytes.split_first()?;
+    let (&len, bytes) = bytes.split_first()?;
+    if actual_tag != tag || len >= 0x80 || bytes.len() < len as usize {
+        return None;
+    }
+    Some(bytes.split_at(len as usize))
+}
+
+fn run_signer<T: DeserializeOwned>(
+    config: &ExternalIdentityConfiguration,
+    request: &ExternalSignerRequest,
+) -> Result<T, ExternalSignerError> {
+    let command = &config.command;
+    let mut child = Command::new(command)
+        .args(&config.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| SpawnFailed(command.clone(), e))?;
+    {
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let request = serde_json::to_vec(request).expect("the request is serializable");
+        stdin
+            .write_all(&request)
+            .and_then(|_| stdin.write_all(b"\n"))
+            .map_err(|e| WriteRequestFailed(command.clone(), e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| ReadResponseFailed(command.clone(), e))?;
+    if let Ok(ErrorResponse { error }) = serde_json::from_slice(&output.stdout) {
+        return Err(SignerReturnedError(command.clone(), error));
+    }
+    if !output.status.success() {
+        return Err(SignerFailed(command.clone(), output.status));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| ParseResponseFailed(command.clone(), e))
+}
+
+/// The errors of `ic_agent::Identity` are strings, so the error and its causes are joined.
+fn error_to_string(error: ExternalSignerError) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(&format!(": {cause}"));
+        source = cause.source();
+    }
+    message
+}
+
+impl ic_agent::Identity for ExternalIdentity {
+    fn sender(&self) -> Result<Principal, String> {
+        Ok(Principal::self_authenticating(&self.public_key))
+    }
+
+    fn public_key(&self) -> Option<Vec<u8>> {
+        Some(self.public_key.clone())
+    }
+
+    fn delegation_chain(&self) -> Vec<SignedDelegation> {
+        vec![]
+    }
+
+    fn sign(&self, content: &EnvelopeContent) -> Result<Signature, String> {
+        self.sign_arbitrary(&content.to_request_id().signable())
+    }
+
+    fn sign_arbitrary(&self, content: &[u8]) -> Result<Signature, String> {
+        let signature = self.sign_bytes(content).map_err(error_to_string)?;
+        Ok(Signature {
+            public_key: Some(self.public_key.clone()),
+            signature: Some(signature),
+            delegations: None,
+        })
+    }
+
+    fn sign_delegation(&self, content: &Delegation) -> Result<Signature, String> {
+        self.sign_arbitrary(&content.signable())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_der_ecdsa_signatures_to_r_and_s() {
+        // r has a leading zero byte to keep it positive, and s is shorter than 32 bytes.
+        let r = [0x80; 32];
+        let s = [0x01; 31];
+        let mut der = vec![0x30, 2 + 33 + 2 + 31, 0x02, 33, 0x00];
+        der.extend(r);
+        der.extend([0x02, 31]);
+        der.extend(s);
+
+        let raw = ecdsa_der_to_raw(&der).unwrap();
+        assert_eq!(raw[..32], r);
+        assert_eq!(raw[32], 0);
+        assert_eq!(raw[33..], s);
+
+        assert_eq!(ecdsa_der_to_raw(&der[..der.len() - 1]), None);
+        assert_eq!(ecdsa_der_to_raw(&[der.as_slice(), &[0]].concat()), None);
+    }
+}
@@ -14,8 +14,8 @@ use crate::error::identity::{
     CreateNewIdentityError,
     CreateNewIdentityError::{
         ConvertSecretKeyToSec1PemFailed, CreateMnemonicFromPhraseFailed,
-        CreateTemporaryIdentityDirectoryFailed, SwitchBackToIdentityFailed,
-        SwitchToAnonymousIdentityFailed,
+        CreateTemporaryIdentityDirectoryFailed, DelegationChainDoesNotMatchKey,
+        SwitchBackToIdentityFailed, SwitchToAnonymousIdentityFailed,
     },
     ExportIdentityError,
     ExportIdentityError::TranslatePemContentToTextFailed,
@@ -31,7 +31,9 @@ use crate::error::identity::{
         WritePemToFileFailed,
     },
     InstantiateIdentityFromNameError,
-    InstantiateIdentityFromNameError::{GetIdentityPrincipalFailed, LoadIdentityFailed},
+    InstantiateIdentityFromNameError::{
+        GetIdentityPrincipalFailed, LoadEphemeralIdentityFailed, LoadIdentityFailed,
+    },
     LoadIdentityError, NewIdentityManagerError,
     NewIdentityManagerError::LoadIdentityManagerConfigurationFailed,
     RemoveIdentityError,
@@ -54,11 +56,13 @@ use crate::error::identity::{
 use crate::error::structured_file::StructuredFileError;
 use crate::foundation::get_user_home;
 use crate::fs::composite::ensure_parent_dir_exists;
+use crate::identity::delegation::DelegationChain;
+use crate::identity::ephemeral::EphemeralIdentitySource;
 use crate::identity::identity_file_locations::{IDENTITY_PEM, IdentityFileLocations};
 use crate::identity::identity_manager::IdentityStorageModeError::UnknownStorageMode;
 use crate::identity::{
-    ANONYMOUS_IDENTITY_NAME, IDENTITY_JSON, Identity as DfxIdentity, TEMP_IDENTITY_PREFIX,
-    pem_safekeeping, pem_utils,
+    ANONYMOUS_IDENTITY_NAME, IDENTITY_JSON, Identity as DfxIdentity, IdentityType,
+    TEMP_IDENTITY_PREFIX, pem_safekeeping, pem_utils,
 };
 use crate::json::{load_json_file, save_json_file};
 use bip32::XPrv;
@@ -92,6 +96,12 @@ fn default_identity() -> String {
 pub struct IdentityConfiguration {
     pub hsm: Option<HardwareIdentityConfiguration>,
 
+    /// If the identity's key is held by an external signer, this contains the command to run it.
+    pub external: Option<ExternalIdentityConfiguration>,
+
+    /// If the identity's key is a session key, this contains the delegation chain from the root identity to it.
+    pub delegation: Option<DelegationChain>,
+
     /// If the identity's PEM file is encrypted on disk this contains everything (except the password) to decrypt the file.
     pub encryption: Option<EncryptionConfiguration>,
 
@@ -144,6 +154,16 @@ pub struct HardwareIdentityConfiguration {
     pub key_id: String,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExternalIdentityConfiguration {
+    /// The program that gets the public key and signs on behalf of the identity.
+    pub command: String,
+
+    /// The arguments passed to the program.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Copy, PartialEq, Eq)]
 pub enum IdentityStorageMode {
     Keyring,
@@ -191,6 +211,14 @@ pub enum IdentityCreationParameters {
     Hardware {
         hsm: HardwareIdentityConfiguration,
     },
+    External {
+        external: ExternalIdentityConfiguration,
+    },
+    Delegated {
+        session_pem_content: Vec<u8>,
+        delegation: DelegationChain,
+        mode: IdentityStorageMode,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -243,7 +271,9 @@ impl IdentityManager {
             selected_identity_principal: None,
         };
 
-        if let Some(identity) = identity_override {
+        if let Some(identity) = identity_override
+            .filter(|identity| EphemeralIdentitySource::from_identity_name(identity).is_none())
+        {
             mgr.require_identity_exists(logger, identity)
                 .map_err(NewIdentityManagerError::OverrideIdentityMustExist)?;
         }
@@ -270,16 +300,20 @@ impl IdentityManager {
         identity_name: &str,
         log: &Logger,
     ) -> Result<Box<DfxIdentity>, InstantiateIdentityFromNameError> {
-        let identity = match identity_name {
-            ANONYMOUS_IDENTITY_NAME => Box::new(DfxIdentity::anonymous()),
-            identity_name => {
-                self.require_identity_exists(log, identity_name)
-                    .map_err(InstantiateIdentityFromNameError::RequireIdentityExistsFailed)?;
-                Box::new(
-                    self.load_identity(identity_name, log)
-                        .map_err(LoadIdentityFailed)?,
-                )
-            }
+        let identity = if identity_name == ANONYMOUS_IDENTITY_NAME {
+            Box::new(DfxIdentity::anonymous())
+        } else if let Some(source) = EphemeralIdentitySource::from_identity_name(identity_name) {
+            Box::new(
+                DfxIdentity::ephemeral(identity_name, &source)
+                    .map_err(|e| LoadEphemeralIdentityFailed(identity_name.to_string(), e))?,
+            )
+        } else {
+            self.require_identity_exists(log, identity_name)
+                .map_err(InstantiateIdentityFromNameError::RequireIdentityExistsFailed)?;
+            Box::new(
+                self.load_identity(identity_name, log)
+                    .map_err(LoadIdentityFailed)?,
+            )
         };
         use ic_agent::identity::Identity;
         self.selected_identity_principal =
@@ -423,6 +457,46 @@ impl IdentityManager {
                 crate::fs::create_dir_all(&temp_identity_dir)
                     .map_err(CreateTemporaryIdentityDirectoryFailed)?;
             }
+            IdentityCreationParameters::Delegated {
+                session_pem_content,
+                delegation,
+                mode,
+            } => {
+                pem_utils::validate_pem_file(&session_pem_content)
+                    .map_err(CreateNewIdentityError::ValidatePemFileFailed)?;
+                let session =
+                    DfxIdentity::secp256k1(name, &session_pem_content, IdentityType::Plaintext)
+                        .or_else(|e| {
+                            DfxIdentity::basic(name, &session_pem_content, IdentityType::Plaintext)
+                                .map_err(|_| e)
+                        })
+                        .map_err(CreateNewIdentityError::LoadPemIdentityFailed)?;
+                use ic_agent::identity::Identity;
+                if session.public_key().as_deref() != delegation.session_public_key() {
+                    return Err(DelegationChainDoesNotMatchKey());
+                }
+                identity_config = IdentityConfiguration {
+                    delegation: Some(delegation),
+                    ..create_identity_config(log, mode, name, None)
+                        .map_err(CreateNewIdentityError::CreateIdentityConfigFailed)?
+                };
+                pem_safekeeping::save_pem(
+                    log,
+                    self.file_locations(),
+                    &temp_identity_name,
+                    &identity_config,
+                    &session_pem_content,
+                )
+                .map_err(CreateNewIdentityError::SavePemFailed)?;
+            }
+            IdentityCreationParameters::External { external } => {
+                identity_config = IdentityConfiguration {
+                    external: Some(external),
+                    ..Default::default()
+                };
+                crate::fs::create_dir_all(&temp_identity_dir)
+                    .map_err(CreateTemporaryIdentityDirectoryFailed)?;
+            }
             IdentityCreationParameters::SeedPhrase { mnemonic, mode } => {
                 identity_config = create_identity_config(log, mode, name, None)
                     .map_err(CreateNewIdentityError::CreateIdentityConfigFailed)?;
@@ -484,6 +558,11 @@ impl IdentityManager {
         &self.selected_identity
     }
 
+    /// Return the name of the identity selected with `dfx identity use`
+    pub fn get_default_identity_name(&self) -> &str {
+        &self.configuration.default
+    }
+
     pub(crate) fn file_locations(&self) -> &IdentityFileLocations {
         &self.file_locations
     }
@@ -694,6 +773,8 @@ impl IdentityManager {
                         encryption: None,
                         keyring_identity_suffix: None,
                         hsm: None,
+                        external: None,
+                        delegation: _,
                     } = config
                     {
                         let sender = self.load_identity(name, log).ok()?.sender().ok()?;
@@ -852,7 +933,7 @@ fn remove_identity_file(file: &Path) -> Result<(), RemoveIdentityError> {
 }
 
 /// Generates a new secp256k1 key.
-pub(super) fn generate_key() -> Result<(Vec<u8>, Mnemonic), GenerateKeyError> {
+pub fn generate_key() -> Result<(Vec<u8>, Mnemonic), GenerateKeyError> {
     let mnemonic = Mnemonic::new(MnemonicType::for_key_size(256).unwrap(), Language::English);
     let secret =
         mnemonic_to_key(&mnemonic).map_err(GenerateKeyError::ConvertMnemonicToKeyFailed)?;
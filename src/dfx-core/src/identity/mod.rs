@@ -18,12 +18,18 @@ use crate::error::{
         MapWalletsToRenamedIdentityError::RenameWalletGlobalConfigKeyFailed,
         NewHardwareIdentityError,
         NewHardwareIdentityError::InstantiateHardwareIdentityFailed,
-        NewIdentityError, RenameWalletGlobalConfigKeyError,
+        NewIdentityError,
+        NewIdentityError::{
+            DelegationExpired, NewDelegatedIdentityFailed, NewExternalIdentityFailed,
+        },
+        RenameWalletGlobalConfigKeyError,
         RenameWalletGlobalConfigKeyError::RenameWalletFailed,
     },
     wallet_config::{WalletConfigError, WalletConfigError::LoadWalletConfigFailed},
 };
 use crate::fs::composite::ensure_parent_dir_exists;
+use crate::identity::delegation::DelegationChain;
+use crate::identity::external::ExternalIdentity;
 use crate::identity::identity_file_locations::IdentityFileLocations;
 use crate::identity::wallet::wallet_canister_id;
 use crate::json::{load_json_file, save_json_file};
@@ -31,23 +37,28 @@ use candid::Principal;
 use ic_agent::Signature;
 use ic_agent::agent::EnvelopeContent;
 use ic_agent::identity::{
-    AnonymousIdentity, BasicIdentity, Delegation, Secp256k1Identity, SignedDelegation,
+    AnonymousIdentity, BasicIdentity, DelegatedIdentity, Delegation, Secp256k1Identity,
+    SignedDelegation,
 };
 use ic_identity_hsm::HardwareIdentity;
 pub use identity_manager::{
-    HardwareIdentityConfiguration, IdentityConfiguration, IdentityCreationParameters,
-    IdentityManager,
+    ExternalIdentityConfiguration, HardwareIdentityConfiguration, IdentityConfiguration,
+    IdentityCreationParameters, IdentityManager,
 };
 use serde::{Deserialize, Serialize};
 use slog::{Logger, info};
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
+pub mod delegation;
+pub mod ephemeral;
+pub mod external;
 mod identity_file_locations;
 pub mod identity_manager;
 pub mod keyring_mock;
 pub mod pem_safekeeping;
 pub mod pem_utils;
+pub mod signature;
 pub mod wallet;
 
 pub const ANONYMOUS_IDENTITY_NAME: &str = "anonymous";
@@ -153,6 +164,43 @@ impl Identity {
         })
     }
 
+    fn external(
+        name: &str,
+        external: ExternalIdentityConfiguration,
+    ) -> Result<Self, NewIdentityError> {
+        let inner = Box::new(
+            ExternalIdentity::new(name, external)
+                .map_err(|e| NewExternalIdentityFailed(name.into(), e))?,
+        );
+        Ok(Self {
+            name: name.to_string(),
+            inner,
+            insecure: false,
+            identity_type: IdentityType::External,
+        })
+    }
+
+    /// Wraps the identity of a session key, so that it acts as the root identity of the delegation chain.
+    fn delegated(session: Identity, chain: DelegationChain) -> Result<Self, NewIdentityError> {
+        if chain.is_expired() {
+            return Err(DelegationExpired(session.name, chain.expiration_text()));
+        }
+        let inner = Box::new(
+            DelegatedIdentity::new(
+                chain.public_key.clone(),
+                session.inner,
+                chain.signed_delegations(),
+            )
+            .map_err(|e| NewDelegatedIdentityFailed(session.name.clone(), e))?,
+        );
+        Ok(Self {
+            name: session.name,
+            inner,
+            insecure: session.insecure,
+            identity_type: IdentityType::Delegated,
+        })
+    }
+
     pub(crate) fn new(
         name: &str,
         config: IdentityConfiguration,
@@ -161,13 +209,19 @@ impl Identity {
     ) -> Result<Self, NewIdentityError> {
         if let Some(hsm) = config.hsm {
             Identity::hardware(name, hsm).map_err(NewIdentityError::NewHardwareIdentityFailed)
+        } else if let Some(external) = config.external {
+            Identity::external(name, external)
         } else {
             let (pem_content, identity_type) =
                 pem_safekeeping::load_pem(log, locations, name, &config)
                     .map_err(NewIdentityError::LoadPemFailed)?;
-            Identity::secp256k1(name, &pem_content, identity_type)
+            let identity = Identity::secp256k1(name, &pem_content, identity_type)
                 .or_else(|e| Identity::basic(name, &pem_content, identity_type).map_err(|_| e))
-                .map_err(NewIdentityError::LoadPemIdentityFailed)
+                .map_err(NewIdentityError::LoadPemIdentityFailed)?;
+            match config.delegation {
+                Some(chain) => Identity::delegated(identity, chain),
+                None => Ok(identity),
+            }
         }
     }
 
@@ -326,6 +380,9 @@ pub enum IdentityType {
     EncryptedLocal,
     Hsm,
     Anonymous,
+    Ephemeral,
+    External,
+    Delegated,
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -8,11 +8,14 @@ use crate::error::{
     },
     identity::{
         LoadPemError,
-        LoadPemError::LoadFromKeyringFailed,
+        LoadPemError::{LoadFromKeyringFailed, NoPemForExternalIdentity},
         LoadPemFromFileError,
         LoadPemFromFileError::DecryptPemFileFailed,
         SavePemError,
-        SavePemError::{CannotSavePemContentForHsm, WritePemToKeyringFailed},
+        SavePemError::{
+            CannotSavePemContentForExternalIdentity, CannotSavePemContentForHsm,
+            WritePemToKeyringFailed,
+        },
         WritePemToFileError,
         WritePemToFileError::{EncryptPemFileFailed, WritePemContentFailed},
     },
@@ -35,6 +38,8 @@ pub(crate) fn load_pem(
 ) -> Result<(Vec<u8>, IdentityType), LoadPemError> {
     if identity_config.hsm.is_some() {
         unreachable!("Cannot load pem content for an HSM identity.")
+    } else if identity_config.external.is_some() {
+        Err(NoPemForExternalIdentity(identity_name.to_string()))
     } else if identity_config.keyring_identity_suffix.is_some() {
         debug!(
             log,
@@ -63,6 +68,8 @@ pub(crate) fn save_pem(
     );
     if identity_config.hsm.is_some() {
         Err(CannotSavePemContentForHsm())
+    } else if identity_config.external.is_some() {
+        Err(CannotSavePemContentForExternalIdentity())
     } else if let Some(keyring_identity) = &identity_config.keyring_identity_suffix {
         debug!(log, "Saving keyring identity.");
         keyring_mock::write_pem_to_keyring(keyring_identity, pem_content)
@@ -2,6 +2,7 @@ use crate::config::directories::get_user_dfx_config_dir;
 use crate::config::model::network_descriptor::{NetworkDescriptor, NetworkTypeDescriptor};
 use crate::error::wallet_config::WalletConfigError;
 use crate::error::wallet_config::WalletConfigError::GetWalletConfigPathFailed;
+use crate::identity::ephemeral::EphemeralIdentitySource;
 use crate::identity::{Identity, WALLET_CONFIG_FILENAME};
 use candid::Principal;
 use std::path::PathBuf;
@@ -12,6 +13,9 @@ pub fn get_wallet_config_path(
 ) -> Result<PathBuf, WalletConfigError> {
     Ok(match &network.r#type {
         NetworkTypeDescriptor::Persistent | NetworkTypeDescriptor::Playground { .. } => {
+            if EphemeralIdentitySource::from_identity_name(name).is_some() {
+                return Err(WalletConfigError::EphemeralIdentityWallet(name.to_string()));
+            }
             // Using the global
             get_user_dfx_config_dir()
                 .map_err(|e| {
@@ -35,7 +39,10 @@ pub fn wallet_canister_id(
     network: &NetworkDescriptor,
     name: &str,
 ) -> Result<Option<Principal>, WalletConfigError> {
-    let wallet_path = get_wallet_config_path(network, name)?;
+    let wallet_path = match get_wallet_config_path(network, name) {
+        Err(WalletConfigError::EphemeralIdentityWallet(_)) => return Ok(None),
+        wallet_path => wallet_path?,
+    };
     if !wallet_path.exists() {
         return Ok(None);
     }
@@ -14,11 +14,15 @@ use crate::{
     extension::manager::ExtensionManager,
     identity::{IdentityManager, identity_manager::InitializeIdentity},
     network::{
+        health::dynamic_route_provider,
         provider::{LocalBindDetermination, create_network_descriptor},
         root_key::fetch_root_key_when_non_mainnet_or_error,
     },
 };
-use ic_agent::{Agent, Identity, agent::route_provider::RoundRobinRouteProvider};
+use ic_agent::{
+    Agent, Identity,
+    agent::route_provider::{RoundRobinRouteProvider, RouteProvider},
+};
 use reqwest::Client;
 use semver::Version;
 use std::path::Path;
@@ -120,7 +124,9 @@ impl DfxInterfaceBuilder {
         let config = Config::from_current_dir(self.extension_manager.as_ref())?.map(Arc::new);
         let network_descriptor = self.build_network_descriptor(config.clone(), &networks_config)?;
         let identity = self.build_identity()?;
-        let agent = self.build_agent(identity.clone(), &network_descriptor)?;
+        let agent = self
+            .build_agent(identity.clone(), &network_descriptor.providers)
+            .await?;
 
         if fetch_root_key {
             fetch_root_key_when_non_mainnet_or_error(&agent, &network_descriptor).await?;
@@ -135,20 +141,33 @@ impl DfxInterfaceBuilder {
         })
     }
 
-    fn build_agent(
+    /// With several providers, requests are routed by the health and latency of the providers,
+    /// and requests that fail to connect are retried with the next provider.
+    async fn build_agent(
         &self,
         identity: Arc<dyn Identity>,
-        network_descriptor: &NetworkDescriptor,
+        providers: &[String],
     ) -> Result<Agent, BuildAgentError> {
-        let route_provider = RoundRobinRouteProvider::new(network_descriptor.providers.clone())
-            .map_err(BuildAgentError::CreateRouteProvider)?;
         let client = Client::builder()
             .use_rustls_tls()
             .build()
             .map_err(BuildAgentError::CreateHttpClient)?;
+        let dynamic_route_provider = if providers.len() > 1 {
+            dynamic_route_provider(providers, client.clone()).await
+        } else {
+            None
+        };
+        let route_provider: Arc<dyn RouteProvider> = match dynamic_route_provider {
+            Some(route_provider) => route_provider,
+            None => Arc::new(
+                RoundRobinRouteProvider::new(providers.to_vec())
+                    .map_err(BuildAgentError::CreateRouteProvider)?,
+            ),
+        };
         let agent = Agent::builder()
             .with_http_client(client)
-            .with_route_provider(route_provider)
+            .with_arc_route_provider(route_provider)
+            .with_max_tcp_error_retries(providers.len().saturating_sub(1))
             .with_arc_identity(identity)
             .build()
             .map_err(BuildAgentError::CreateAgent)?;
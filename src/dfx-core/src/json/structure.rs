@@ -75,7 +75,7 @@ impl DerefMut for VersionReqWithJsonSchema {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct VersionWithJsonSchema(#[schemars(with = "String")] pub Version);
 
@@ -93,7 +93,7 @@ impl DerefMut for VersionWithJsonSchema {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct UrlWithJsonSchema(#[schemars(with = "String")] pub Url);
 
@@ -0,0 +1,231 @@
+//! Health checks of the providers of a network.
+//!
+//! Networks can list several providers, e.g. several API boundary nodes.
+//! Agents route their requests with the dynamic routing of ic-agent, which checks the health of
+//! the providers in the background and prefers the healthy ones with the lowest latency.
+//! The checks in this module report the health of the providers to the user.
+use futures::future::join_all;
+use ic_agent::Agent;
+use ic_agent::agent::route_provider::RouteProvider;
+use ic_agent::agent::route_provider::dynamic_routing::dynamic_route_provider::DynamicRouteProviderBuilder;
+use ic_agent::agent::route_provider::dynamic_routing::node::Node;
+use ic_agent::agent::route_provider::dynamic_routing::snapshot::latency_based_routing::LatencyRoutingSnapshot;
+use reqwest::Client;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// How long to wait for the status of a provider before considering it unreachable.
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProviderStatus {
+    Healthy {
+        latency: Duration,
+    },
+
+    /// The provider responded, but the replica behind it is not healthy, e.g. still starting.
+    Unhealthy {
+        latency: Duration,
+        status: String,
+    },
+
+    Unreachable(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProviderHealth {
+    pub url: String,
+    pub status: ProviderStatus,
+}
+
+impl ProviderStatus {
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, ProviderStatus::Healthy { .. })
+    }
+}
+
+impl fmt::Display for ProviderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProviderStatus::Healthy { latency } => {
+                write!(f, "healthy ({} ms)", latency.as_millis())
+            }
+            ProviderStatus::Unhealthy { latency, status } => {
+                write!(f, "unhealthy: {status} ({} ms)", latency.as_millis())
+            }
+            ProviderStatus::Unreachable(reason) => write!(f, "unreachable: {reason}"),
+        }
+    }
+}
+
+impl fmt::Display for ProviderHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.url, self.status)
+    }
+}
+
+/// Reads the status of a provider, and measures how long it takes to respond.
+pub async fn check_provider(url: &str, timeout: Duration) -> ProviderHealth {
+    let start = Instant::now();
+    let status = match Agent::builder().with_url(url).build() {
+        Err(err) => ProviderStatus::Unreachable(err.to_string()),
+        Ok(agent) => match tokio::time::timeout(timeout, agent.status()).await {
+            Err(_) => {
+                ProviderStatus::Unreachable(format!("no response within {} s", timeout.as_secs()))
+            }
+            Ok(Err(err)) => ProviderStatus::Unreachable(err.to_string()),
+            Ok(Ok(status)) => {
+                let latency = start.elapsed();
+                match status.replica_health_status {
+                    None => ProviderStatus::Healthy { latency },
+                    Some(status) if status == "healthy" => ProviderStatus::Healthy { latency },
+                    Some(status) => ProviderStatus::Unhealthy { latency, status },
+                }
+            }
+        },
+    };
+    ProviderHealth {
+        url: url.to_string(),
+        status,
+    }
+}
+
+/// Checks all providers concurrently.
+pub async fn check_providers(providers: &[String], timeout: Duration) -> Vec<ProviderHealth> {
+    join_all(
+        providers
+            .iter()
+            .map(|provider| check_provider(provider, timeout)),
+    )
+    .await
+}
+
+/// Like `check_providers`, but for callers that are not async.
+/// The checks run on a separate thread, so this can also be called from within a runtime.
+pub fn check_providers_blocking(providers: &[String], timeout: Duration) -> Vec<ProviderHealth> {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("Unable to create a runtime")
+                    .block_on(check_providers(providers, timeout))
+            })
+            .join()
+            .expect("Provider health checks panicked.")
+    })
+}
+
+/// Creates a route provider that checks the health of `providers` in the background,
+/// and routes each request to a healthy provider, preferring the ones with the lowest latency.
+/// The providers are checked like API boundary nodes, so they must be HTTPS URLs without a path.
+/// Returns `None` if they are not, e.g. for a local network.
+pub async fn dynamic_route_provider(
+    providers: &[String],
+    client: Client,
+) -> Option<Arc<dyn RouteProvider>> {
+    let seeds = providers
+        .iter()
+        .map(|provider| {
+            let url = Url::parse(provider).ok()?;
+            if url.scheme() != "https" || url.path() != "/" || url.query().is_some() {
+                return None;
+            }
+            let host = url.host_str()?;
+            let domain = match url.port() {
+                Some(port) => format!("{host}:{port}"),
+                None => host.to_string(),
+            };
+            Node::new(domain).ok()
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let route_provider =
+        DynamicRouteProviderBuilder::new(LatencyRoutingSnapshot::new(), seeds, Arc::new(client))
+            .build()
+            .await;
+    Some(Arc::new(route_provider))
+}
+
+/// The providers to route requests to: the healthy ones, ordered by latency.
+/// If no provider is healthy, all of them in the configured order, so that requests still fail
+/// with the error of the provider rather than with a failed health check.
+pub fn preferred_providers(health: &[ProviderHealth]) -> Vec<String> {
+    let mut healthy: Vec<(Duration, &String)> = health
+        .iter()
+        .filter_map(|provider| match provider.status {
+            ProviderStatus::Healthy { latency } => Some((latency, &provider.url)),
+            _ => None,
+        })
+        .collect();
+    if healthy.is_empty() {
+        return health.iter().map(|provider| provider.url.clone()).collect();
+    }
+    healthy.sort_by_key(|(latency, _)| *latency);
+    healthy.into_iter().map(|(_, url)| url.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(url: &str, status: ProviderStatus) -> ProviderHealth {
+        ProviderHealth {
+            url: url.to_string(),
+            status,
+        }
+    }
+
+    #[test]
+    fn prefers_healthy_providers_by_latency() {
+        let health = vec![
+            provider(
+                "https://a",
+                ProviderStatus::Healthy {
+                    latency: Duration::from_millis(300),
+                },
+            ),
+            provider("https://b", ProviderStatus::Unreachable("down".to_string())),
+            provider(
+                "https://c",
+                ProviderStatus::Healthy {
+                    latency: Duration::from_millis(20),
+                },
+            ),
+            provider(
+                "https://d",
+                ProviderStatus::Unhealthy {
+                    latency: Duration::from_millis(10),
+                    status: "starting".to_string(),
+                },
+            ),
+        ];
+        assert_eq!(preferred_providers(&health), vec!["https://c", "https://a"]);
+    }
+
+    #[test]
+    fn falls_back_to_all_providers() {
+        let health = vec![
+            provider("https://a", ProviderStatus::Unreachable("down".to_string())),
+            provider("https://b", ProviderStatus::Unreachable("down".to_string())),
+        ];
+        assert_eq!(preferred_providers(&health), vec!["https://a", "https://b"]);
+    }
+
+    #[tokio::test]
+    async fn routes_only_https_providers_dynamically() {
+        for providers in [
+            vec!["http://127.0.0.1:4943", "https://icp-api.io"],
+            vec!["https://icp-api.io", "https://example.com/gateway"],
+        ] {
+            let providers: Vec<String> = providers.into_iter().map(String::from).collect();
+            assert!(
+                dynamic_route_provider(&providers, Client::new())
+                    .await
+                    .is_none()
+            );
+        }
+    }
+}
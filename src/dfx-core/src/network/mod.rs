@@ -1,3 +1,4 @@
 pub mod directory;
+pub mod health;
 pub mod provider;
 pub mod root_key;
@@ -6,6 +6,9 @@ use crate::lib::error::DfxResult;
 use crate::lib::models::canister::CanisterPool;
 use crate::lib::network::network_opt::NetworkOpt;
 use crate::lib::operations::canister::add_canisters_with_ids;
+use crate::lib::wasm::report::{WasmReport, WasmReportFormat};
+use anyhow::bail;
+use byte_unit::Byte;
 use clap::Parser;
 use std::path::PathBuf;
 use tokio::runtime::Runtime;
@@ -29,6 +32,22 @@ pub struct CanisterBuildOpts {
     #[arg(long)]
     output_env_file: Option<PathBuf>,
 
+    /// Prints a size and composition report of each built wasm module.
+    #[arg(long)]
+    report: bool,
+
+    /// The format of the report. In JSON, the reports of all built canisters are printed as one array.
+    #[arg(long, value_enum, default_value = "table", requires("report"))]
+    report_format: WasmReportFormat,
+
+    /// Fails if a gzipped wasm module is larger than this size, e.g. "1.5MiB".
+    #[arg(long, requires("report"))]
+    budget: Option<Byte>,
+
+    /// Records the hash of each wasm post-processing step. Used by `dfx canister verify`.
+    #[arg(long, hide = true)]
+    record_post_process_steps: bool,
+
     #[command(flatten)]
     network: NetworkOpt,
 }
@@ -54,7 +73,7 @@ pub fn exec(env: &dyn Environment, opts: CanisterBuildOpts) -> DfxResult {
         .get_canister_names_with_dependencies(opts.canister_name.as_deref())?;
     let canisters_to_load = add_canisters_with_ids(&required_canisters, &env, &config);
 
-    let canisters_to_build = required_canisters
+    let canisters_to_build: Vec<String> = required_canisters
         .into_iter()
         .filter(|canister_name| {
             !config
@@ -84,11 +103,42 @@ pub fn exec(env: &dyn Environment, opts: CanisterBuildOpts) -> DfxResult {
 
     let runtime = Runtime::new().expect("Unable to create a runtime");
     let build_config = BuildConfig::from_config(&config)?
-        .with_canisters_to_build(canisters_to_build)
-        .with_env_file(env_file);
+        .with_canisters_to_build(canisters_to_build.clone())
+        .with_env_file(env_file)
+        .with_record_post_process_steps(opts.record_post_process_steps);
     runtime.block_on(canister_pool.build_or_fail(&env, logger, &build_config))?;
 
     slog::info!(logger, "Finished building canisters.");
 
+    if opts.report {
+        let mut reports = vec![];
+        for canister in canister_pool.get_canister_list() {
+            let info = canister.get_info();
+            if !canisters_to_build
+                .iter()
+                .any(|name| name == info.get_name())
+            {
+                continue;
+            }
+            reports.push(WasmReport::from_file(
+                info.get_name(),
+                &info.get_build_wasm_path(),
+            )?);
+        }
+        WasmReport::print_all(&reports, opts.report_format)?;
+        let mut over_budget = false;
+        if let Some(budget) = opts.budget {
+            for report in &reports {
+                if let Err(err) = report.check_budget(budget) {
+                    slog::error!(logger, "{}", err);
+                    over_budget = true;
+                }
+            }
+        }
+        if over_budget {
+            bail!("Some wasm modules exceed the budget.");
+        }
+    }
+
     Ok(())
 }
@@ -4,6 +4,7 @@ use crate::lib::error::DfxResult;
 use crate::lib::operations::canister::get_canister_id_and_candid_path;
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::lib::telemetry::{CyclesHost, Telemetry};
+use crate::util::candid_json::BlobFormat;
 use crate::util::clap::argument_from_cli::ArgumentFromCliPositionalOpt;
 use crate::util::clap::parsers::cycle_amount_parser;
 use crate::util::{blob_from_arguments, fetch_remote_did_file, get_candid_type, print_idl_blob};
@@ -68,6 +69,10 @@ pub struct CanisterCallOpts {
         value_parser = ["idl", "raw", "pp", "json"])]
     output: Option<String>,
 
+    /// Specifies how blobs are encoded with `--output json`.
+    #[arg(long, value_enum, default_value = "hex", conflicts_with("async"))]
+    blob_format: BlobFormat,
+
     /// Specifies the amount of cycles to send on the call.
     /// Deducted from the wallet.
     /// Requires --wallet as a flag to `dfx canister`.
@@ -357,57 +362,7 @@ To figure out the id of your wallet, run 'dfx identity get-wallet (--network ic)
         }
     }
 
-    if is_query {
-        let blob = match call_sender {
-            CallSender::SelectedId => {
-                let query_builder = agent
-                    .query(&canister_id, method_name)
-                    .with_effective_canister_id(effective_canister_id)
-                    .with_arg(arg_value);
-                query_builder.call().await.context("Failed query call.")?
-            }
-            CallSender::Impersonate(sender) => {
-                let pocketic = env.get_pocketic();
-                if let Some(pocketic) = pocketic {
-                    pocketic
-                        .query_call_with_effective_principal(
-                            canister_id,
-                            RawEffectivePrincipal::CanisterId(
-                                effective_canister_id.as_slice().to_vec(),
-                            ),
-                            *sender,
-                            method_name,
-                            arg_value,
-                        )
-                        .await
-                        .map_err(|err| {
-                            anyhow!(
-                                "Failed to perform query call: {} ({})",
-                                err.reject_message,
-                                err.error_code
-                            )
-                        })?
-                } else {
-                    bail!("Impersonating sender is only supported for a local PocketIC instance.")
-                }
-            }
-            CallSender::Wallet(wallet_id) => {
-                let wallet = build_wallet_canister(*wallet_id, agent).await?;
-                do_wallet_call(
-                    &wallet,
-                    &CallIn {
-                        canister: canister_id,
-                        method_name: method_name.to_string(),
-                        args: arg_value,
-                        cycles,
-                    },
-                )
-                .await
-                .context("Failed wallet call.")?
-            }
-        };
-        print_idl_blob(&blob, output_type, &method_type)?;
-    } else if opts.r#async {
+    if opts.r#async {
         let call_response = match call_sender {
             CallSender::SelectedId => agent
                 .update(&canister_id, method_name)
@@ -461,11 +416,91 @@ To figure out the id of your wallet, run 'dfx identity get-wallet (--network ic)
                 println!("0x{}", String::from(request_id));
             }
             CallResponse::Response(response) => {
-                print_idl_blob(&response, output_type, &method_type)?;
+                print_idl_blob(&response, output_type, &method_type, opts.blob_format)?;
+            }
+        }
+    } else {
+        let blob = call_and_wait(
+            env,
+            call_sender,
+            canister_id,
+            effective_canister_id,
+            method_name,
+            arg_value,
+            is_query,
+            cycles,
+        )
+        .await?;
+        print_idl_blob(&blob, output_type, &method_type, opts.blob_format)?;
+    }
+
+    Ok(())
+}
+
+/// Sends a query or an update call and waits for the reply.
+#[allow(clippy::future_not_send)]
+pub(crate) async fn call_and_wait(
+    env: &dyn Environment,
+    call_sender: &CallSender,
+    canister_id: CanisterId,
+    effective_canister_id: CanisterId,
+    method_name: &str,
+    arg_value: Vec<u8>,
+    is_query: bool,
+    cycles: u128,
+) -> DfxResult<Vec<u8>> {
+    let agent = env.get_agent();
+    let blob = if is_query {
+        match call_sender {
+            CallSender::SelectedId => {
+                let query_builder = agent
+                    .query(&canister_id, method_name)
+                    .with_effective_canister_id(effective_canister_id)
+                    .with_arg(arg_value);
+                query_builder.call().await.context("Failed query call.")?
+            }
+            CallSender::Impersonate(sender) => {
+                let pocketic = env.get_pocketic();
+                if let Some(pocketic) = pocketic {
+                    pocketic
+                        .query_call_with_effective_principal(
+                            canister_id,
+                            RawEffectivePrincipal::CanisterId(
+                                effective_canister_id.as_slice().to_vec(),
+                            ),
+                            *sender,
+                            method_name,
+                            arg_value,
+                        )
+                        .await
+                        .map_err(|err| {
+                            anyhow!(
+                                "Failed to perform query call: {} ({})",
+                                err.reject_message,
+                                err.error_code
+                            )
+                        })?
+                } else {
+                    bail!("Impersonating sender is only supported for a local PocketIC instance.")
+                }
+            }
+            CallSender::Wallet(wallet_id) => {
+                let wallet = build_wallet_canister(*wallet_id, agent).await?;
+                do_wallet_call(
+                    &wallet,
+                    &CallIn {
+                        canister: canister_id,
+                        method_name: method_name.to_string(),
+                        args: arg_value,
+                        cycles,
+                    },
+                )
+                .await
+                .context("Failed wallet call.")?
             }
         }
     } else {
-        let blob = match call_sender {
+        match call_sender {
             CallSender::SelectedId => agent
                 .update(&canister_id, method_name)
                 .with_effective_canister_id(effective_canister_id)
@@ -518,9 +553,7 @@ To figure out the id of your wallet, run 'dfx identity get-wallet (--network ic)
                 .await
                 .context("Failed to do wallet call.")?
             }
-        };
-        print_idl_blob(&blob, output_type, &method_type)?;
-    }
-
-    Ok(())
+        }
+    };
+    Ok(blob)
 }
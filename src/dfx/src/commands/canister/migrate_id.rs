@@ -2,24 +2,38 @@ use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use crate::lib::ic_attributes::CanisterSettings;
 use crate::lib::operations::canister::{
-    get_canister_status, list_canister_snapshots, update_settings,
+    delete_canister_snapshot, get_canister_status, list_canister_snapshots, stop_canister,
+    update_settings,
 };
 use crate::lib::operations::canister_migration::{
     MigrationStatus, NNS_MIGRATION_CANISTER_ID, migrate_canister, migration_status,
 };
+use crate::lib::operations::cycles_ledger;
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::lib::subnet::get_subnet_for_canister;
-use crate::util::ask_for_consent;
+use crate::util::{ask_for_consent, format_as_trillions};
 use anyhow::{Context, bail};
 use candid::Principal;
 use clap::Parser;
+use dfx_core::error::cli::UserConsent;
 use dfx_core::identity::CallSender;
 use ic_management_canister_types::CanisterStatusType;
 use num_traits::ToPrimitive;
-use slog::{debug, error, info};
-use std::time::Duration;
+use slog::{debug, info, warn};
+use std::fmt;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use time::{OffsetDateTime, macros::format_description};
 
+/// The migrated canister needs at least this many cycles to pay for the migration.
+const MIN_MIGRATION_CYCLES: u128 = 10_000_000_000_000;
+/// Cycles above this amount are burned during the migration.
+const MAX_MIGRATION_CYCLES: u128 = 15_000_000_000_000;
+/// Added on top of the minimum when topping up, so that the idle burn of the stopped canister
+/// does not push it below the minimum again before the migration starts.
+const TOP_UP_MARGIN: u128 = 100_000_000_000;
+/// How long to wait for a freshly stopped canister to become ready for migration.
+const READY_FOR_MIGRATION_TIMEOUT: Duration = Duration::from_secs(120);
+
 /// Migrate a canister ID from one subnet to another.
 #[derive(Parser)]
 #[command(override_usage = "dfx canister migrate-id [OPTIONS] <CANISTER> --replace <REPLACE>")]
@@ -31,6 +45,11 @@ pub struct CanisterMigrateIdOpts {
     #[arg(long)]
     replace: String,
 
+    /// Only runs the pre-flight checks, and offers to fix the problems they find,
+    /// without starting the migration.
+    #[arg(long)]
+    check: bool,
+
     /// Skips yes/no checks by answering 'yes'. Not recommended outside of CI.
     #[arg(long, short)]
     yes: bool,
@@ -59,6 +78,65 @@ pub async fn exec(
         bail!("The canisters to migrate and replace are identical.");
     }
 
+    let migrated = MigrationCanister {
+        name: migrated_canister,
+        id: migrated_canister_id,
+    };
+    let replaced = MigrationCanister {
+        name: replaced_canister,
+        id: replaced_canister_id,
+    };
+
+    // Check all conditions the NNS migration canister validates up front,
+    // and offer to fix the ones dfx can fix.
+    let mut problems = preflight_checks(env, &migrated, &replaced, call_sender).await?;
+    print_problems(&problems);
+    // Declining a fix skips it; the remaining fixes are still offered.
+    let mut applied = false;
+    let mut unfixed = vec![];
+    for problem in &problems {
+        let Some(fix) = problem.fix() else {
+            continue;
+        };
+        if !opts.yes {
+            match ask_for_consent(env, &format!("{fix}?")) {
+                Ok(()) => {}
+                Err(UserConsent::Declined) => {
+                    unfixed.push(fix);
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        problem
+            .apply_fix(env, call_sender)
+            .await
+            .with_context(|| format!("Failed to fix problem: {problem}"))?;
+        info!(log, "{}.", fix);
+        applied = true;
+    }
+    for fix in &unfixed {
+        warn!(log, "Skipped: {}.", fix);
+    }
+    if applied {
+        // A canister only becomes ready for migration some time after it stopped.
+        wait_until_ready_for_migration(env, &migrated, call_sender).await?;
+        problems = preflight_checks(env, &migrated, &replaced, call_sender).await?;
+        print_problems(&problems);
+    }
+    if !problems.is_empty() {
+        bail!(
+            "Canister '{migrated_canister}' cannot be migrated until the problems above are resolved."
+        );
+    }
+
+    if opts.check {
+        println!(
+            "Canister '{migrated_canister}' is ready to replace canister '{replaced_canister}'."
+        );
+        return Ok(());
+    }
+
     if !opts.yes {
         ask_for_consent(
             env,
@@ -71,31 +149,12 @@ pub async fn exec(
     let migrated_canister_status = get_canister_status(env, migrated_canister_id, call_sender)
         .await
         .with_context(|| format!("Could not retrieve status of canister {migrated_canister}"))?;
-    let replaced_canister_status = get_canister_status(env, replaced_canister_id, call_sender)
-        .await
-        .with_context(|| format!("Could not retrieve status of canister {replaced_canister}"))?;
-
-    // Check that the two canisters are stopped.
-    ensure_canister_stopped(migrated_canister_status.status, migrated_canister)?;
-    ensure_canister_stopped(replaced_canister_status.status, replaced_canister)?;
-
-    // Check that the canister is ready for migration.
-    if !migrated_canister_status.ready_for_migration {
-        bail!(
-            "Canister '{migrated_canister}' is not ready for migration. Wait a few seconds and try again"
-        );
-    }
-
-    // Check the cycles balance of migrated canister.
     let cycles = migrated_canister_status
         .cycles
         .0
         .to_u128()
         .context("Unable to parse cycles")?;
-    if cycles < 10_000_000_000_000 {
-        bail!("Canister '{migrated_canister}' has less than 10T cycles");
-    }
-    if !opts.yes && cycles > 15_000_000_000_000 {
+    if !opts.yes && cycles > MAX_MIGRATION_CYCLES {
         ask_for_consent(
             env,
             &format!(
@@ -104,59 +163,6 @@ pub async fn exec(
         )?;
     }
 
-    // Check that the replaced canister has no snapshots.
-    let snapshots = list_canister_snapshots(env, replaced_canister_id, call_sender).await?;
-    if !snapshots.is_empty() {
-        bail!(
-            "The canister '{replaced_canister}' whose canister ID will be replaced has snapshots"
-        );
-    }
-
-    // Check that the two canisters are on different subnets.
-    let migrated_canister_subnet = get_subnet_for_canister(agent, migrated_canister_id).await?;
-    let replaced_canister_subnet = get_subnet_for_canister(agent, replaced_canister_id).await?;
-    if migrated_canister_subnet == replaced_canister_subnet {
-        bail!(
-            "The canisters '{migrated_canister}' and '{replaced_canister}' are on the same subnet"
-        );
-    }
-
-    // Add the NNS migration canister as a controller to the migrated canister.
-    let mut controllers = migrated_canister_status.settings.controllers.clone();
-    if !controllers.contains(&NNS_MIGRATION_CANISTER_ID) {
-        controllers.push(NNS_MIGRATION_CANISTER_ID);
-        let settings = CanisterSettings {
-            controllers: Some(controllers),
-            compute_allocation: None,
-            memory_allocation: None,
-            freezing_threshold: None,
-            reserved_cycles_limit: None,
-            wasm_memory_limit: None,
-            wasm_memory_threshold: None,
-            log_visibility: None,
-            environment_variables: None,
-        };
-        update_settings(env, migrated_canister_id, settings, call_sender).await?;
-    }
-
-    // Add the NNS migration canister as a controller to the replaced canister.
-    let mut controllers = replaced_canister_status.settings.controllers.clone();
-    if !controllers.contains(&NNS_MIGRATION_CANISTER_ID) {
-        controllers.push(NNS_MIGRATION_CANISTER_ID);
-        let settings = CanisterSettings {
-            controllers: Some(controllers),
-            compute_allocation: None,
-            memory_allocation: None,
-            freezing_threshold: None,
-            reserved_cycles_limit: None,
-            wasm_memory_limit: None,
-            wasm_memory_threshold: None,
-            log_visibility: None,
-            environment_variables: None,
-        };
-        update_settings(env, replaced_canister_id, settings, call_sender).await?;
-    }
-
     // Migrate the from canister to the rename_to canister.
     debug!(
         log,
@@ -179,13 +185,7 @@ pub async fn exec(
                 }
                 Some(MigrationStatus::Failed { reason, time }) => {
                     spinner.finish_and_clear();
-                    error!(
-                        log,
-                        "Migration failed at {}: {}",
-                        format_time(&time),
-                        reason
-                    );
-                    break;
+                    bail!("Migration failed at {}: {}", format_time(&time), reason);
                 }
                 None => (),
             },
@@ -197,19 +197,286 @@ pub async fn exec(
         tokio::time::sleep(Duration::from_secs(1)).await;
     }
 
-    canister_id_store.remove(log, replaced_canister)?;
+    // The migrated canister now has the canister ID of the replaced canister.
+    if let Some(name) = canister_id_store.get_name(&replaced_canister_id.to_text()) {
+        canister_id_store.remove(log, &name)?;
+    }
+    if let Some(name) = canister_id_store.get_name(&migrated_canister_id.to_text()) {
+        canister_id_store.add(log, &name, &replaced_canister_id.to_text(), None)?;
+        info!(
+            log,
+            "Canister '{}' now has canister ID {}.", name, replaced_canister_id
+        );
+    }
 
     Ok(())
 }
 
-fn ensure_canister_stopped(status: CanisterStatusType, canister: &str) -> DfxResult {
-    match status {
-        CanisterStatusType::Stopped => Ok(()),
-        CanisterStatusType::Running => {
-            bail!("Canister {canister} is running. Run 'dfx canister stop' first");
+struct MigrationCanister<'a> {
+    name: &'a str,
+    id: Principal,
+}
+
+/// A condition under which the NNS migration canister would reject the migration.
+enum Problem {
+    NotStopped {
+        canister: String,
+        canister_id: Principal,
+        status: CanisterStatusType,
+    },
+    NotReady {
+        canister: String,
+    },
+    InsufficientCycles {
+        canister: String,
+        canister_id: Principal,
+        balance: u128,
+    },
+    HasSnapshots {
+        canister: String,
+        canister_id: Principal,
+        snapshot_ids: Vec<Vec<u8>>,
+    },
+    NnsNotController {
+        canister: String,
+        canister_id: Principal,
+        controllers: Vec<Principal>,
+    },
+    SameSubnet,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::NotStopped {
+                canister, status, ..
+            } => match status {
+                CanisterStatusType::Stopping => write!(f, "Canister '{canister}' is stopping."),
+                _ => write!(f, "Canister '{canister}' is running."),
+            },
+            Problem::NotReady { canister } => write!(
+                f,
+                "Canister '{canister}' is not ready for migration. Wait a few seconds and try again."
+            ),
+            Problem::InsufficientCycles {
+                canister, balance, ..
+            } => write!(
+                f,
+                "Canister '{canister}' has {} TC, but the migration requires at least {} TC.",
+                format_as_trillions(*balance),
+                format_as_trillions(MIN_MIGRATION_CYCLES)
+            ),
+            Problem::HasSnapshots {
+                canister,
+                snapshot_ids,
+                ..
+            } => write!(
+                f,
+                "Canister '{canister}' whose canister ID will be replaced has {} snapshot(s).",
+                snapshot_ids.len()
+            ),
+            Problem::NnsNotController { canister, .. } => write!(
+                f,
+                "The NNS migration canister {NNS_MIGRATION_CANISTER_ID} is not a controller of canister '{canister}'."
+            ),
+            Problem::SameSubnet => write!(f, "Both canisters are on the same subnet."),
         }
-        CanisterStatusType::Stopping => {
-            bail!("Canister {canister} is stopping. Wait a few seconds and try again");
+    }
+}
+
+impl Problem {
+    /// Describes how dfx can fix the problem, if it can.
+    fn fix(&self) -> Option<String> {
+        match self {
+            Problem::NotStopped { canister, .. } => Some(format!("Stop canister '{canister}'")),
+            Problem::InsufficientCycles { canister, .. } => Some(format!(
+                "Top up canister '{canister}' from the cycles ledger"
+            )),
+            Problem::HasSnapshots { canister, .. } => {
+                Some(format!("Delete the snapshots of canister '{canister}'"))
+            }
+            Problem::NnsNotController { canister, .. } => Some(format!(
+                "Add the NNS migration canister as a controller of canister '{canister}'"
+            )),
+            Problem::NotReady { .. } | Problem::SameSubnet => None,
+        }
+    }
+
+    async fn apply_fix(&self, env: &dyn Environment, call_sender: &CallSender) -> DfxResult {
+        match self {
+            Problem::NotStopped { canister_id, .. } => {
+                stop_canister(env, *canister_id, call_sender).await
+            }
+            Problem::InsufficientCycles {
+                canister_id,
+                balance,
+                ..
+            } => {
+                let amount = MIN_MIGRATION_CYCLES - balance + TOP_UP_MARGIN;
+                let created_at_time = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_nanos() as u64;
+                cycles_ledger::withdraw(
+                    env.get_agent(),
+                    env.get_logger(),
+                    *canister_id,
+                    amount,
+                    created_at_time,
+                    None,
+                )
+                .await?;
+                Ok(())
+            }
+            Problem::HasSnapshots {
+                canister_id,
+                snapshot_ids,
+                ..
+            } => {
+                for snapshot_id in snapshot_ids {
+                    delete_canister_snapshot(env, *canister_id, snapshot_id, call_sender).await?;
+                }
+                Ok(())
+            }
+            Problem::NnsNotController {
+                canister_id,
+                controllers,
+                ..
+            } => {
+                let mut controllers = controllers.clone();
+                controllers.push(NNS_MIGRATION_CANISTER_ID);
+                let settings = CanisterSettings {
+                    controllers: Some(controllers),
+                    ..Default::default()
+                };
+                update_settings(env, *canister_id, settings, call_sender).await
+            }
+            Problem::NotReady { .. } | Problem::SameSubnet => unreachable!(),
+        }
+    }
+}
+
+/// Checks the conditions the NNS migration canister validates before accepting a migration.
+async fn preflight_checks(
+    env: &dyn Environment,
+    migrated: &MigrationCanister<'_>,
+    replaced: &MigrationCanister<'_>,
+    call_sender: &CallSender,
+) -> DfxResult<Vec<Problem>> {
+    let agent = env.get_agent();
+    let mut problems = vec![];
+
+    let migrated_status = get_canister_status(env, migrated.id, call_sender)
+        .await
+        .with_context(|| format!("Could not retrieve status of canister {}", migrated.name))?;
+    let replaced_status = get_canister_status(env, replaced.id, call_sender)
+        .await
+        .with_context(|| format!("Could not retrieve status of canister {}", replaced.name))?;
+
+    // Both canisters must be stopped, and the migrated canister must be ready for migration.
+    for (canister, status) in [(migrated, &migrated_status), (replaced, &replaced_status)] {
+        if status.status != CanisterStatusType::Stopped {
+            problems.push(Problem::NotStopped {
+                canister: canister.name.to_string(),
+                canister_id: canister.id,
+                status: status.status,
+            });
+        }
+    }
+    if migrated_status.status == CanisterStatusType::Stopped && !migrated_status.ready_for_migration
+    {
+        problems.push(Problem::NotReady {
+            canister: migrated.name.to_string(),
+        });
+    }
+
+    // The migrated canister pays for the migration.
+    let balance = migrated_status
+        .cycles
+        .0
+        .to_u128()
+        .context("Unable to parse cycles")?;
+    if balance < MIN_MIGRATION_CYCLES {
+        problems.push(Problem::InsufficientCycles {
+            canister: migrated.name.to_string(),
+            canister_id: migrated.id,
+            balance,
+        });
+    }
+
+    // The replaced canister must not have snapshots.
+    let snapshots = list_canister_snapshots(env, replaced.id, call_sender).await?;
+    if !snapshots.is_empty() {
+        problems.push(Problem::HasSnapshots {
+            canister: replaced.name.to_string(),
+            canister_id: replaced.id,
+            snapshot_ids: snapshots.into_iter().map(|snapshot| snapshot.id).collect(),
+        });
+    }
+
+    // The NNS migration canister must control both canisters.
+    for (canister, status) in [(migrated, &migrated_status), (replaced, &replaced_status)] {
+        let controllers = &status.settings.controllers;
+        if !controllers.contains(&NNS_MIGRATION_CANISTER_ID) {
+            problems.push(Problem::NnsNotController {
+                canister: canister.name.to_string(),
+                canister_id: canister.id,
+                controllers: controllers.clone(),
+            });
+        }
+    }
+
+    // The two canisters must be on different subnets.
+    let migrated_subnet = get_subnet_for_canister(agent, migrated.id).await?;
+    let replaced_subnet = get_subnet_for_canister(agent, replaced.id).await?;
+    if migrated_subnet == replaced_subnet {
+        problems.push(Problem::SameSubnet);
+    }
+
+    Ok(problems)
+}
+
+/// Waits until the migrated canister reports that it is ready for migration, if it is stopped.
+/// Gives up after `READY_FOR_MIGRATION_TIMEOUT`, leaving it to the pre-flight checks to report it.
+async fn wait_until_ready_for_migration(
+    env: &dyn Environment,
+    migrated: &MigrationCanister<'_>,
+    call_sender: &CallSender,
+) -> DfxResult {
+    let spinner = env.new_spinner(
+        format!(
+            "Waiting for canister '{}' to be ready for migration...",
+            migrated.name
+        )
+        .into(),
+    );
+    let start = Instant::now();
+    loop {
+        let status = get_canister_status(env, migrated.id, call_sender)
+            .await
+            .with_context(|| format!("Could not retrieve status of canister {}", migrated.name))?;
+        if status.status != CanisterStatusType::Stopped
+            || status.ready_for_migration
+            || start.elapsed() > READY_FOR_MIGRATION_TIMEOUT
+        {
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    spinner.finish_and_clear();
+    Ok(())
+}
+
+fn print_problems(problems: &[Problem]) {
+    if problems.is_empty() {
+        println!("All pre-flight checks passed.");
+        return;
+    }
+    println!("Pre-flight checks found {} problem(s):", problems.len());
+    for problem in problems {
+        match problem.fix() {
+            Some(fix) => println!("  - {problem} Fix: {fix}."),
+            None => println!("  - {problem}"),
         }
     }
 }
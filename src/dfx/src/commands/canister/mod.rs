@@ -18,8 +18,10 @@ mod metadata;
 mod migrate_id;
 mod migration_status;
 mod request_status;
+mod run_script;
 mod send;
 mod set_id;
+mod settings;
 mod sign;
 mod snapshot;
 mod start;
@@ -28,6 +30,8 @@ mod stop;
 mod uninstall_code;
 mod update_settings;
 mod url;
+mod verify;
+mod wasm_report;
 
 /// Manages canisters deployed on a network replica.
 #[derive(Parser)]
@@ -58,8 +62,10 @@ pub enum SubCommand {
     MigrateId(migrate_id::CanisterMigrateIdOpts),
     MigrationStatus(migration_status::CanisterMigrationStatusOpts),
     RequestStatus(request_status::RequestStatusOpts),
+    RunScript(run_script::CanisterRunScriptOpts),
     Send(send::CanisterSendOpts),
     SetId(set_id::CanisterSetIdOpts),
+    Settings(settings::SettingsOpts),
     Sign(sign::CanisterSignOpts),
     Snapshot(snapshot::SnapshotOpts),
     Start(start::CanisterStartOpts),
@@ -69,6 +75,8 @@ pub enum SubCommand {
     UpdateSettings(update_settings::UpdateSettingsOpts),
     Logs(logs::LogsOpts),
     Url(url::CanisterUrlOpts),
+    Verify(verify::CanisterVerifyOpts),
+    WasmReport(wasm_report::WasmReportOpts),
 }
 
 pub fn exec(env: &dyn Environment, opts: CanisterOpts) -> DfxResult {
@@ -95,8 +103,10 @@ pub fn exec(env: &dyn Environment, opts: CanisterOpts) -> DfxResult {
             SubCommand::MigrateId(v) => migrate_id::exec(env, v, &call_sender()?).await,
             SubCommand::MigrationStatus(v) => migration_status::exec(env, v).await,
             SubCommand::RequestStatus(v) => request_status::exec(env, v).await,
+            SubCommand::RunScript(v) => run_script::exec(env, v, &call_sender()?).await,
             SubCommand::Send(v) => send::exec(env, v, &call_sender()?).await,
             SubCommand::SetId(v) => set_id::exec(env, v).await,
+            SubCommand::Settings(v) => settings::exec(env, v, &call_sender()?).await,
             SubCommand::Sign(v) => sign::exec(env, v, &call_sender()?).await,
             SubCommand::Snapshot(v) => snapshot::exec(env, v, &call_sender()?).await,
             SubCommand::Start(v) => start::exec(env, v, &call_sender()?).await,
@@ -106,6 +116,8 @@ pub fn exec(env: &dyn Environment, opts: CanisterOpts) -> DfxResult {
             SubCommand::UpdateSettings(v) => update_settings::exec(env, v, &call_sender()?).await,
             SubCommand::Logs(v) => logs::exec(env, v, &call_sender()?).await,
             SubCommand::Url(v) => url::exec(env, v).await,
+            SubCommand::Verify(v) => verify::exec(env, v).await,
+            SubCommand::WasmReport(v) => wasm_report::exec(env, v).await,
         }
     })
 }
@@ -1,15 +1,19 @@
 use crate::lib::environment::Environment;
 use crate::lib::error::{DfxError, DfxResult};
 use crate::lib::root_key::fetch_root_key_if_needed;
+use crate::util::candid_json::BlobFormat;
 use crate::util::clap::parsers;
-use crate::util::print_idl_blob;
+use crate::util::{fetch_remote_did_file, get_candid_type, print_idl_blob};
 use anyhow::Context;
 use backoff::ExponentialBackoff;
 use backoff::backoff::Backoff;
 use candid::Principal;
+use candid_parser::utils::CandidSource;
 use clap::Parser;
 use ic_agent::agent::RequestStatusResponse;
 use ic_agent::{AgentError, RequestId};
+use slog::warn;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 /// Requests the status of a call from a canister.
@@ -29,8 +33,22 @@ pub struct RequestStatusOpts {
     canister: String,
 
     /// Specifies the format for displaying the method's return result.
-    #[arg(long, value_parser = ["idl", "raw", "pp"])]
+    #[arg(long, value_parser = ["idl", "raw", "pp", "json"])]
     output: Option<String>,
+
+    /// Specifies how blobs are encoded with `--output json`.
+    #[arg(long, value_enum, default_value = "hex")]
+    blob_format: BlobFormat,
+
+    /// The method that the request called. Its result types are used to decode the response,
+    /// e.g. for the field names with `--output json`.
+    #[arg(long)]
+    method: Option<String>,
+
+    /// Provide the .did file with which to decode the response. Requires `--method`.
+    /// By default, the Candid interface is read from the canister metadata.
+    #[arg(long, requires("method"))]
+    candid: Option<PathBuf>,
 }
 
 pub async fn exec(env: &dyn Environment, opts: RequestStatusOpts) -> DfxResult {
@@ -89,7 +107,20 @@ pub async fn exec(env: &dyn Environment, opts: RequestStatusOpts) -> DfxResult {
     }
     .await?;
 
+    let method_type = match (&opts.method, &opts.candid) {
+        (Some(method), Some(path)) => get_candid_type(CandidSource::File(path), method),
+        (Some(method), None) => fetch_remote_did_file(agent, canister_id)
+            .await
+            .and_then(|did| get_candid_type(CandidSource::Text(&did), method)),
+        (None, _) => None,
+    };
+    if let (Some(method), None) = (&opts.method, &method_type) {
+        warn!(
+            env.get_logger(),
+            "Cannot fetch Candid interface for {method}, decoding the response without types."
+        );
+    }
     let output_type = opts.output.as_deref();
-    print_idl_blob(&blob, output_type, &None)?;
+    print_idl_blob(&blob, output_type, &method_type, opts.blob_format)?;
     Ok(())
 }
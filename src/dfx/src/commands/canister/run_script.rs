@@ -0,0 +1,268 @@
+use crate::commands::canister::call::{call_and_wait, get_effective_canister_id};
+use crate::lib::environment::{AgentEnvironment, Environment};
+use crate::lib::error::DfxResult;
+use crate::lib::operations::canister::get_canister_id_and_candid_path;
+use crate::lib::root_key::fetch_root_key_if_needed;
+use crate::lib::scenario::{
+    ArgumentType, Outcome, Scenario, Step, TestReport, Variables, check_assertions,
+};
+use crate::util::candid_json::{BlobFormat, idl_args_to_json};
+use crate::util::{blob_from_arguments, fetch_remote_did_file, get_candid_type, print_idl_blob};
+use anyhow::{Context, anyhow, bail};
+use candid::types::{Type, TypeEnv, TypeInner};
+use candid::{IDLArgs, Principal};
+use candid_parser::error::pretty_wrap;
+use candid_parser::utils::CandidSource;
+use clap::Parser;
+use dfx_core::identity::CallSender;
+use dfx_core::util::expiry_duration;
+use ic_utils::interfaces::management_canister::MgmtMethod;
+use serde_json::Value;
+use slog::{info, warn};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Runs a sequence of canister calls from a YAML or JSON scenario file.
+/// Each step can check the result of its call and capture values for later steps.
+#[derive(Parser)]
+pub struct CanisterRunScriptOpts {
+    /// The scenario file. Files with the `.json` extension are read as JSON, all others as YAML.
+    file: PathBuf,
+
+    /// Writes a JUnit XML report of the steps to the specified file.
+    #[arg(long)]
+    junit: Option<PathBuf>,
+
+    /// Stops at the first failed step. The remaining steps are reported as skipped.
+    #[arg(long)]
+    fail_fast: bool,
+
+    /// Specifies the format for displaying the results of the calls.
+    #[arg(long, value_parser = ["idl", "raw", "pp", "json"])]
+    output: Option<String>,
+
+    /// Specifies how blobs are encoded with `--output json`, in assertions and in captured values.
+    #[arg(long, value_enum, default_value = "hex")]
+    blob_format: BlobFormat,
+}
+
+#[allow(clippy::future_not_send)]
+pub async fn exec(
+    env: &dyn Environment,
+    opts: CanisterRunScriptOpts,
+    call_sender: &CallSender,
+) -> DfxResult {
+    let log = env.get_logger();
+    fetch_root_key_if_needed(env).await?;
+
+    let scenario = Scenario::load(&opts.file)?;
+    let suite = scenario.name.clone().unwrap_or_else(|| {
+        opts.file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    });
+    let mut variables = Variables::new(scenario.variables.clone());
+    let mut identity_envs = BTreeMap::new();
+    let mut report = TestReport::new(suite);
+    for step in &scenario.steps {
+        let name = step.display_name();
+        if opts.fail_fast && report.failures() > 0 {
+            report.push(name, Duration::ZERO, Outcome::Skipped);
+            continue;
+        }
+        info!(log, "Running step '{name}'.");
+        let start = Instant::now();
+        let result = run_step(
+            env,
+            &mut identity_envs,
+            step,
+            &mut variables,
+            call_sender,
+            &opts,
+        )
+        .await;
+        let outcome = match result {
+            Ok(()) => Outcome::Passed,
+            Err(err) => {
+                let message = format!("{err:#}");
+                warn!(log, "Step '{name}' failed: {message}");
+                Outcome::Failed(message)
+            }
+        };
+        report.push(name, start.elapsed(), outcome);
+    }
+
+    if let Some(junit) = &opts.junit {
+        dfx_core::fs::write(junit, report.to_junit_xml())?;
+    }
+    let steps = scenario.steps.len();
+    match report.failures() {
+        0 => {
+            info!(log, "All {steps} steps passed.");
+            Ok(())
+        }
+        failures => bail!("{failures} of {steps} steps failed."),
+    }
+}
+
+#[allow(clippy::future_not_send)]
+async fn run_step<'a>(
+    env: &'a dyn Environment,
+    identity_envs: &mut BTreeMap<String, AgentEnvironment<'a>>,
+    step: &Step,
+    variables: &mut Variables,
+    call_sender: &CallSender,
+    opts: &CanisterRunScriptOpts,
+) -> DfxResult {
+    let (step_env, call_sender): (&dyn Environment, CallSender) =
+        match (&step.impersonate, &step.identity) {
+            (Some(principal), _) => {
+                let principal = Principal::from_text(variables.substitute_text(principal)?)
+                    .context("Invalid principal to impersonate.")?;
+                (env, CallSender::Impersonate(principal))
+            }
+            (None, Some(identity)) => {
+                if !identity_envs.contains_key(identity) {
+                    let identity_env = AgentEnvironment::new(
+                        env,
+                        env.get_network_descriptor().clone(),
+                        expiry_duration(),
+                        Some(identity),
+                    )?;
+                    fetch_root_key_if_needed(&identity_env).await?;
+                    identity_envs.insert(identity.clone(), identity_env);
+                }
+                (&identity_envs[identity], CallSender::SelectedId)
+            }
+            (None, None) => (env, *call_sender),
+        };
+
+    let method_name = step.method.as_str();
+    let canister = variables.substitute_text(&step.canister)?;
+    let (canister_id, maybe_local_candid_path) =
+        get_canister_id_and_candid_path(step_env, &canister)?;
+    let method_type = if let Some(path) = &step.candid {
+        get_candid_type(CandidSource::File(path), method_name)
+    } else if let Some(did) = fetch_remote_did_file(step_env.get_agent(), canister_id).await {
+        get_candid_type(CandidSource::Text(&did), method_name)
+    } else {
+        maybe_local_candid_path
+            .and_then(|path| get_candid_type(CandidSource::File(&path), method_name))
+    };
+
+    let (argument, argument_type) = match &step.args {
+        None => (None, step.r#type),
+        Some(Value::String(text)) => (Some(variables.substitute_text(text)?), step.r#type),
+        Some(value) => {
+            if matches!(step.r#type, Some(ArgumentType::Idl | ArgumentType::Raw)) {
+                bail!(
+                    "A structured argument can only be passed as JSON. Pass Candid or raw arguments as strings."
+                );
+            }
+            let value = variables.substitute_value(value)?;
+            (Some(value.to_string()), Some(ArgumentType::Json))
+        }
+    };
+    // Without an argument, `blob_from_arguments` would ask for one interactively.
+    let requires_argument = method_type.as_ref().is_some_and(|(_, func)| {
+        !func
+            .args
+            .iter()
+            .all(|t| matches!(t.as_ref(), TypeInner::Opt(_)))
+    });
+    if argument.is_none() && requires_argument {
+        bail!("The method {method_name} requires arguments.");
+    }
+    let arg_value = blob_from_arguments(
+        Some(step_env),
+        argument.as_deref(),
+        None,
+        argument_type.map(|t| t.as_str()),
+        &method_type,
+        false,
+        false,
+    )?;
+
+    let effective_canister_id = if canister_id == Principal::management_canister() {
+        let management_method = MgmtMethod::from_str(method_name).map_err(|_| {
+            anyhow!("Attempted to call an unsupported management canister method: {method_name}")
+        })?;
+        get_effective_canister_id(&management_method, &arg_value)?
+    } else {
+        canister_id
+    };
+    let is_query = step.query || method_type.as_ref().is_some_and(|(_, f)| f.is_query());
+
+    let result = call_and_wait(
+        step_env,
+        &call_sender,
+        canister_id,
+        effective_canister_id,
+        method_name,
+        arg_value,
+        is_query,
+        0,
+    )
+    .await;
+    let blob = match (result, &step.expect_error) {
+        (Ok(blob), None) => blob,
+        (Ok(_), Some(expected)) => {
+            bail!("Expected the call to fail with '{expected}', but it succeeded.")
+        }
+        (Err(err), Some(expected)) => {
+            let message = format!("{err:#}");
+            if message.contains(expected.as_str()) {
+                return Ok(());
+            }
+            bail!("Expected the call to fail with '{expected}', but it failed with: {message}")
+        }
+        (Err(err), None) => return Err(err),
+    };
+    print_idl_blob(
+        &blob,
+        opts.output.as_deref(),
+        &method_type,
+        opts.blob_format,
+    )?;
+
+    let rets = method_type
+        .as_ref()
+        .map(|(env, func)| (env, func.rets.as_slice()));
+    if let Some(expected) = &step.expect {
+        let Some((env, rets)) = rets else {
+            bail!(
+                "Cannot check the expected result, because the Candid types of {method_name} are unknown. Use `assert` instead."
+            );
+        };
+        check_expected_result(&blob, &variables.substitute_text(expected)?, env, rets)?;
+    }
+    if !step.assert.is_empty() || !step.capture.is_empty() {
+        let args = match rets {
+            Some((env, rets)) => IDLArgs::from_bytes_with_types(&blob, env, rets),
+            None => IDLArgs::from_bytes(&blob),
+        }
+        .context("Failed to decode the result.")?;
+        let json = Value::Array(idl_args_to_json(&args, rets, opts.blob_format));
+        check_assertions(&step.assert, &json)?;
+        variables.capture(&step.capture, &json)?;
+    }
+    Ok(())
+}
+
+/// Both the result and the expected values are encoded with the result types,
+/// so that they compare equal regardless of how the canister encoded the result.
+fn check_expected_result(blob: &[u8], expected: &str, env: &TypeEnv, rets: &[Type]) -> DfxResult {
+    let expected = pretty_wrap("Candid value", expected, candid_parser::parse_idl_args)
+        .map_err(|e| anyhow!("Invalid expected Candid values: {e}"))?
+        .annotate_types(true, env, rets)
+        .context("The expected values do not match the result types of the method.")?;
+    let actual =
+        IDLArgs::from_bytes_with_types(blob, env, rets).context("Failed to decode the result.")?;
+    if expected.to_bytes_with_types(env, rets)? != actual.to_bytes_with_types(env, rets)? {
+        bail!("Expected {expected}, got {actual}.");
+    }
+    Ok(())
+}
@@ -1,8 +1,11 @@
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use crate::lib::sign::signed_message::SignedMessageV1;
+use crate::util::candid_json::BlobFormat;
+use crate::util::{fetch_remote_did_file, get_candid_type, print_idl_blob};
 use anyhow::{Context, bail};
 use candid::{IDLArgs, Principal};
+use candid_parser::utils::CandidSource;
 use clap::Parser;
 use dfx_core::identity::CallSender;
 use dfx_core::json::load_json_file;
@@ -20,6 +23,20 @@ pub struct CanisterSendOpts {
     /// Send the signed request-status call in the message
     #[arg(long)]
     status: bool,
+
+    /// Specifies the format for displaying the method's return result.
+    /// By default, the result is decoded without the types of the method.
+    #[arg(long, value_parser = ["idl", "raw", "pp", "json"])]
+    output: Option<String>,
+
+    /// Specifies how blobs are encoded with `--output json`.
+    #[arg(long, value_enum, default_value = "hex")]
+    blob_format: BlobFormat,
+
+    /// Provide the .did file with which to decode the response.
+    /// By default, the Candid interface is read from the canister metadata.
+    #[arg(long, requires("output"))]
+    candid: Option<PathBuf>,
 }
 
 pub async fn exec(
@@ -44,6 +61,24 @@ pub async fn exec(
     let content = hex::decode(&message.content).context("Failed to decode message content.")?;
     let canister_id = Principal::from_text(&message.canister_id)
         .with_context(|| format!("Failed to parse canister id {:?}.", message.canister_id))?;
+    let method_type = match (&opts.output, &opts.candid) {
+        (None, _) => None,
+        (Some(_), Some(path)) => get_candid_type(CandidSource::File(path), &message.method_name),
+        (Some(_), None) => fetch_remote_did_file(&agent, canister_id)
+            .await
+            .and_then(|did| get_candid_type(CandidSource::Text(&did), &message.method_name)),
+    };
+    let print_response = |response: &[u8]| match &opts.output {
+        Some(output) => print_idl_blob(response, Some(output), &method_type, opts.blob_format),
+        None => {
+            if let Ok(idl) = IDLArgs::from_bytes(response) {
+                println!("{idl}");
+            } else {
+                println!("{}", hex::encode(response));
+            }
+            Ok(())
+        }
+    };
 
     if opts.status {
         if message.call_type != "update" {
@@ -82,11 +117,7 @@ pub async fn exec(
             }
             RequestStatusResponse::Replied(response) => {
                 eprint!("Replied: ");
-                if let Ok(idl) = IDLArgs::from_bytes(&response.arg) {
-                    println!("{idl}");
-                } else {
-                    println!("{}", hex::encode(&response.arg));
-                }
+                print_response(&response.arg)?;
             }
             RequestStatusResponse::Done => println!("Done, response no longer available"),
             RequestStatusResponse::Unknown => println!("Unknown"),
@@ -121,11 +152,7 @@ pub async fn exec(
                 .await
                 .with_context(|| format!("Query call to {canister_id} failed."))?;
             eprint!("Response: ");
-            if let Ok(idl) = IDLArgs::from_bytes(&response) {
-                println!("{idl}")
-            } else {
-                println!("{}", hex::encode(&response));
-            }
+            print_response(&response)?;
         }
         "update" => {
             let call_response = agent
@@ -148,11 +175,7 @@ pub async fn exec(
                 }
                 CallResponse::Response(response) => {
                     eprint!("Response: ");
-                    if let Ok(idl) = IDLArgs::from_bytes(&response) {
-                        println!("{idl}");
-                    } else {
-                        println!("{}", hex::encode(&response));
-                    }
+                    print_response(&response)?;
                 }
             }
         }
@@ -0,0 +1,286 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::ic_attributes::{
+    CanisterSettings, get_compute_allocation, get_freezing_threshold, get_log_visibility,
+    get_memory_allocation, get_reserved_cycles_limit, get_wasm_memory_limit,
+    get_wasm_memory_threshold,
+};
+use crate::lib::operations::canister::{
+    get_canister_status, skip_remote_canister, update_settings,
+};
+use crate::lib::root_key::fetch_root_key_if_needed;
+use candid::{Nat, Principal};
+use clap::{Parser, Subcommand};
+use dfx_core::config::model::dfinity::ConfigInterface;
+use dfx_core::identity::CallSender;
+use fn_error_context::context;
+use ic_utils::interfaces::management_canister::{CanisterStatusResult, LogVisibility};
+
+/// Inspects canister settings.
+#[derive(Parser)]
+pub struct SettingsOpts {
+    #[command(subcommand)]
+    subcmd: SettingsSubcommand,
+}
+
+#[derive(Subcommand)]
+enum SettingsSubcommand {
+    Diff(SettingsDiffOpts),
+}
+
+/// Compares the settings of deployed canisters with the settings defined in dfx.json.
+#[derive(Parser)]
+struct SettingsDiffOpts {
+    /// Specifies the name of the canister to compare.
+    /// You must specify either a canister name or the --all flag.
+    canister: Option<String>,
+
+    /// Compares the settings of all canisters configured in the dfx.json file.
+    /// Canisters that are not created yet are skipped.
+    #[arg(long, required_unless_present("canister"))]
+    all: bool,
+
+    /// Updates the deployed settings that differ to the values defined in dfx.json.
+    #[arg(long)]
+    apply: bool,
+
+    /// Send request on behalf of the specified principal.
+    /// This option only works for a local PocketIC instance.
+    #[arg(long)]
+    impersonate: Option<Principal>,
+}
+
+/// A single setting whose deployed value differs from the configured one.
+struct SettingDifference {
+    name: &'static str,
+    deployed: String,
+    configured: String,
+}
+
+pub async fn exec(
+    env: &dyn Environment,
+    opts: SettingsOpts,
+    call_sender: &CallSender,
+) -> DfxResult {
+    match opts.subcmd {
+        SettingsSubcommand::Diff(opts) => diff(env, opts, call_sender).await,
+    }
+}
+
+async fn diff(
+    env: &dyn Environment,
+    opts: SettingsDiffOpts,
+    mut call_sender: &CallSender,
+) -> DfxResult {
+    let call_sender_override = opts.impersonate.map(CallSender::Impersonate);
+    if let Some(ref call_sender_override) = call_sender_override {
+        call_sender = call_sender_override;
+    };
+
+    fetch_root_key_if_needed(env).await?;
+
+    let config = env.get_config_or_anyhow()?;
+    let config_interface = config.get_config();
+
+    let all = opts.canister.is_none();
+    let canisters: Vec<String> = if let Some(canister) = opts.canister {
+        vec![canister]
+    } else if opts.all {
+        let mut canisters = vec![];
+        if let Some(configured) = &config_interface.canisters {
+            for canister in configured.keys() {
+                if !skip_remote_canister(env, canister)? {
+                    canisters.push(canister.clone());
+                }
+            }
+        }
+        canisters
+    } else {
+        unreachable!()
+    };
+
+    let canister_id_store = env.get_canister_id_store()?;
+    for canister in &canisters {
+        // With --all, canisters that are not created yet are reported instead of aborting the run,
+        // so that the command stays usable in partially deployed projects.
+        let canister_id = if all {
+            match canister_id_store.find(canister) {
+                Some(canister_id) => canister_id,
+                None => {
+                    println!("Canister '{canister}' is not created.");
+                    continue;
+                }
+            }
+        } else {
+            canister_id_store.get(canister)?
+        };
+        diff_canister_settings(
+            env,
+            config_interface,
+            canister,
+            canister_id,
+            opts.apply,
+            call_sender,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+#[context("Failed to compare settings of canister '{}'.", canister)]
+async fn diff_canister_settings(
+    env: &dyn Environment,
+    config_interface: &ConfigInterface,
+    canister: &str,
+    canister_id: Principal,
+    apply: bool,
+    call_sender: &CallSender,
+) -> DfxResult {
+    let configured = configured_settings(env, config_interface, canister)?;
+    let status = get_canister_status(env, canister_id, call_sender).await?;
+    let (differences, to_apply) = compare_settings(&configured, &status);
+
+    if differences.is_empty() {
+        println!("Settings of canister '{canister}' match dfx.json.");
+        return Ok(());
+    }
+
+    println!("Settings of canister '{canister}' differ from dfx.json:");
+    for difference in &differences {
+        println!(
+            "  {}: deployed {}, dfx.json {}",
+            difference.name, difference.deployed, difference.configured
+        );
+    }
+
+    if apply {
+        update_settings(env, canister_id, to_apply, call_sender).await?;
+        println!("Updated settings of canister '{canister}' to match dfx.json.");
+    }
+    Ok(())
+}
+
+/// Collects the settings defined in dfx.json for a canister.
+/// Settings that are not defined are left as `None` and never reported as different.
+fn configured_settings(
+    env: &dyn Environment,
+    config_interface: &ConfigInterface,
+    canister: &str,
+) -> DfxResult<CanisterSettings> {
+    let config_interface = Some(config_interface);
+    let canister_name = Some(canister);
+    Ok(CanisterSettings {
+        controllers: None,
+        compute_allocation: get_compute_allocation(None, config_interface, canister_name)?,
+        memory_allocation: get_memory_allocation(None, config_interface, canister_name)?,
+        freezing_threshold: get_freezing_threshold(None, config_interface, canister_name)?,
+        reserved_cycles_limit: get_reserved_cycles_limit(None, config_interface, canister_name)?,
+        wasm_memory_limit: get_wasm_memory_limit(None, config_interface, canister_name)?,
+        wasm_memory_threshold: get_wasm_memory_threshold(None, config_interface, canister_name)?,
+        log_visibility: get_log_visibility(env, None, None, config_interface, canister_name)?,
+        environment_variables: None,
+    })
+}
+
+/// Returns the differing settings, and the settings update that would resolve them.
+fn compare_settings(
+    configured: &CanisterSettings,
+    status: &CanisterStatusResult,
+) -> (Vec<SettingDifference>, CanisterSettings) {
+    let deployed = &status.settings;
+    let mut differences = vec![];
+    let mut to_apply = CanisterSettings::default();
+
+    if let Some(compute_allocation) = configured.compute_allocation {
+        let configured_value = Nat::from(u8::from(compute_allocation));
+        if configured_value != deployed.compute_allocation {
+            differences.push(SettingDifference {
+                name: "Compute allocation",
+                deployed: format!("{} %", deployed.compute_allocation),
+                configured: format!("{configured_value} %"),
+            });
+            to_apply.compute_allocation = Some(compute_allocation);
+        }
+    }
+    if let Some(memory_allocation) = configured.memory_allocation {
+        let configured_value = Nat::from(u64::from(memory_allocation));
+        if configured_value != deployed.memory_allocation {
+            differences.push(SettingDifference {
+                name: "Memory allocation",
+                deployed: format!("{} Bytes", deployed.memory_allocation),
+                configured: format!("{configured_value} Bytes"),
+            });
+            to_apply.memory_allocation = Some(memory_allocation);
+        }
+    }
+    if let Some(freezing_threshold) = configured.freezing_threshold {
+        let configured_value = Nat::from(u64::from(freezing_threshold));
+        if configured_value != deployed.freezing_threshold {
+            differences.push(SettingDifference {
+                name: "Freezing threshold",
+                deployed: format!("{} Seconds", deployed.freezing_threshold),
+                configured: format!("{configured_value} Seconds"),
+            });
+            to_apply.freezing_threshold = Some(freezing_threshold);
+        }
+    }
+    if let Some(reserved_cycles_limit) = configured.reserved_cycles_limit {
+        let configured_value = Nat::from(u128::from(reserved_cycles_limit));
+        if configured_value != deployed.reserved_cycles_limit {
+            differences.push(SettingDifference {
+                name: "Reserved cycles limit",
+                deployed: format!("{} Cycles", deployed.reserved_cycles_limit),
+                configured: format!("{configured_value} Cycles"),
+            });
+            to_apply.reserved_cycles_limit = Some(reserved_cycles_limit);
+        }
+    }
+    if let Some(wasm_memory_limit) = configured.wasm_memory_limit {
+        let configured_value = Nat::from(u64::from(wasm_memory_limit));
+        if configured_value != deployed.wasm_memory_limit {
+            differences.push(SettingDifference {
+                name: "Wasm memory limit",
+                deployed: format!("{} Bytes", deployed.wasm_memory_limit),
+                configured: format!("{configured_value} Bytes"),
+            });
+            to_apply.wasm_memory_limit = Some(wasm_memory_limit);
+        }
+    }
+    if let Some(wasm_memory_threshold) = configured.wasm_memory_threshold {
+        let configured_value = Nat::from(u64::from(wasm_memory_threshold));
+        if configured_value != deployed.wasm_memory_threshold {
+            differences.push(SettingDifference {
+                name: "Wasm memory threshold",
+                deployed: format!("{} Bytes", deployed.wasm_memory_threshold),
+                configured: format!("{configured_value} Bytes"),
+            });
+            to_apply.wasm_memory_threshold = Some(wasm_memory_threshold);
+        }
+    }
+    if let Some(log_visibility) = &configured.log_visibility {
+        let configured_value = format_log_visibility(log_visibility);
+        let deployed_value = format_log_visibility(&deployed.log_visibility);
+        if configured_value != deployed_value {
+            differences.push(SettingDifference {
+                name: "Log visibility",
+                deployed: deployed_value,
+                configured: configured_value,
+            });
+            to_apply.log_visibility = Some(log_visibility.clone());
+        }
+    }
+
+    (differences, to_apply)
+}
+
+fn format_log_visibility(log_visibility: &LogVisibility) -> String {
+    match log_visibility {
+        LogVisibility::Controllers => "controllers".to_string(),
+        LogVisibility::Public => "public".to_string(),
+        LogVisibility::AllowedViewers(viewers) => {
+            let mut viewers: Vec<_> = viewers.iter().map(Principal::to_text).collect();
+            viewers.sort();
+            format!("allowed viewers: [{}]", viewers.join(", "))
+        }
+    }
+}
@@ -2,17 +2,22 @@ use crate::commands::canister::call::get_effective_canister_id;
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use crate::lib::operations::canister::get_canister_id_and_candid_path;
-use crate::lib::sign::signed_message::SignedMessageV1;
+use crate::lib::sign::signed_message::{SignedMessageV1, format_utc, sign_update};
 use crate::util::clap::argument_from_cli::ArgumentFromCliPositionalOpt;
-use crate::util::{blob_from_arguments, get_candid_type};
-use anyhow::{anyhow, bail};
-use candid::Principal;
+use crate::util::{blob_from_arguments, fetch_remote_did_file, get_candid_type};
+use anyhow::{Context, anyhow, bail};
+use candid::types::{Function, TypeEnv};
+use candid::{IDLArgs, Principal};
 use candid_parser::utils::CandidSource;
 use clap::Parser;
+use dfx_core::error::identity::VerifySignatureError;
 use dfx_core::identity::CallSender;
-use dfx_core::json::save_json_file;
+use dfx_core::identity::signature::{SenderSignature, verify_envelope};
+use dfx_core::json::{load_json_file, save_json_file};
+use ic_agent::Agent;
+use ic_agent::agent::EnvelopeContent;
 use ic_utils::interfaces::management_canister::MgmtMethod;
-use slog::info;
+use slog::{info, warn};
 use std::convert::TryInto;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
@@ -23,10 +28,12 @@ use time::OffsetDateTime;
 #[derive(Parser)]
 pub struct CanisterSignOpts {
     /// Specifies the name/id of the canister to call.
-    canister_name: String,
+    #[arg(required_unless_present("inspect"))]
+    canister_name: Option<String>,
 
     /// Specifies the method name to call on the canister.
-    method_name: String,
+    #[arg(required_unless_present("inspect"))]
+    method_name: Option<String>,
 
     #[command(flatten)]
     argument_from_cli: ArgumentFromCliPositionalOpt,
@@ -64,6 +71,23 @@ pub struct CanisterSignOpts {
         conflicts_with("random")
     )]
     always_assist: bool,
+
+    /// Decodes and verifies a previously signed message file instead of signing a call.
+    /// Shows the sender, canister, method, expiry and argument of the message.
+    #[arg(
+        long,
+        conflicts_with("canister_name"),
+        conflicts_with("query"),
+        conflicts_with("update"),
+        conflicts_with("random"),
+        conflicts_with("always_assist")
+    )]
+    inspect: Option<PathBuf>,
+
+    /// Provide the .did file with which to decode the argument of the inspected message.
+    /// By default, the Candid interface of the project canister or the canister metadata is used.
+    #[arg(long, requires("inspect"))]
+    candid: Option<PathBuf>,
 }
 
 pub async fn exec(
@@ -72,16 +96,21 @@ pub async fn exec(
     call_sender: &CallSender,
 ) -> DfxResult {
     let log = env.get_logger();
+    if let Some(path) = &opts.inspect {
+        return inspect(env, path, opts.candid.as_deref()).await;
+    }
     if *call_sender != CallSender::SelectedId {
         bail!(
             "`sign` currently doesn't support proxying through the wallet canister, please use `dfx canister sign --no-wallet ...`."
         );
     }
 
-    let method_name = opts.method_name.as_str();
+    let (Some(canister_name), Some(method_name)) = (&opts.canister_name, &opts.method_name) else {
+        unreachable!("required unless --inspect is specified")
+    };
+    let method_name = method_name.as_str();
 
-    let (canister_id, maybe_candid_path) =
-        get_canister_id_and_candid_path(env, opts.canister_name.as_str())?;
+    let (canister_id, maybe_candid_path) = get_canister_id_and_candid_path(env, canister_name)?;
 
     let method_type =
         maybe_candid_path.and_then(|path| get_candid_type(CandidSource::File(&path), method_name));
@@ -111,40 +140,8 @@ pub async fn exec(
         false,
         opts.always_assist,
     )?;
-    let agent = env.get_agent();
-
-    let network_descriptor = env.get_network_descriptor();
-    let network = network_descriptor
-        .providers
-        .first()
-        .expect("Cannot get network provider (url).")
-        .to_string();
-
-    let sender = env
-        .get_selected_identity_principal()
-        .expect("Selected identity not instantiated.");
-
     let timeout = humantime::parse_duration(&opts.expire_after)
         .map_err(|_| anyhow!("Cannot parse expire_after as a duration (e.g. `1h`, `1h 30m`)"))?;
-    //let timeout = Duration::from_secs(opts.expire_after);
-    let expiration_system_time = SystemTime::now()
-        .checked_add(timeout)
-        .ok_or_else(|| anyhow!("Time wrapped around."))?;
-    let creation = OffsetDateTime::now_utc();
-    let expiration = creation
-        .checked_add(timeout.try_into()?)
-        .ok_or_else(|| anyhow!("Expiration datetime overflow."))?;
-
-    let message_template = SignedMessageV1::new(
-        creation,
-        expiration,
-        network,
-        network_descriptor.is_ic,
-        sender,
-        canister_id,
-        method_name.to_string(),
-        arg_value.clone(),
-    );
 
     let file_name = opts.file;
     if Path::new(&file_name).exists() {
@@ -164,35 +161,54 @@ pub async fn exec(
     };
 
     if is_query {
-        let signed_query = agent
+        let network_descriptor = env.get_network_descriptor();
+        let network = network_descriptor
+            .providers
+            .first()
+            .expect("Cannot get network provider (url).")
+            .to_string();
+        let sender = env
+            .get_selected_identity_principal()
+            .expect("Selected identity not instantiated.");
+        let expiration_system_time = SystemTime::now()
+            .checked_add(timeout)
+            .ok_or_else(|| anyhow!("Time wrapped around."))?;
+        let creation = OffsetDateTime::now_utc();
+        let expiration = creation
+            .checked_add(timeout.try_into()?)
+            .ok_or_else(|| anyhow!("Expiration datetime overflow."))?;
+
+        let signed_query = env
+            .get_agent()
             .query(&canister_id, method_name)
             .with_effective_canister_id(effective_canister_id)
-            .with_arg(arg_value)
+            .with_arg(arg_value.clone())
             .expire_at(expiration_system_time)
             .sign()?;
-        let message = message_template
-            .clone()
-            .with_call_type("query".to_string())
-            .with_content(hex::encode(signed_query.signed_query));
+        let message = SignedMessageV1::new(
+            creation,
+            expiration,
+            network,
+            network_descriptor.is_ic,
+            sender,
+            canister_id,
+            method_name.to_string(),
+            arg_value,
+        )
+        .with_call_type("query".to_string())
+        .with_content(hex::encode(signed_query.signed_query));
         save_json_file(&file_name, &message)?;
         info!(log, "Query message generated at [{}]", file_name.display());
         Ok(())
     } else {
-        let signed_update = agent
-            .update(&canister_id, method_name)
-            .with_effective_canister_id(effective_canister_id)
-            .with_arg(arg_value)
-            .expire_at(expiration_system_time)
-            .sign()?;
-        let request_id = signed_update.request_id;
-        let message = message_template
-            .clone()
-            .with_call_type("update".to_string())
-            .with_request_id(request_id)
-            .with_content(hex::encode(&signed_update.signed_update));
-        let signed_request_status = agent.sign_request_status(canister_id, request_id)?;
-        let message = message
-            .with_signed_request_status(hex::encode(signed_request_status.signed_request_status));
+        let message = sign_update(
+            env,
+            canister_id,
+            effective_canister_id,
+            method_name,
+            arg_value,
+            timeout,
+        )?;
         save_json_file(&file_name, &message)?;
         info!(
             log,
@@ -202,3 +218,153 @@ pub async fn exec(
         Ok(())
     }
 }
+
+/// Fetches the Candid type of a method from the network the message was signed for.
+async fn fetch_remote_candid_type(
+    message: &SignedMessageV1,
+    canister_id: Principal,
+    method_name: &str,
+) -> Option<(TypeEnv, Function)> {
+    let agent = Agent::builder().with_url(&message.network).build().ok()?;
+    if !message.is_ic {
+        agent.fetch_root_key().await.ok()?;
+    }
+    let did = fetch_remote_did_file(&agent, canister_id).await?;
+    get_candid_type(CandidSource::Text(&did), method_name)
+}
+
+/// Prints what a signed message would do when sent, after checking its signature.
+#[allow(clippy::future_not_send)]
+async fn inspect(env: &dyn Environment, path: &Path, candid: Option<&Path>) -> DfxResult {
+    let log = env.get_logger();
+    let message: SignedMessageV1 = load_json_file(path)?;
+    message.validate_content()?;
+    let envelope = message.envelope()?;
+    let (canister_id, method_name, arg) = match envelope.content.as_ref() {
+        EnvelopeContent::Call {
+            canister_id,
+            method_name,
+            arg,
+            ..
+        }
+        | EnvelopeContent::Query {
+            canister_id,
+            method_name,
+            arg,
+            ..
+        } => (*canister_id, method_name.as_str(), arg.as_slice()),
+        _ => bail!("The message is neither a query nor an update call."),
+    };
+
+    let maybe_candid_path = get_canister_id_and_candid_path(env, &canister_id.to_text())
+        .ok()
+        .and_then(|(_, path)| path);
+    let method_type = if let Some(path) = candid {
+        get_candid_type(CandidSource::File(path), method_name)
+    } else if let Some(path) = maybe_candid_path {
+        get_candid_type(CandidSource::File(&path), method_name)
+    } else {
+        fetch_remote_candid_type(&message, canister_id, method_name).await
+    };
+    if method_type.is_none() {
+        warn!(
+            log,
+            "Cannot fetch Candid interface for {method_name}, decoding the argument without types."
+        );
+    }
+    let argument = match &method_type {
+        Some((env, func)) => IDLArgs::from_bytes_with_types(arg, env, &func.args),
+        None => IDLArgs::from_bytes(arg),
+    }
+    .map_or_else(|_| hex::encode(arg), |args| args.to_string());
+
+    let expiry = envelope.content.ingress_expiry();
+    let expiration = OffsetDateTime::from_unix_timestamp_nanos(i128::from(expiry))?;
+    let now = OffsetDateTime::now_utc();
+    let expiry_text = if expiration > now {
+        let remaining = std::time::Duration::from_secs((expiration - now).whole_seconds() as u64);
+        format!(
+            "{} (in {})",
+            format_utc(expiration),
+            humantime::format_duration(remaining)
+        )
+    } else {
+        format!("{} (expired)", format_utc(expiration))
+    };
+
+    let signature = verify_envelope(&envelope);
+    let request_status_signature = message
+        .request_status_envelope()?
+        .map(|envelope| verify_envelope(&envelope));
+
+    println!("Call type:  {}", message.call_type);
+    println!("Sender:     {}", envelope.content.sender());
+    println!("Canister:   {canister_id}");
+    println!("Method:     {method_name}");
+    println!("Network:    {}", message.network);
+    println!("Created:    {}", format_utc(message.creation));
+    println!("Expires:    {expiry_text}");
+    if let Some(request_id) = &message.request_id {
+        println!("Request ID: 0x{request_id}");
+    }
+    println!("Signature:  {}", signature_text(&signature));
+    if let Some(request_status_signature) = &request_status_signature {
+        println!(
+            "Request status signature: {}",
+            signature_text(request_status_signature)
+        );
+    }
+    println!("Argument:   {argument}");
+
+    if expiration <= now {
+        warn!(
+            log,
+            "The message expired at {}. The IC will reject it.",
+            format_utc(expiration)
+        );
+    }
+    match &signature {
+        Ok(SenderSignature::Signed {
+            delegation_expiration: Some(delegation_expiration),
+            ..
+        }) if *delegation_expiration < expiry => warn!(
+            log,
+            "The delegation of the sender expires before the message. The IC will reject the message after {}.",
+            nanos_to_utc(*delegation_expiration)
+        ),
+        _ => {}
+    }
+    for result in std::iter::once(signature).chain(request_status_signature) {
+        match result {
+            Ok(_) => {}
+            Err(VerifySignatureError::CanisterSignature()) => warn!(
+                log,
+                "The message is signed with a canister signature, which cannot be verified offline."
+            ),
+            Err(err) => return Err(err).context("The signature of the message is invalid."),
+        }
+    }
+    Ok(())
+}
+
+fn signature_text(result: &Result<SenderSignature, VerifySignatureError>) -> String {
+    match result {
+        Ok(SenderSignature::Anonymous) => "none (anonymous sender)".to_string(),
+        Ok(SenderSignature::Signed { delegations: 0, .. }) => "valid".to_string(),
+        Ok(SenderSignature::Signed {
+            delegations,
+            delegation_expiration,
+        }) => {
+            let expiration = delegation_expiration.map(nanos_to_utc).unwrap_or_default();
+            format!("valid, through {delegations} delegation(s) until {expiration}")
+        }
+        Err(VerifySignatureError::CanisterSignature()) => "not verified".to_string(),
+        Err(err) => format!("INVALID: {err}"),
+    }
+}
+
+fn nanos_to_utc(nanos: u64) -> String {
+    OffsetDateTime::from_unix_timestamp_nanos(i128::from(nanos))
+        .map(format_utc)
+        .unwrap_or_else(|_| nanos.to_string())
+}
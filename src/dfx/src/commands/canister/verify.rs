@@ -0,0 +1,335 @@
+use crate::lib::canister_info::CanisterInfo;
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::metadata::names::{CANDID_ARGS, CANDID_SERVICE, DFX};
+use crate::lib::models::canister::PostProcessStep;
+use crate::lib::root_key::fetch_root_key_if_needed;
+use crate::lib::state_tree::canister_info::{
+    read_state_tree_canister_metadata, read_state_tree_canister_module_hash,
+};
+use crate::lib::wasm::file::{compress_bytes, read_wasm_module};
+use anyhow::{Context, anyhow, bail};
+use candid::Principal;
+use clap::Parser;
+use dfx_core::json::load_json_file;
+use fn_error_context::context;
+use ic_agent::Agent;
+use ic_wasm::metadata::{Kind, add_metadata, get_metadata, list_metadata, remove_metadata};
+use sha2::{Digest, Sha256};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use walkdir::WalkDir;
+
+/// Directories that are not copied to rebuild the canister from scratch, wherever they are in the project.
+const BUILD_ARTIFACT_DIRS: [&str; 4] = [".dfx", ".git", "node_modules", "target"];
+
+/// Public metadata sections that dfx and the Motoko compiler add to a module.
+const WELL_KNOWN_METADATA: [&str; 5] = [
+    CANDID_SERVICE,
+    CANDID_ARGS,
+    DFX,
+    "motoko:stable-types",
+    "motoko:compiler",
+];
+
+/// Rebuilds a canister and checks that the result matches the module deployed on the network.
+/// The canister is rebuilt in a temporary directory, so the build artifacts of the project are left untouched.
+#[derive(Parser)]
+pub struct CanisterVerifyOpts {
+    /// Specifies the name of the canister to verify.
+    canister: String,
+
+    /// Builds the canister from a clean checkout of this git commit, branch or tag,
+    /// instead of from the working tree.
+    #[arg(long)]
+    git_ref: Option<String>,
+}
+
+pub async fn exec(env: &dyn Environment, opts: CanisterVerifyOpts) -> DfxResult {
+    fetch_root_key_if_needed(env).await?;
+
+    let config = env.get_config_or_anyhow()?;
+    let canister = opts.canister.as_str();
+    let canister_id = env.get_canister_id_store()?.get(canister)?;
+    let deployed_hash = read_state_tree_canister_module_hash(env.get_agent(), canister_id)
+        .await?
+        .ok_or_else(|| anyhow!("Canister '{canister}' has no module installed."))?;
+    let deployed_hash = hex::encode(deployed_hash);
+
+    let network = &env.get_network_descriptor().name;
+    let project_root = config.get_project_root();
+    let canister_info = CanisterInfo::load(&config, canister, Some(canister_id))?;
+    let steps_path = canister_info
+        .get_post_process_steps_path()
+        .strip_prefix(project_root)?
+        .to_path_buf();
+    let wasm_path = canister_info
+        .get_build_wasm_path()
+        .strip_prefix(project_root)?
+        .to_path_buf();
+
+    // Keeps the temporary directory alive until the build is compared.
+    let build_dir = tempfile::tempdir().context("Failed to create temporary directory.")?;
+    let (build_root, source) = match &opts.git_ref {
+        Some(git_ref) => (
+            clean_checkout(project_root, git_ref, build_dir.path())?,
+            format!("git ref '{git_ref}'"),
+        ),
+        None => (
+            copy_working_tree(project_root, build_dir.path())?,
+            "the working tree".to_string(),
+        ),
+    };
+    copy_canister_ids(project_root, &build_root, network)?;
+
+    rebuild(&build_root, canister, network)?;
+    let steps_path = build_root.join(steps_path);
+    if !steps_path.exists() {
+        bail!("Canister '{canister}' has no wasm module built by this project.");
+    }
+    let steps: Vec<PostProcessStep> = load_json_file(&steps_path)?;
+    let rebuilt_hash = &steps
+        .last()
+        .ok_or_else(|| anyhow!("No wasm module was built for canister '{canister}'."))?
+        .hash;
+
+    println!("Deployed module hash: 0x{deployed_hash}");
+    println!("Rebuilt module hash:  0x{rebuilt_hash}");
+    if *rebuilt_hash == deployed_hash {
+        println!(
+            "Canister '{canister}' is verified: the deployed module matches the module built from {source}."
+        );
+        return Ok(());
+    }
+
+    println!("Hashes of the rebuilt module after each step:");
+    for step in &steps {
+        println!("  {:<9} 0x{}", step.name, step.hash);
+    }
+
+    // Every step after the metadata step changes the hash when only the metadata differs,
+    // so the metadata is compared separately from the code.
+    let wasm_path = build_root.join(wasm_path);
+    let agent = env.get_agent();
+    if let Some(differences) = metadata_differences(
+        agent,
+        canister_id,
+        &wasm_path,
+        &deployed_hash,
+        canister_info.metadata().sections.keys(),
+    )
+    .await?
+    {
+        bail!(
+            "The code of the deployed module matches the rebuilt module, but the 'metadata' step diverged:\n{}",
+            differences.join("\n")
+        );
+    }
+    match steps.iter().rposition(|step| step.hash == deployed_hash) {
+        Some(matching) => bail!(
+            "The deployed module matches the rebuilt module after the '{}' step, so the '{}' step diverged.",
+            steps[matching].name,
+            steps[matching + 1].name
+        ),
+        None => bail!(
+            "The deployed module matches none of the steps, so the module compiled from {source} already differs."
+        ),
+    }
+}
+
+/// Replaces the metadata sections of the rebuilt module with the ones of the deployed module.
+/// If that reproduces the deployed module, the code is identical, and the sections that differ are returned.
+/// Returns `None` if the code differs, or if the deployed metadata cannot be read,
+/// e.g. private sections when the identity is not a controller.
+async fn metadata_differences(
+    agent: &Agent,
+    canister_id: Principal,
+    wasm_path: &Path,
+    deployed_hash: &str,
+    declared: impl Iterator<Item = &String>,
+) -> DfxResult<Option<Vec<String>>> {
+    let mut module = read_wasm_module(wasm_path)?;
+    let mut sections: Vec<(Kind, String)> = list_metadata(&module)
+        .into_iter()
+        .filter_map(|section| match section.split_once(' ') {
+            Some(("icp:public", name)) => Some((Kind::Public, name.to_string())),
+            Some(("icp:private", name)) => Some((Kind::Private, name.to_string())),
+            _ => None,
+        })
+        .collect();
+    let rebuilt_count = sections.len();
+    // The sections of the deployed module cannot be listed, so the sections that are only in the
+    // deployed module are looked up by the names that dfx adds and the names declared in dfx.json.
+    for name in WELL_KNOWN_METADATA
+        .iter()
+        .map(|name| name.to_string())
+        .chain(declared.cloned())
+    {
+        if !sections.iter().any(|(_, section)| *section == name) {
+            sections.push((Kind::Public, name));
+        }
+    }
+
+    let mut differences = vec![];
+    let mut deployed_sections = vec![];
+    for (index, (visibility, name)) in sections.into_iter().enumerate() {
+        let in_rebuilt = index < rebuilt_count;
+        let deployed = match read_state_tree_canister_metadata(agent, canister_id, &name).await {
+            Ok(deployed) => deployed,
+            Err(_) if !in_rebuilt => continue,
+            Err(_) => return Ok(None),
+        };
+        let rebuilt = get_metadata(&module, &name).map(|data| data.to_vec());
+        match (&deployed, &rebuilt) {
+            (None, None) => continue,
+            (None, Some(_)) => differences.push(format!(
+                "  Metadata section '{name}' is not in the deployed module."
+            )),
+            (Some(_), None) => differences.push(format!(
+                "  Metadata section '{name}' is only in the deployed module."
+            )),
+            (Some(deployed), Some(rebuilt)) if deployed != rebuilt => differences.push(format!(
+                "  Metadata section '{name}' has different content in the deployed module."
+            )),
+            (Some(_), Some(_)) => {}
+        }
+        deployed_sections.push((visibility, name, deployed));
+    }
+    if differences.is_empty() {
+        return Ok(None);
+    }
+
+    // Re-adding all sections in their original order keeps the layout of the module.
+    for (_, name, _) in &deployed_sections {
+        remove_metadata(&mut module, name);
+    }
+    for (visibility, name, deployed) in deployed_sections {
+        if let Some(data) = deployed {
+            add_metadata(&mut module, visibility, &name, data);
+        }
+    }
+    let mut bytes = module.emit_wasm();
+    if wasm_path.extension() == Some(OsStr::new("gz")) {
+        bytes = compress_bytes(&bytes)?;
+    }
+    if hex::encode(Sha256::digest(&bytes)) == deployed_hash {
+        Ok(Some(differences))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Clones the git repository of the project and checks out `git_ref`.
+/// Returns the path of the project in the checkout.
+#[context("Failed to check out git ref '{}'.", git_ref)]
+fn clean_checkout(project_root: &Path, git_ref: &str, dir: &Path) -> DfxResult<PathBuf> {
+    let toplevel = git(project_root, &["rev-parse", "--show-toplevel"])?;
+    let prefix = git(project_root, &["rev-parse", "--show-prefix"])?;
+    let dir_str = dir
+        .to_str()
+        .ok_or_else(|| anyhow!("Invalid temporary directory {}.", dir.display()))?;
+    git(
+        project_root,
+        &["clone", "--quiet", "--no-checkout", &toplevel, dir_str],
+    )?;
+    git(dir, &["checkout", "--quiet", git_ref])?;
+    git(
+        dir,
+        &["submodule", "update", "--quiet", "--init", "--recursive"],
+    )?;
+    Ok(dir.join(prefix))
+}
+
+fn git(dir: &Path, args: &[&str]) -> DfxResult<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stderr(Stdio::inherit())
+        .output()
+        .context("Failed to run 'git'.")?;
+    if !output.status.success() {
+        bail!("'git {}' failed with {}", args.join(" "), output.status);
+    }
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// Copies the project into `dir`, without the build artifacts, so that the canister is built from scratch.
+/// Links are copied as links, so the copy never reaches outside of the project.
+/// Returns the path of the project in the copy.
+#[context("Failed to copy the project to {}.", dir.display())]
+fn copy_working_tree(project_root: &Path, dir: &Path) -> DfxResult<PathBuf> {
+    let walker = WalkDir::new(project_root)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !entry.file_type().is_dir()
+                || !BUILD_ARTIFACT_DIRS.contains(&entry.file_name().to_str().unwrap_or_default())
+        });
+    for entry in walker {
+        let entry = entry?;
+        let target = dir.join(entry.path().strip_prefix(project_root)?);
+        if entry.file_type().is_dir() {
+            dfx_core::fs::create_dir_all(&target)?;
+        } else if entry.file_type().is_symlink() {
+            copy_link(entry.path(), &target)?;
+        } else {
+            dfx_core::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(dir.to_path_buf())
+}
+
+#[cfg(unix)]
+fn copy_link(from: &Path, to: &Path) -> DfxResult {
+    let link = std::fs::read_link(from)
+        .with_context(|| format!("Failed to read link {}.", from.display()))?;
+    std::os::unix::fs::symlink(&link, to)
+        .with_context(|| format!("Failed to create link {}.", to.display()))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn copy_link(from: &Path, _to: &Path) -> DfxResult {
+    bail!("Cannot copy link {}.", from.display())
+}
+
+/// The copy of the project does not contain the `.dfx` directory, and the checkout only contains
+/// committed files, so the canister IDs that are compiled into the module are taken from the project.
+fn copy_canister_ids(project_root: &Path, build_root: &Path, network: &str) -> DfxResult {
+    for path in [
+        PathBuf::from("canister_ids.json"),
+        Path::new(".dfx").join(network).join("canister_ids.json"),
+    ] {
+        let from = project_root.join(&path);
+        let to = build_root.join(&path);
+        if from.exists() && !to.exists() {
+            dfx_core::fs::composite::ensure_parent_dir_exists(&to)?;
+            dfx_core::fs::copy(&from, &to)?;
+        }
+    }
+    Ok(())
+}
+
+#[context("Failed to rebuild canister '{}'.", canister)]
+fn rebuild(build_root: &Path, canister: &str, network: &str) -> DfxResult {
+    let exe = std::env::current_exe().context("Failed to get current executable.")?;
+    let status = Command::new(exe)
+        .args([
+            "build",
+            canister,
+            "--network",
+            network,
+            "--record-post-process-steps",
+        ])
+        .current_dir(build_root)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .context("Failed to run 'dfx build'.")?;
+    if !status.success() {
+        bail!("'dfx build' failed with {status}");
+    }
+    Ok(())
+}
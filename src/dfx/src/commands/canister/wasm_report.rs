@@ -0,0 +1,86 @@
+use crate::lib::canister_info::CanisterInfo;
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::operations::canister::skip_remote_canister;
+use crate::lib::wasm::report::{WasmReport, WasmReportFormat};
+use anyhow::bail;
+use byte_unit::Byte;
+use clap::Parser;
+use slog::{error, warn};
+
+/// Reports the size and composition of a canister's built wasm module.
+#[derive(Parser)]
+pub struct WasmReportOpts {
+    /// Specifies the name of the canister.
+    /// You must specify either a canister name or the --all flag.
+    canister: Option<String>,
+
+    /// Reports on all canisters configured in the dfx.json file that have been built.
+    #[arg(long, required_unless_present("canister"))]
+    all: bool,
+
+    /// The format of the report. In JSON, the reports are printed as one array.
+    #[arg(long, value_enum, default_value = "table")]
+    format: WasmReportFormat,
+
+    /// Fails if the gzipped wasm module is larger than this size, e.g. "1.5MiB".
+    #[arg(long)]
+    budget: Option<Byte>,
+}
+
+pub async fn exec(env: &dyn Environment, opts: WasmReportOpts) -> DfxResult {
+    let config = env.get_config_or_anyhow()?;
+
+    if let Some(canister) = opts.canister {
+        let canister_info = CanisterInfo::load(&config, &canister, None)?;
+        let wasm_path = canister_info.get_build_wasm_path();
+        if !wasm_path.exists() {
+            bail!(
+                "The wasm module of canister '{canister}' has not been built yet. Run `dfx build {canister}` first."
+            );
+        }
+
+        let report = WasmReport::from_file(&canister, &wasm_path)?;
+        WasmReport::print_all(std::slice::from_ref(&report), opts.format)?;
+        if let Some(budget) = opts.budget {
+            report.check_budget(budget)?;
+        }
+        return Ok(());
+    } else if !opts.all {
+        unreachable!()
+    }
+
+    let mut reports = vec![];
+    if let Some(canisters) = &config.get_config().canisters {
+        for canister in canisters.keys() {
+            if skip_remote_canister(env, canister)? {
+                continue;
+            }
+            let canister_info = CanisterInfo::load(&config, canister, None)?;
+            let wasm_path = canister_info.get_build_wasm_path();
+            if !wasm_path.exists() {
+                warn!(
+                    env.get_logger(),
+                    "Skipping canister '{}' because it has not been built yet.", canister
+                );
+                continue;
+            }
+            reports.push(WasmReport::from_file(canister, &wasm_path)?);
+        }
+    }
+    WasmReport::print_all(&reports, opts.format)?;
+
+    if let Some(budget) = opts.budget {
+        let mut over_budget = false;
+        for report in &reports {
+            if let Err(err) = report.check_budget(budget) {
+                error!(env.get_logger(), "{}", err);
+                over_budget = true;
+            }
+        }
+        if over_budget {
+            bail!("Some wasm modules exceed the budget.");
+        }
+    }
+    Ok(())
+}
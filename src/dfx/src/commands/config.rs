@@ -1,7 +1,10 @@
 use clap::{Parser, Subcommand};
 use dfx_core::config::model::dfinity::{TelemetryState, ToolConfigInterface};
+use dfx_core::json::structure::UrlWithJsonSchema;
 use slog::{info, warn};
+use url::Url;
 
+use crate::lib::telemetry::{DEFAULT_ENDPOINT, Telemetry};
 use crate::lib::{environment::Environment, error::DfxResult};
 
 /// Changes settings in dfx's configuration.
@@ -18,6 +21,15 @@ enum ConfigOption {
     ///
     /// `local` collects telemetry but does not store it.
     Telemetry { telemetry: Option<TelemetryState> },
+    /// Gets or sets the URL that telemetry is sent to.
+    ///
+    /// Use this to send telemetry to a self-hosted endpoint instead of DFINITY.
+    TelemetryEndpoint {
+        endpoint: Option<Url>,
+        /// Sends telemetry to the default endpoint again.
+        #[arg(long, conflicts_with = "endpoint")]
+        unset: bool,
+    },
 }
 
 pub fn exec(env: &dyn Environment, opts: ConfigOpts) -> DfxResult {
@@ -33,6 +45,22 @@ pub fn exec(env: &dyn Environment, opts: ConfigOpts) -> DfxResult {
                 println!("{}", env.telemetry_mode());
             }
         }
+        ConfigOption::TelemetryEndpoint { endpoint, unset } => {
+            if unset {
+                update_config(env, |settings| settings.telemetry_endpoint = None)?;
+                info!(
+                    env.get_logger(),
+                    "Telemetry endpoint set to the default {DEFAULT_ENDPOINT}"
+                );
+            } else if let Some(endpoint) = endpoint {
+                info!(env.get_logger(), "Telemetry endpoint set to {endpoint}");
+                update_config(env, |settings| {
+                    settings.telemetry_endpoint = Some(UrlWithJsonSchema(endpoint))
+                })?;
+            } else {
+                println!("{}", Telemetry::get_endpoint(env));
+            }
+        }
     }
     Ok(())
 }
@@ -0,0 +1,265 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::{DfxResult, NotifyTopUpError::Notify};
+use crate::lib::ledger_types::Memo;
+use crate::lib::ledger_types::NotifyError::Refunded;
+use crate::lib::nns_types::icpts::{ICPTs, TRANSACTION_FEE};
+use crate::lib::operations::canister::{get_canister_status, skip_remote_canister};
+use crate::lib::operations::cmc::{MEMO_TOP_UP_CANISTER, notify_top_up, transfer_cmc};
+use crate::lib::operations::cycles_ledger;
+use crate::lib::operations::ledger::xdr_permyriad_per_icp;
+use crate::lib::root_key::fetch_root_key_if_needed;
+use crate::util::clap::parsers::cycle_amount_parser;
+use crate::util::{format_as_trillions, pretty_thousand_separators};
+use anyhow::{anyhow, bail};
+use candid::Principal;
+use clap::Parser;
+use dfx_core::config::model::dfinity::AutoTopUp;
+use dfx_core::identity::CallSender;
+use num_traits::ToPrimitive;
+use slog::{info, warn};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Top up project canisters whose cycles balance is below the minimum defined in dfx.json.
+/// Each canister is topped up to the target balance defined in its `auto_top_up` configuration.
+#[derive(Parser)]
+pub struct AutoTopUpOpts {
+    /// Specifies the name of the canister to top up.
+    /// You must specify either a canister name or the --all flag.
+    canister: Option<String>,
+
+    /// Tops up all canisters with an `auto_top_up` configuration in the dfx.json file.
+    #[arg(long, required_unless_present("canister"))]
+    all: bool,
+
+    /// The maximum number of cycles to spend in this run, across all canisters and including fees.
+    /// Canisters are skipped or only partially topped up once the cap is reached.
+    #[arg(long, value_parser = cycle_amount_parser)]
+    max_spend: Option<u128>,
+
+    /// Mint the cycles from the selected identity's ICP through the cycles minting canister,
+    /// instead of withdrawing them from the cycles ledger.
+    #[arg(long)]
+    from_icp: bool,
+
+    /// Transaction timestamp, in nanoseconds, for use in controlling transaction deduplication, default is system time.
+    /// Each canister's top-up uses the next nanosecond. Pass the timestamp of a failed run to retry it safely.
+    #[arg(long)]
+    created_at_time: Option<u64>,
+
+    /// Only print the top-ups that would be made.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+pub async fn exec(env: &dyn Environment, opts: AutoTopUpOpts) -> DfxResult {
+    fetch_root_key_if_needed(env).await?;
+
+    let config = env.get_config_or_anyhow()?;
+    let canister_configs = config.get_config().canisters.as_ref();
+    let canister_id_store = env.get_canister_id_store()?;
+    let log = env.get_logger();
+
+    let mut canisters: Vec<(String, AutoTopUp)> = vec![];
+    if let Some(canister) = opts.canister {
+        let policy = canister_configs
+            .and_then(|canisters| canisters.get(&canister))
+            .and_then(|canister_config| canister_config.auto_top_up);
+        match policy {
+            Some(policy) => canisters.push((canister, policy)),
+            None => bail!("Canister '{canister}' has no auto_top_up configuration in dfx.json."),
+        }
+    } else if opts.all {
+        for (canister, canister_config) in canister_configs.into_iter().flatten() {
+            if let Some(policy) = canister_config.auto_top_up {
+                if !skip_remote_canister(env, canister)? {
+                    canisters.push((canister.clone(), policy));
+                }
+            }
+        }
+    } else {
+        unreachable!()
+    }
+    for (canister, policy) in &canisters {
+        if policy.target < policy.min {
+            bail!(
+                "The auto_top_up target of canister '{canister}' is lower than its minimum balance."
+            );
+        }
+    }
+
+    // Each top-up gets its own timestamp, so that the ledger never deduplicates two of them.
+    // The timestamps follow the order of the canisters, so a retry with the same base timestamp
+    // deduplicates exactly the top-ups that went through.
+    let created_at_time = opts.created_at_time.unwrap_or(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64,
+    );
+    // Each top-up is charged a fee on top of the deposited cycles: the withdrawal fee of the cycles ledger,
+    // or the ICP transfer fee when minting. The fees count against --max-spend.
+    let cycles_per_e8 = if opts.from_icp {
+        Some(cycles_per_e8(env).await?)
+    } else {
+        None
+    };
+    let fee = match cycles_per_e8 {
+        Some(cycles_per_e8) => TRANSACTION_FEE.get_e8s() as u128 * cycles_per_e8,
+        None => cycles_ledger::WITHDRAW_FEE,
+    };
+    let mut remaining = opts.max_spend.unwrap_or(u128::MAX);
+    let mut spent: u128 = 0;
+    let mut failed = false;
+
+    for (index, (canister, policy)) in canisters.into_iter().enumerate() {
+        let transfer_created_at_time = created_at_time + index as u64;
+        let canister_id = canister_id_store.get(&canister)?;
+        let status = get_canister_status(env, canister_id, &CallSender::SelectedId).await?;
+        let balance = status
+            .cycles
+            .0
+            .to_u128()
+            .ok_or_else(|| anyhow!("Balance of canister '{canister}' is out of range."))?;
+        if balance >= policy.min {
+            info!(
+                log,
+                "Canister '{}' has {} TC, no top-up needed.",
+                canister,
+                trillions(balance)
+            );
+            continue;
+        }
+
+        let needed = policy.target - balance;
+        let available = remaining.saturating_sub(fee);
+        let mut amount = needed.min(available);
+        // ICP is transferred in whole e8s. Round up, unless that would exceed the spend cap.
+        let e8s = cycles_per_e8.map(|cycles_per_e8| {
+            let e8s = amount.div_ceil(cycles_per_e8);
+            if e8s * cycles_per_e8 > available {
+                amount / cycles_per_e8
+            } else {
+                e8s
+            }
+        });
+        if let (Some(e8s), Some(cycles_per_e8)) = (e8s, cycles_per_e8) {
+            amount = e8s * cycles_per_e8;
+        }
+        if amount == 0 {
+            warn!(
+                log,
+                "Spend cap reached, skipping top-up of canister '{}'.", canister
+            );
+            continue;
+        }
+        if amount < needed {
+            warn!(
+                log,
+                "Spend cap reached, topping up canister '{}' with {} TC instead of {} TC.",
+                canister,
+                trillions(amount),
+                trillions(needed)
+            );
+        }
+
+        let deposited = if opts.dry_run {
+            println!(
+                "Would top up canister '{canister}' from {} TC with {} TC.",
+                trillions(balance),
+                trillions(amount)
+            );
+            amount
+        } else {
+            let result = match e8s {
+                Some(e8s) => top_up_from_icp(env, canister_id, e8s, transfer_created_at_time).await,
+                None => cycles_ledger::withdraw(
+                    env.get_agent(),
+                    log,
+                    canister_id,
+                    amount,
+                    transfer_created_at_time,
+                    None,
+                )
+                .await
+                .map(|_| amount),
+            };
+            match result {
+                Ok(deposited) => {
+                    println!(
+                        "Topped up canister '{canister}' from {} TC with {} TC.",
+                        trillions(balance),
+                        trillions(deposited)
+                    );
+                    deposited
+                }
+                Err(err) => {
+                    warn!(log, "Failed to top up canister '{}': {:#}", canister, err);
+                    failed = true;
+                    continue;
+                }
+            }
+        };
+        // The minting canister can deposit slightly more than expected if the conversion rate changed.
+        remaining = remaining.saturating_sub(deposited + fee);
+        spent += deposited + fee;
+    }
+
+    println!(
+        "{} {} TC in total, including fees.",
+        if opts.dry_run { "Would spend" } else { "Spent" },
+        trillions(spent)
+    );
+    if failed {
+        bail!(
+            "Some canisters could not be topped up. To retry safely, use --created-at-time {created_at_time}"
+        );
+    }
+    Ok(())
+}
+
+/// The number of cycles that one e8 of ICP mints.
+async fn cycles_per_e8(env: &dyn Environment) -> DfxResult<u128> {
+    // 1 ICP mints xdr_permyriad_per_icp * 10^8 cycles, so each e8 mints xdr_permyriad_per_icp cycles.
+    let cycles_per_e8 = xdr_permyriad_per_icp(env.get_agent()).await? as u128;
+    if cycles_per_e8 == 0 {
+        bail!("The cycles minting canister reported an ICP/XDR conversion rate of 0.");
+    }
+    Ok(cycles_per_e8)
+}
+
+/// Mints cycles from `e8s` of ICP into the canister and returns the number of cycles deposited.
+async fn top_up_from_icp(
+    env: &dyn Environment,
+    canister_id: Principal,
+    e8s: u128,
+    created_at_time: u64,
+) -> DfxResult<u128> {
+    let agent = env.get_agent();
+    let e8s = u64::try_from(e8s)?;
+    let height = transfer_cmc(
+        agent,
+        env.get_logger(),
+        Memo(MEMO_TOP_UP_CANISTER),
+        ICPTs::from_e8s(e8s),
+        TRANSACTION_FEE,
+        None,
+        canister_id,
+        Some(created_at_time),
+    )
+    .await?;
+    match notify_top_up(agent, canister_id, height).await {
+        Ok(cycles) => Ok(cycles),
+        Err(Notify(Refunded {
+            reason,
+            block_index,
+        })) => match block_index {
+            Some(height) => bail!("Refunded at block height {height} with message: {reason}"),
+            None => bail!("Refunded with message: {reason}"),
+        },
+        Err(other) => bail!("{other:?}"),
+    }
+}
+
+fn trillions(amount: u128) -> String {
+    pretty_thousand_separators(format_as_trillions(amount))
+}
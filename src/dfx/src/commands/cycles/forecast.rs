@@ -0,0 +1,158 @@
+use crate::lib::cycles_forecast::{
+    BurnRateSource, CyclesSample, CyclesSamples, SECONDS_PER_DAY, estimate_burn_rate, forecast,
+    get_cycles_samples_path,
+};
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::operations::canister::{get_canister_status, skip_remote_canister};
+use crate::lib::root_key::fetch_root_key_if_needed;
+use crate::util::clap::parsers::duration_parser;
+use crate::util::{format_as_trillions, pretty_thousand_separators};
+use anyhow::anyhow;
+use candid::Principal;
+use clap::Parser;
+use dfx_core::identity::CallSender;
+use num_traits::ToPrimitive;
+use slog::warn;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Estimate the cycles burn rate of canisters and how long until they are frozen.
+/// Every run records the current balances, which refines later estimates.
+#[derive(Parser)]
+pub struct ForecastOpts {
+    /// Specifies the name or id of the canister to forecast.
+    /// You must specify either a canister or the --all flag.
+    canister: Option<String>,
+
+    /// Forecasts all canisters configured in the dfx.json file that are created.
+    #[arg(long, required_unless_present("canister"))]
+    all: bool,
+
+    /// The runway the canisters should have before reaching their freezing threshold.
+    /// Used to compute the top-up plan. Can include units, e.g. "90d".
+    #[arg(long, default_value = "90d", value_parser = duration_parser)]
+    runway: u64,
+}
+
+pub async fn exec(env: &dyn Environment, opts: ForecastOpts) -> DfxResult {
+    fetch_root_key_if_needed(env).await?;
+
+    let canister_id_store = env.get_canister_id_store()?;
+    let canisters: Vec<(String, Principal)> = if let Some(canister) = opts.canister {
+        let canister_id =
+            Principal::from_text(&canister).or_else(|_| canister_id_store.get(&canister))?;
+        vec![(canister, canister_id)]
+    } else if opts.all {
+        let config = env.get_config_or_anyhow()?;
+        let mut canisters = vec![];
+        if let Some(configured) = &config.get_config().canisters {
+            for canister in configured.keys() {
+                if skip_remote_canister(env, canister)? {
+                    continue;
+                }
+                // Canisters that are not created yet are skipped instead of aborting the run,
+                // as in `dfx canister settings diff --all`.
+                match canister_id_store.find(canister) {
+                    Some(canister_id) => canisters.push((canister.clone(), canister_id)),
+                    None => warn!(
+                        env.get_logger(),
+                        "Skipping canister '{canister}' because it is not created."
+                    ),
+                }
+            }
+        }
+        canisters
+    } else {
+        unreachable!()
+    };
+
+    let samples_path = get_cycles_samples_path(env)?;
+    let mut samples = CyclesSamples::load(&samples_path)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let mut plan = vec![];
+    for (canister, canister_id) in canisters {
+        let status = get_canister_status(env, canister_id, &CallSender::SelectedId).await?;
+        let balance = status
+            .cycles
+            .0
+            .to_u128()
+            .ok_or_else(|| anyhow!("Balance of canister '{canister}' is out of range."))?;
+        let idle_burn = status
+            .idle_cycles_burned_per_day
+            .0
+            .to_u128()
+            .unwrap_or(u128::MAX);
+        let freezing_threshold = status
+            .settings
+            .freezing_threshold
+            .0
+            .to_u64()
+            .unwrap_or(u64::MAX);
+
+        samples.record(
+            &canister_id,
+            CyclesSample {
+                timestamp: now,
+                balance,
+            },
+        );
+        let burn_rate = estimate_burn_rate(samples.get(&canister_id), idle_burn);
+        let estimate = forecast(
+            balance,
+            burn_rate,
+            idle_burn,
+            freezing_threshold,
+            opts.runway,
+        );
+
+        let source = match burn_rate.source {
+            BurnRateSource::Samples { count, seconds } => format!(
+                "measured from {count} samples over {:.1} days",
+                seconds as f64 / SECONDS_PER_DAY as f64
+            ),
+            BurnRateSource::IdleBurn => {
+                "idle burn only, run this command again later to measure actual usage".to_string()
+            }
+        };
+        let days_until_frozen = match estimate.days_until_frozen {
+            Some(days) => format!("{days} days"),
+            None => "never".to_string(),
+        };
+        println!(
+            "\
+Canister '{canister}' ({canister_id}):
+  Balance: {balance} TC
+  Daily burn: {daily_burn} TC ({source})
+  Freezing threshold balance: {freezing_balance} TC
+  Time until frozen: {days_until_frozen}
+  Top-up for a {runway_days} day runway: {top_up} TC",
+            balance = trillions(estimate.balance),
+            daily_burn = trillions(burn_rate.cycles_per_day),
+            freezing_balance = trillions(estimate.freezing_balance),
+            runway_days = opts.runway / SECONDS_PER_DAY,
+            top_up = trillions(estimate.top_up),
+        );
+        if estimate.top_up > 0 {
+            plan.push((canister, estimate.top_up));
+        }
+    }
+
+    samples.save(&samples_path)?;
+
+    if !plan.is_empty() {
+        let network = &env.get_network_descriptor().name;
+        println!("\nTop-up plan:");
+        for (canister, amount) in plan {
+            println!("  dfx cycles top-up {canister} {amount} --network {network}");
+        }
+    }
+    Ok(())
+}
+
+fn trillions(amount: u128) -> String {
+    pretty_thousand_separators(format_as_trillions(amount))
+}
@@ -7,8 +7,10 @@ use clap::Parser;
 use tokio::runtime::Runtime;
 
 mod approve;
+mod auto_top_up;
 mod balance;
 mod convert;
+mod forecast;
 mod redeem_faucet_coupon;
 pub mod top_up;
 mod transfer;
@@ -27,8 +29,10 @@ pub struct CyclesOpts {
 #[derive(Parser)]
 enum SubCommand {
     Approve(approve::ApproveOpts),
+    AutoTopUp(auto_top_up::AutoTopUpOpts),
     Balance(balance::CyclesBalanceOpts),
     Convert(convert::ConvertOpts),
+    Forecast(forecast::ForecastOpts),
     TopUp(top_up::TopUpOpts),
     Transfer(transfer::TransferOpts),
     RedeemFaucetCoupon(redeem_faucet_coupon::RedeemFaucetCouponOpts),
@@ -41,8 +45,10 @@ pub fn exec(env: &dyn Environment, opts: CyclesOpts) -> DfxResult {
     runtime.block_on(async {
         match opts.subcmd {
             SubCommand::Approve(v) => approve::exec(&agent_env, v).await,
+            SubCommand::AutoTopUp(v) => auto_top_up::exec(&agent_env, v).await,
             SubCommand::Balance(v) => balance::exec(&agent_env, v).await,
             SubCommand::Convert(v) => convert::exec(&agent_env, v).await,
+            SubCommand::Forecast(v) => forecast::exec(&agent_env, v).await,
             SubCommand::TopUp(v) => top_up::exec(&agent_env, v).await,
             SubCommand::Transfer(v) => transfer::exec(&agent_env, v).await,
             SubCommand::RedeemFaucetCoupon(v) => redeem_faucet_coupon::exec(&agent_env, v).await,
@@ -1,11 +1,14 @@
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
-use crate::lib::operations::cycles_ledger;
+use crate::lib::operations::cycles_ledger::{self, CYCLES_LEDGER_CANISTER_ID};
+use crate::lib::operations::{ICRC1_TRANSFER_METHOD, ICRC2_TRANSFER_FROM_METHOD};
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::util::clap::parsers::{cycle_amount_parser, icrc_subaccount_parser};
-use candid::Principal;
+use crate::util::clap::sign_only_opt::SignOnlyOpt;
+use candid::{Encode, Nat, Principal};
 use clap::Parser;
 use icrc_ledger_types::icrc1::{self, account::Subaccount};
+use icrc_ledger_types::icrc2;
 use slog::warn;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -43,6 +46,9 @@ pub struct TransferOpts {
     /// Memo.
     #[arg(long)]
     memo: Option<u64>,
+
+    #[command(flatten)]
+    sign_only: SignOnlyOpt,
 }
 
 pub async fn exec(env: &dyn Environment, opts: TransferOpts) -> DfxResult {
@@ -50,6 +56,50 @@ pub async fn exec(env: &dyn Environment, opts: TransferOpts) -> DfxResult {
 
     let amount = opts.amount;
 
+    if let Some(file) = opts.sign_only.file() {
+        let to = icrc1::account::Account {
+            owner: opts.to,
+            subaccount: opts.to_subaccount,
+        };
+        let method_name = if opts.from.is_some() {
+            ICRC2_TRANSFER_FROM_METHOD
+        } else {
+            ICRC1_TRANSFER_METHOD
+        };
+        return opts.sign_only.sign_ledger_update(
+            env,
+            file,
+            opts.created_at_time,
+            CYCLES_LEDGER_CANISTER_ID,
+            method_name,
+            |created_at_time| {
+                if let Some(from_owner) = opts.from {
+                    Encode!(&icrc2::transfer_from::TransferFromArgs {
+                        spender_subaccount: opts.spender_subaccount,
+                        from: icrc1::account::Account {
+                            owner: from_owner,
+                            subaccount: opts.from_subaccount,
+                        },
+                        to,
+                        fee: None,
+                        created_at_time: Some(created_at_time),
+                        memo: opts.memo.map(|v| v.into()),
+                        amount: Nat::from(amount),
+                    })
+                } else {
+                    Encode!(&icrc1::transfer::TransferArg {
+                        from_subaccount: opts.from_subaccount,
+                        to,
+                        fee: None,
+                        created_at_time: Some(created_at_time),
+                        memo: opts.memo.map(|v| v.into()),
+                        amount: Nat::from(amount),
+                    })
+                }
+            },
+        );
+    }
+
     fetch_root_key_if_needed(env).await?;
 
     let created_at_time = opts.created_at_time.unwrap_or(
@@ -5,6 +5,7 @@ use crate::lib::deps::{
 };
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
+use crate::util::candid_json::json_to_idl_args;
 use crate::util::clap::argument_from_cli::ArgumentFromCliLongOpt;
 use crate::util::fuzzy_parse_argument;
 use anyhow::{Context, anyhow, bail};
@@ -86,7 +87,7 @@ pub async fn exec(env: &dyn Environment, opts: DepsInitOpts) -> DfxResult {
     Ok(())
 }
 
-fn set_init(
+pub(super) fn set_init(
     logger: &Logger,
     canister_id: &Principal,
     init_json: &mut InitJson,
@@ -114,6 +115,10 @@ fn set_init(
                 let bytes = hex::decode(arg_str)
                     .map_err(|e| anyhow!("Argument is not a valid hex string: {e}"))?;
                 init_json.set_init_arg(canister_id, None, &bytes);
+            } else if argument_type_from_cli == Some("json") {
+                let args = json_to_idl_args(arg_str, &env, &types)?;
+                let bytes = args.to_bytes_with_types(&env, &types)?;
+                init_json.set_init_arg(canister_id, Some(args.to_string()), &bytes);
             } else {
                 let bytes = fuzzy_parse_argument(arg_str, &env, &types)?;
                 init_json.set_init_arg(canister_id, Some(arg_str.to_string()), &bytes);
@@ -6,7 +6,10 @@ use tokio::runtime::Runtime;
 
 mod deploy;
 mod init;
+mod outdated;
 mod pull;
+mod update;
+mod vendor;
 
 /// Pull dependencies and integrate locally.
 #[derive(Parser)]
@@ -26,6 +29,9 @@ enum SubCommand {
     Pull(pull::DepsPullOpts),
     Init(init::DepsInitOpts),
     Deploy(deploy::DepsDeployOpts),
+    Vendor(vendor::DepsVendorOpts),
+    Outdated(outdated::DepsOutdatedOpts),
+    Update(update::DepsUpdateOpts),
 }
 
 /// Executes `dfx deps` and its subcommands.
@@ -38,6 +44,9 @@ pub fn exec(env: &dyn Environment, opts: DepsOpts) -> DfxResult {
             SubCommand::Pull(v) => pull::exec(&agent_env, v).await,
             SubCommand::Init(v) => init::exec(&agent_env, v).await,
             SubCommand::Deploy(v) => deploy::exec(&agent_env, v).await,
+            SubCommand::Vendor(v) => vendor::exec(&agent_env, v),
+            SubCommand::Outdated(v) => outdated::exec(&agent_env, v).await,
+            SubCommand::Update(v) => update::exec(&agent_env, v).await,
         }
     })
 }
@@ -0,0 +1,83 @@
+use crate::lib::agent::create_anonymous_agent_environment;
+use crate::lib::deps::pull::fetch_upstream_canister;
+use crate::lib::deps::{get_canister_prompt, get_pull_canisters_in_config, load_pulled_json};
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::network::network_opt::NetworkOpt;
+use crate::lib::root_key::fetch_root_key_if_needed;
+use clap::Parser;
+use slog::{info, warn};
+
+/// Check whether the pulled canisters have newer versions on the network.
+/// This command connects to the "ic" mainnet by default.
+/// You can still choose other network by setting `--network`.
+#[derive(Parser)]
+pub struct DepsOutdatedOpts {
+    #[command(flatten)]
+    network: NetworkOpt,
+}
+
+pub async fn exec(env: &dyn Environment, opts: DepsOutdatedOpts) -> DfxResult {
+    let logger = env.get_logger();
+    let pull_canisters_in_config = get_pull_canisters_in_config(env)?;
+    if pull_canisters_in_config.is_empty() {
+        info!(logger, "There are no pull dependencies defined in dfx.json");
+        return Ok(());
+    }
+
+    let network = opts
+        .network
+        .to_network_name()
+        .unwrap_or_else(|| "ic".to_string());
+    let env = create_anonymous_agent_environment(env, Some(network))?;
+    fetch_root_key_if_needed(&env).await?;
+
+    let project_root = env.get_config_or_anyhow()?.get_project_root().to_path_buf();
+    let pulled_json = load_pulled_json(&project_root)?;
+
+    let mut outdated = vec![];
+    for (canister_id, pulled_canister) in &pulled_json.canisters {
+        let canister_prompt = get_canister_prompt(canister_id, pulled_canister);
+        let Some(upstream) = fetch_upstream_canister(env.get_agent(), logger, *canister_id).await?
+        else {
+            println!("{canister_prompt}: pulled through a facade, not checked");
+            continue;
+        };
+        if upstream.wasm_hash == pulled_canister.wasm_hash
+            && upstream.dependencies == pulled_canister.dependencies
+        {
+            println!("{canister_prompt}: up to date");
+            continue;
+        }
+        println!("{canister_prompt}: outdated");
+        println!("    pulled wasm_hash:  {}", pulled_canister.wasm_hash);
+        println!("    current wasm_hash: {}", upstream.wasm_hash);
+        if upstream.dependencies != pulled_canister.dependencies {
+            let format_dependencies = |dependencies: &[candid::Principal]| {
+                dependencies
+                    .iter()
+                    .map(|id| id.to_text())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            println!(
+                "    pulled dependencies:  [{}]",
+                format_dependencies(&pulled_canister.dependencies)
+            );
+            println!(
+                "    current dependencies: [{}]",
+                format_dependencies(&upstream.dependencies)
+            );
+        }
+        outdated.push(canister_prompt);
+    }
+
+    if !outdated.is_empty() {
+        warn!(
+            logger,
+            "{} pulled canister(s) have a newer version. Please run `dfx deps update` to pull them.",
+            outdated.len()
+        );
+    }
+    Ok(())
+}
@@ -1,8 +1,9 @@
 use crate::lib::agent::create_anonymous_agent_environment;
 use crate::lib::deps::pull::{
-    copy_service_candid_to_project, download_all_and_generate_pulled_json, resolve_all_dependencies,
+    Mirror, PullSource, copy_service_candid_to_project, download_all_and_generate_pulled_json,
+    resolve_all_dependencies,
 };
-use crate::lib::deps::{get_pull_canisters_in_config, save_pulled_json};
+use crate::lib::deps::{get_pull_canisters_in_config, get_vendor_dir, save_pulled_json};
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use crate::lib::network::network_opt::NetworkOpt;
@@ -10,6 +11,7 @@ use crate::lib::root_key::fetch_root_key_if_needed;
 use anyhow::anyhow;
 use clap::Parser;
 use slog::info;
+use std::path::PathBuf;
 
 /// Pull canisters upon which the project depends.
 /// This command connects to the "ic" mainnet by default.
@@ -18,6 +20,15 @@ use slog::info;
 pub struct DepsPullOpts {
     #[command(flatten)]
     network: NetworkOpt,
+
+    /// Pulls the canisters from a directory written by `dfx deps vendor` instead of the network.
+    #[arg(long)]
+    mirror: Option<PathBuf>,
+
+    /// Does not connect to the network.
+    /// Pulls the canisters from the mirror, which defaults to the canisters vendored in deps/vendor.
+    #[arg(long)]
+    offline: bool,
 }
 
 pub async fn exec(env: &dyn Environment, opts: DepsPullOpts) -> DfxResult {
@@ -36,15 +47,26 @@ pub async fn exec(env: &dyn Environment, opts: DepsPullOpts) -> DfxResult {
 
     let project_root = env.get_config_or_anyhow()?.get_project_root().to_path_buf();
 
-    fetch_root_key_if_needed(&env).await?;
-
-    let agent = env.get_agent();
+    let mirror_dir = opts
+        .mirror
+        .or_else(|| opts.offline.then(|| get_vendor_dir(&project_root)));
+    let mirror = mirror_dir
+        .as_deref()
+        .map(|dir| Mirror::load(dir, &project_root))
+        .transpose()?;
+    let source = match &mirror {
+        Some(mirror) => PullSource::Mirror(mirror),
+        None => {
+            fetch_root_key_if_needed(&env).await?;
+            PullSource::Network(env.get_agent())
+        }
+    };
 
     let all_dependencies =
-        resolve_all_dependencies(agent, logger, &pull_canisters_in_config).await?;
+        resolve_all_dependencies(&source, logger, &pull_canisters_in_config).await?;
 
     let mut pulled_json =
-        download_all_and_generate_pulled_json(agent, logger, &all_dependencies).await?;
+        download_all_and_generate_pulled_json(&source, logger, &all_dependencies).await?;
 
     for (name, canister_id) in &pull_canisters_in_config {
         copy_service_candid_to_project(&project_root, name, canister_id)?;
@@ -0,0 +1,300 @@
+use super::init::set_init;
+use crate::lib::agent::create_anonymous_agent_environment;
+use crate::lib::deps::pull::{
+    PullSource, copy_service_candid_to_project, download_all_and_generate_pulled_json,
+    resolve_all_dependencies,
+};
+use crate::lib::deps::{
+    InitJson, PulledJson, create_init_json_if_not_existed, get_canister_prompt,
+    get_pull_canister_or_principal, get_pull_canisters_in_config, get_pulled_service_candid_path,
+    load_init_json, load_pulled_json, save_init_json, save_pulled_json,
+};
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::network::network_opt::NetworkOpt;
+use crate::lib::root_key::fetch_root_key_if_needed;
+use candid::Principal;
+use candid_parser::utils::{CandidSource, service_compatible};
+use clap::Parser;
+use slog::{Logger, info, warn};
+use std::collections::BTreeMap;
+
+/// Pull the newer versions of pulled canisters.
+/// Shows the changes of the candid interfaces and refreshes the init arguments whose type changed.
+/// This command connects to the "ic" mainnet by default.
+/// You can still choose other network by setting `--network`.
+#[derive(Parser)]
+pub struct DepsUpdateOpts {
+    /// Name of the pulled canister (as defined in dfx.json) or its Principal.
+    /// If not specified, all pulled canisters will be updated.
+    canister: Option<String>,
+
+    #[command(flatten)]
+    network: NetworkOpt,
+}
+
+pub async fn exec(env: &dyn Environment, opts: DepsUpdateOpts) -> DfxResult {
+    let logger = env.get_logger();
+    let pull_canisters_in_config = get_pull_canisters_in_config(env)?;
+    if pull_canisters_in_config.is_empty() {
+        info!(logger, "There are no pull dependencies defined in dfx.json");
+        return Ok(());
+    }
+
+    let network = opts
+        .network
+        .to_network_name()
+        .unwrap_or_else(|| "ic".to_string());
+    let env = create_anonymous_agent_environment(env, Some(network))?;
+    fetch_root_key_if_needed(&env).await?;
+
+    let project_root = env.get_config_or_anyhow()?.get_project_root().to_path_buf();
+    let mut pulled_json = load_pulled_json(&project_root)?;
+
+    let canisters_to_update = match &opts.canister {
+        Some(canister) => {
+            let canister_id =
+                get_pull_canister_or_principal(canister, &pull_canisters_in_config, &pulled_json)?;
+            BTreeMap::from([(canister.clone(), canister_id)])
+        }
+        None => pull_canisters_in_config.clone(),
+    };
+
+    let source = PullSource::Network(env.get_agent());
+    let all_dependencies = resolve_all_dependencies(&source, logger, &canisters_to_update).await?;
+
+    // The pulled cache is overwritten by the download, so the old interfaces are read first.
+    let mut old_service_candids = BTreeMap::new();
+    for canister_id in &all_dependencies {
+        let service_candid_path = get_pulled_service_candid_path(canister_id)?;
+        if pulled_json.canisters.contains_key(canister_id) && service_candid_path.exists() {
+            let service_candid = dfx_core::fs::read_to_string(&service_candid_path)?;
+            old_service_candids.insert(*canister_id, service_candid);
+        }
+    }
+
+    let updated_json =
+        download_all_and_generate_pulled_json(&source, logger, &all_dependencies).await?;
+
+    let mut old_canisters = BTreeMap::new();
+    for (canister_id, mut pulled_canister) in updated_json.canisters {
+        pulled_canister.name = pull_canisters_in_config
+            .iter()
+            .find(|(_, id)| **id == canister_id)
+            .map(|(name, _)| name.clone());
+        let old_canister = pulled_json.canisters.insert(canister_id, pulled_canister);
+        old_canisters.insert(canister_id, old_canister);
+    }
+    for (name, canister_id) in &pull_canisters_in_config {
+        if old_canisters.contains_key(canister_id) {
+            copy_service_candid_to_project(&project_root, name, canister_id)?;
+        }
+    }
+    save_pulled_json(&project_root, &pulled_json)?;
+
+    create_init_json_if_not_existed(&project_root)?;
+    let mut init_json = load_init_json(&project_root)?;
+    let mut canisters_require_init = vec![];
+    for (canister_id, old_canister) in old_canisters {
+        let pulled_canister = &pulled_json.canisters[&canister_id];
+        let canister_prompt = get_canister_prompt(&canister_id, pulled_canister);
+        let Some(old_canister) = old_canister else {
+            info!(logger, "Pulled new dependency {canister_prompt}.");
+            continue;
+        };
+        if old_canister.wasm_hash == pulled_canister.wasm_hash
+            && old_canister.wasm_hash_download == pulled_canister.wasm_hash_download
+        {
+            info!(logger, "Canister {canister_prompt} is up to date.");
+            continue;
+        }
+        info!(
+            logger,
+            "Updated canister {canister_prompt}:\n    wasm_hash: {} -> {}",
+            old_canister.wasm_hash,
+            pulled_canister.wasm_hash
+        );
+
+        let service_candid =
+            dfx_core::fs::read_to_string(&get_pulled_service_candid_path(&canister_id)?)?;
+        match old_service_candids.get(&canister_id) {
+            Some(old_service_candid) if *old_service_candid != service_candid => {
+                println!("Changes of the candid interface of {canister_prompt}:");
+                for line in diff_lines(old_service_candid, &service_candid) {
+                    println!("{line}");
+                }
+                if let Err(e) = service_compatible(
+                    CandidSource::Text(&service_candid),
+                    CandidSource::Text(old_service_candid),
+                ) {
+                    warn!(
+                        logger,
+                        "The new candid interface of {canister_prompt} is not compatible with the pulled one:\n{e}"
+                    );
+                }
+            }
+            Some(_) => info!(
+                logger,
+                "The candid interface of {canister_prompt} is unchanged."
+            ),
+            None => {}
+        }
+
+        if old_canister.candid_args != pulled_canister.candid_args
+            && init_json.contains(&canister_id)
+            && !refresh_init(
+                logger,
+                &canister_id,
+                &canister_prompt,
+                &mut init_json,
+                &pulled_json,
+            )
+        {
+            canisters_require_init.push(canister_prompt);
+        }
+    }
+    save_init_json(&project_root, &init_json)?;
+
+    if !canisters_require_init.is_empty() {
+        let mut message = "The init argument type of the following canister(s) changed. Please run `dfx deps init <NAME/PRINCIPAL>` to set them individually:".to_string();
+        for canister_prompt in canisters_require_init {
+            message.push_str(&format!("\n{canister_prompt}"));
+        }
+        warn!(logger, "{message}");
+    }
+    Ok(())
+}
+
+/// Sets the init argument of a canister whose init argument type changed.
+///
+/// The init argument in `init.json` is kept if it is valid for the new type.
+/// Otherwise, it is replaced in the same way as `dfx deps init` would set it.
+/// Returns false if the canister requires an init argument from the user, and keeps the old one until then.
+fn refresh_init(
+    logger: &Logger,
+    canister_id: &Principal,
+    canister_prompt: &str,
+    init_json: &mut InitJson,
+    pulled_json: &PulledJson,
+) -> bool {
+    let arg_str = init_json.get_arg_str(canister_id).map(str::to_string);
+    if let Some(arg_str) = arg_str {
+        let kept = set_init(
+            logger,
+            canister_id,
+            init_json,
+            pulled_json,
+            Some(&arg_str),
+            None,
+        );
+        if kept.is_ok() {
+            info!(
+                logger,
+                "The init argument type of {canister_prompt} changed. The init argument in init.json is still valid."
+            );
+            return true;
+        }
+    }
+    // `set_init` keeps an existing entry, so the reset starts without it,
+    // and replaces the old entry only if it succeeds.
+    let mut reset = init_json.clone();
+    reset.remove(canister_id);
+    if set_init(logger, canister_id, &mut reset, pulled_json, None, None).is_ok() {
+        *init_json = reset;
+        info!(
+            logger,
+            "The init argument type of {canister_prompt} changed. Its init argument in init.json was reset."
+        );
+        return true;
+    }
+    false
+}
+
+/// The largest number of line pairs that `diff_lines` compares to find the fewest changed lines.
+const MAX_DIFF_CELLS: usize = 1 << 20;
+
+/// The lines removed from `old` prefixed with "-" and the lines added in `new` prefixed with "+".
+///
+/// Lines shared at the start and end are skipped. If the remaining lines are too many to compare,
+/// all of them are listed as removed and added.
+fn diff_lines(old: &str, new: &str) -> Vec<String> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old = &old[prefix..old.len() - suffix];
+    let new = &new[prefix..new.len() - suffix];
+
+    let removed = |line: &&str| format!("-{line}");
+    let added = |line: &&str| format!("+{line}");
+    if (old.len() + 1).saturating_mul(new.len() + 1) > MAX_DIFF_CELLS {
+        return old
+            .iter()
+            .map(removed)
+            .chain(new.iter().map(added))
+            .collect();
+    }
+
+    // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..].
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(removed(&old[i]));
+            i += 1;
+        } else {
+            diff.push(added(&new[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_lines_shows_removed_and_added_lines() {
+        let old = "service : {\n  get : () -> (nat) query;\n  set : (nat) -> ();\n}";
+        let new = "service : {\n  get : () -> (nat) query;\n  set : (nat, text) -> ();\n  reset : () -> ();\n}";
+        assert_eq!(
+            diff_lines(old, new),
+            vec![
+                "-  set : (nat) -> ();",
+                "+  set : (nat, text) -> ();",
+                "+  reset : () -> ();",
+            ]
+        );
+        assert!(diff_lines(old, old).is_empty());
+    }
+
+    #[test]
+    fn diff_lines_lists_all_changed_lines_of_large_files() {
+        let old = (0..2000).map(|i| format!("a{i}\n")).collect::<String>();
+        let new = (0..2000).map(|i| format!("b{i}\n")).collect::<String>();
+        let diff = diff_lines(&format!("start\n{old}end"), &format!("start\n{new}end"));
+        assert_eq!(diff.len(), 4000);
+        assert_eq!(diff[0], "-a0");
+        assert_eq!(diff[2000], "+b0");
+    }
+}
@@ -0,0 +1,43 @@
+use crate::lib::deps::pull::Mirror;
+use crate::lib::deps::{
+    get_pull_canisters_in_config, get_vendor_dir, load_pulled_json, validate_pulled,
+};
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use anyhow::Context;
+use clap::Parser;
+use slog::info;
+use std::path::PathBuf;
+
+/// Write the pulled canisters into a directory that can be committed,
+/// so that `dfx deps pull --offline` or `dfx deps pull --mirror` can pull them without the network.
+#[derive(Parser)]
+pub struct DepsVendorOpts {
+    /// The directory to write the canisters to. Defaults to deps/vendor.
+    #[arg(long)]
+    dir: Option<PathBuf>,
+}
+
+pub fn exec(env: &dyn Environment, opts: DepsVendorOpts) -> DfxResult {
+    let logger = env.get_logger();
+    let pull_canisters_in_config = get_pull_canisters_in_config(env)?;
+    if pull_canisters_in_config.is_empty() {
+        info!(logger, "There are no pull dependencies defined in dfx.json");
+        return Ok(());
+    }
+
+    let project_root = env.get_config_or_anyhow()?.get_project_root().to_path_buf();
+    let pulled_json = load_pulled_json(&project_root)?;
+    validate_pulled(&pulled_json, &pull_canisters_in_config)
+        .with_context(|| "Please rerun `dfx deps pull`.")?;
+
+    let dir = opts.dir.unwrap_or_else(|| get_vendor_dir(&project_root));
+    Mirror::write(&dir, &pulled_json)?;
+    info!(
+        logger,
+        "Vendored {} canisters into {}.",
+        pulled_json.canisters.len(),
+        dir.display()
+    );
+    Ok(())
+}
@@ -2,10 +2,12 @@ use crate::commands::DfxCommand;
 use crate::config::cache::VersionCache;
 use crate::lib::environment::Environment;
 use crate::lib::error::{DfxError, DfxResult};
+use crate::lib::extension::ProjectExtensions;
 use anyhow::bail;
 use clap::Parser;
 use clap::Subcommand;
 use dfx_core::error::extension::InstallExtensionError::OtherVersionAlreadyInstalled;
+use dfx_core::extension::lock::{EXTENSIONS_LOCK_FILE_NAME, ExtensionsLock};
 use dfx_core::extension::manager::InstallOutcome;
 use semver::Version;
 use slog::{error, info, warn};
@@ -15,7 +17,8 @@ use url::Url;
 #[derive(Parser)]
 pub struct InstallOpts {
     /// Specifies the name of the extension to install.
-    name: String,
+    /// If omitted, installs the extensions declared in dfx.json.
+    name: Option<String>,
     /// Installs the extension under different name. Useful when installing an extension with the same name as: already installed extension, or a built-in command.
     #[clap(long)]
     install_as: Option<String>,
@@ -31,24 +34,39 @@ pub fn exec(env: &dyn Environment, opts: InstallOpts) -> DfxResult<()> {
     // creating an `extensions` directory in an otherwise empty cache directory would
     // cause the cache to be considered "installed" and later commands would fail
     VersionCache::install(env, &env.get_cache().version_str())?;
-    let spinner = env.new_spinner(format!("Installing extension: {}", opts.name).into());
+    let Some(name) = opts.name.clone() else {
+        let Some(project) = ProjectExtensions::from_current_dir()? else {
+            bail!(
+                "Specify the extension to install, or run this command in a project that declares its extensions in dfx.json."
+            );
+        };
+        return project.install(env);
+    };
+    let spinner = env.new_spinner(format!("Installing extension: {name}").into());
     let mgr = env.get_extension_manager();
-    let effective_extension_name = opts.install_as.clone().unwrap_or_else(|| opts.name.clone());
+    let effective_extension_name = opts.install_as.clone().unwrap_or_else(|| name.clone());
     if DfxCommand::has_subcommand(&effective_extension_name) {
         bail!(
             "Extension '{}' cannot be installed because it conflicts with an existing command. Consider using '--install-as' flag to install this extension under different name.",
-            opts.name
+            name
         )
     }
 
+    // Inside a project, installed versions and archive hashes are pinned in a lock file.
+    let lock_path = env
+        .get_config()?
+        .map(|config| config.get_project_root().join(EXTENSIONS_LOCK_FILE_NAME));
+    let mut lock = lock_path.as_deref().map(ExtensionsLock::load).transpose()?;
+
     let runtime = Runtime::new().expect("Unable to create a runtime");
 
     let install_outcome = runtime.block_on(async {
         mgr.install_extension(
-            &opts.name,
+            &name,
             opts.catalog_url.as_ref(),
             opts.install_as.as_deref(),
             opts.version.as_ref(),
+            lock.as_mut(),
         )
         .await
     });
@@ -60,7 +78,11 @@ pub fn exec(env: &dyn Environment, opts: InstallOpts) -> DfxResult<()> {
         "".to_string()
     };
     match install_outcome {
-        Ok(InstallOutcome::Installed(name, version)) => {
+        Ok(InstallOutcome::Installed(name, version, verification)) => {
+            verification.warn_if_unverified(logger, &name);
+            if let (Some(lock), Some(lock_path)) = (&lock, &lock_path) {
+                lock.save(lock_path)?;
+            }
             info!(
                 logger,
                 "Extension '{name}' version {version} installed successfully{install_as}"
@@ -81,7 +103,7 @@ pub fn exec(env: &dyn Environment, opts: InstallOpts) -> DfxResult<()> {
             );
             error!(
                 logger,
-                r#"To upgrade, run "dfx extension uninstall {name}" and then re-run the dfx extension install command"#
+                r#"To install another version, run "dfx extension update {name} --version <version>""#
             );
             bail!("Different version already installed");
         }
@@ -7,6 +7,7 @@ mod install;
 mod list;
 pub mod run;
 mod uninstall;
+mod update;
 
 /// Manages the dfx extensions.
 #[derive(Parser)]
@@ -26,6 +27,14 @@ pub enum SubCommand {
     Run(run::RunOpts),
     /// List installed or available extensions.
     List(list::ListOpts),
+    /// Update an installed extension to the latest compatible version.
+    Update(update::UpdateOpts),
+}
+
+impl ExtensionOpts {
+    pub fn runs_extension(&self) -> bool {
+        matches!(self.subcmd, SubCommand::Run(_))
+    }
 }
 
 pub fn exec(env: &dyn Environment, opts: ExtensionOpts) -> DfxResult {
@@ -34,5 +43,6 @@ pub fn exec(env: &dyn Environment, opts: ExtensionOpts) -> DfxResult {
         SubCommand::Uninstall(v) => uninstall::exec(env, v),
         SubCommand::Run(v) => run::exec(env, v),
         SubCommand::List(v) => list::exec(env, v),
+        SubCommand::Update(v) => update::exec(env, v),
     }
 }
@@ -0,0 +1,106 @@
+use crate::config::cache::VersionCache;
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::extension::ProjectExtensions;
+use anyhow::bail;
+use clap::Parser;
+use dfx_core::extension::lock::ExtensionsLock;
+use dfx_core::extension::manager::UpdateOutcome;
+use semver::Version;
+use slog::{error, info};
+use tokio::runtime::Runtime;
+use url::Url;
+
+#[derive(Parser)]
+pub struct UpdateOpts {
+    /// Specifies the name of the extension to update.
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    name: Option<String>,
+    /// Updates all installed extensions.
+    #[arg(long)]
+    all: bool,
+    /// Updates to a specific version of the extension, bypassing version checks
+    #[arg(long, requires = "name")]
+    version: Option<Version>,
+    /// Specifies the URL of the catalog to use to find the extension.
+    #[arg(long)]
+    catalog_url: Option<Url>,
+}
+
+pub fn exec(env: &dyn Environment, opts: UpdateOpts) -> DfxResult {
+    VersionCache::install(env, &env.get_cache().version_str())?;
+    let logger = env.get_logger();
+    let mgr = env.get_extension_manager();
+    let names = match opts.name {
+        Some(name) if !mgr.is_extension_installed(&name) => {
+            bail!("Extension '{name}' is not installed.")
+        }
+        Some(name) => vec![name],
+        None => mgr.list_installed_extensions()?,
+    };
+
+    let project = ProjectExtensions::from_current_dir()?;
+    let mut lock = project
+        .as_ref()
+        .map(|project| ExtensionsLock::load(&project.lock_path()))
+        .transpose()?;
+
+    let runtime = Runtime::new().expect("Unable to create a runtime");
+    let mut failed = vec![];
+    for name in names {
+        // Extensions declared in dfx.json are updated to the version it requires.
+        let required = project
+            .as_ref()
+            .and_then(|project| project.extensions.get(&name));
+        let version = opts
+            .version
+            .as_ref()
+            .or(required.map(|extension| &*extension.version));
+        let extension_url = required.and_then(|extension| extension.url.as_deref());
+
+        let spinner = env.new_spinner(format!("Updating extension: {name}").into());
+        let outcome = runtime.block_on(mgr.update_extension(
+            &name,
+            opts.catalog_url.as_ref(),
+            extension_url,
+            version,
+            lock.as_mut(),
+        ));
+        spinner.finish_and_clear();
+        let outcome = match outcome {
+            Ok(outcome) => outcome,
+            // With --all, the other extensions are still updated, and the failures are reported at the end.
+            Err(err) if opts.all => {
+                let err = anyhow::Error::new(err);
+                error!(logger, "Failed to update extension '{name}': {err:#}");
+                failed.push(name);
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        };
+        match outcome {
+            UpdateOutcome::Updated {
+                name,
+                from,
+                to,
+                verification,
+            } => {
+                verification.warn_if_unverified(logger, &name);
+                info!(
+                    logger,
+                    "Extension '{name}' updated from version {from} to version {to}"
+                );
+            }
+            UpdateOutcome::UpToDate(name, version) => {
+                info!(logger, "Extension '{name}' version {version} is up to date");
+            }
+        }
+        if let (Some(project), Some(lock)) = (&project, &lock) {
+            lock.save(&project.lock_path())?;
+        }
+    }
+    if !failed.is_empty() {
+        bail!("Failed to update extensions: {}", failed.join(", "));
+    }
+    Ok(())
+}
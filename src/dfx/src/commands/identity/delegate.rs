@@ -0,0 +1,149 @@
+use crate::commands::identity::new::create_new_dfx_identity;
+use crate::lib::agent::create_agent_environment;
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::network::network_opt::NetworkOpt;
+use crate::util::clap::parsers::duration_parser;
+use anyhow::{Context, bail};
+use candid::Principal;
+use clap::{ArgAction, Parser};
+use dfx_core::identity::delegation::DelegationChain;
+use dfx_core::identity::identity_manager::{
+    IdentityCreationParameters, IdentityStorageMode, generate_key,
+};
+use ic_agent::Identity;
+use ic_agent::identity::Secp256k1Identity;
+use slog::info;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Delegates the current identity to a session key for a limited time.
+/// With `--to new`, a new identity is created that holds the session key and the delegation chain.
+/// With `--to <public key>`, the delegation chain is printed, so that it can be imported with
+/// `dfx identity import <name> <session key PEM file> --delegation-chain <file>`.
+#[derive(Parser)]
+pub struct DelegateOpts {
+    /// The name of the identity to create with `--to new`.
+    #[arg(required_if_eq("to", "new"))]
+    new_identity: Option<String>,
+
+    /// Either `new`, to generate a session key, or the hex-encoded DER public key of the session key.
+    #[arg(long)]
+    to: String,
+
+    /// How long the delegation is valid, e.g. 30m, 8h or 7d.
+    #[arg(long, value_parser = duration_parser)]
+    expires: u64,
+
+    /// Restricts the delegation to a canister. Can be specified more than once.
+    /// Canister names are resolved on the network selected with `--network`.
+    #[arg(long, action = ArgAction::Append)]
+    targets: Vec<String>,
+
+    /// Writes the delegation chain to the specified file instead of stdout.
+    #[arg(long, conflicts_with("new_identity"))]
+    outfile: Option<PathBuf>,
+
+    /// How the session key is stored. By default, if keyring/keychain is available, keys are stored there.
+    /// Otherwise, a password-protected file is used as fallback.
+    #[arg(long, value_parser = ["keyring", "password-protected", "plaintext"])]
+    storage_mode: Option<String>,
+
+    /// If the identity already exists, remove and re-create it.
+    #[arg(long)]
+    force: bool,
+}
+
+pub fn exec(env: &dyn Environment, opts: DelegateOpts, network: NetworkOpt) -> DfxResult {
+    let log = env.get_logger();
+    let root = env
+        .new_identity_manager()?
+        .instantiate_selected_identity(log)?;
+    let root_name = root.name().to_string();
+
+    let targets = if opts.targets.is_empty() {
+        None
+    } else {
+        Some(resolve_targets(env, &opts.targets, network)?)
+    };
+    let expiration = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .saturating_add(Duration::from_secs(opts.expires))
+        .as_nanos() as u64;
+
+    if opts.to == "new" {
+        let name = opts.new_identity.expect("required with --to new");
+        let (session_pem_content, _) = generate_key()?;
+        let session_public_key = Secp256k1Identity::from_pem(session_pem_content.as_slice())?
+            .public_key()
+            .context("The session key has no public key.")?;
+        let delegation =
+            DelegationChain::delegate(&*root, session_public_key, expiration, targets)?;
+        let mode = match opts.storage_mode {
+            Some(mode_str) => IdentityStorageMode::from_str(&mode_str)?,
+            None => IdentityStorageMode::default(),
+        };
+        let expiration_text = delegation.expiration_text();
+        let params = IdentityCreationParameters::Delegated {
+            session_pem_content,
+            delegation,
+            mode,
+        };
+        create_new_dfx_identity(env, log, &name, params, opts.force)?;
+        info!(
+            log,
+            r#"Created identity "{name}", delegated by "{root_name}" until {expiration_text}."#
+        );
+    } else {
+        if let Some(name) = opts.new_identity {
+            bail!(
+                "Identity '{name}' can only be created with --to new. Import the delegation chain together with the session key instead."
+            );
+        }
+        let session_public_key = hex::decode(&opts.to)
+            .context("The public key passed to --to must be hex-encoded DER.")?;
+        let delegation =
+            DelegationChain::delegate(&*root, session_public_key, expiration, targets)?;
+        let json = serde_json::to_string_pretty(&delegation)?;
+        match opts.outfile {
+            Some(outfile) => {
+                dfx_core::fs::write(&outfile, json)?;
+                info!(
+                    log,
+                    "Wrote the delegation chain to {}, valid until {}.",
+                    outfile.display(),
+                    delegation.expiration_text()
+                );
+            }
+            None => println!("{json}"),
+        }
+    }
+    Ok(())
+}
+
+fn resolve_targets(
+    env: &dyn Environment,
+    targets: &[String],
+    network: NetworkOpt,
+) -> DfxResult<Vec<Principal>> {
+    if let Ok(principals) = targets
+        .iter()
+        .map(|target| Principal::from_text(target))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        return Ok(principals);
+    }
+    let agent_env = create_agent_environment(env, network.to_network_name())?;
+    let canister_id_store = agent_env.get_canister_id_store()?;
+    let mut principals = vec![];
+    for target in targets {
+        let principal = match Principal::from_text(target) {
+            Ok(principal) => principal,
+            Err(_) => canister_id_store.get(target)?,
+        };
+        principals.push(principal);
+    }
+    Ok(principals)
+}
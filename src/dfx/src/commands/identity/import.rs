@@ -3,7 +3,10 @@ use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use anyhow::Context;
 use clap::Parser;
+use dfx_core::identity::delegation::DelegationChain;
 use dfx_core::identity::identity_manager::{IdentityCreationParameters, IdentityStorageMode};
+use dfx_core::identity::pem_safekeeping::load_pem_from_file;
+use dfx_core::json::load_json_file;
 use slog::{info, warn};
 use std::fs;
 use std::path::PathBuf;
@@ -22,6 +25,10 @@ pub struct ImportOpts {
     #[arg(long, conflicts_with("pem_file"), required_unless_present("pem_file"))]
     seed_file: Option<PathBuf>,
 
+    /// The path to a delegation chain, as written by `dfx identity delegate --to <public key>`, that delegates to the imported key.
+    #[arg(long, requires("pem_file"))]
+    delegation_chain: Option<PathBuf>,
+
     /// DEPRECATED: Please use --storage-mode=plaintext instead
     #[arg(long)]
     disable_encryption: bool,
@@ -57,7 +64,16 @@ pub fn exec(env: &dyn Environment, opts: ImportOpts) -> DfxResult {
         IdentityStorageMode::default()
     };
     let name = opts.new_identity.as_str();
-    let params = if let Some(src_pem_file) = opts.pem_file {
+    let params = if let Some(delegation_chain) = opts.delegation_chain {
+        let src_pem_file = opts.pem_file.unwrap();
+        let (session_pem_content, _) = load_pem_from_file(&src_pem_file, None)?;
+        let delegation: DelegationChain = load_json_file(&delegation_chain)?;
+        IdentityCreationParameters::Delegated {
+            session_pem_content,
+            delegation,
+            mode,
+        }
+    } else if let Some(src_pem_file) = opts.pem_file {
         IdentityCreationParameters::PemFile { src_pem_file, mode }
     } else {
         let mnemonic =
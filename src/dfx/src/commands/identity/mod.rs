@@ -3,6 +3,7 @@ use crate::lib::error::DfxResult;
 use crate::lib::network::network_opt::NetworkOpt;
 use clap::Parser;
 
+mod delegate;
 mod deploy_wallet;
 mod export;
 mod get_wallet;
@@ -30,6 +31,7 @@ pub struct IdentityOpts {
 
 #[derive(Parser)]
 enum SubCommand {
+    Delegate(delegate::DelegateOpts),
     DeployWallet(deploy_wallet::DeployWalletOpts),
     Export(export::ExportOpts),
     GetWallet(get_wallet::GetWalletOpts),
@@ -46,17 +48,18 @@ enum SubCommand {
 
 pub fn exec(env: &dyn Environment, opts: IdentityOpts) -> DfxResult {
     match opts.subcmd {
+        SubCommand::Delegate(v) => delegate::exec(env, v, opts.network),
         SubCommand::DeployWallet(v) => deploy_wallet::exec(env, v, opts.network),
         SubCommand::Export(v) => export::exec(env, v),
         SubCommand::GetWallet(v) => get_wallet::exec(env, v, opts.network),
         SubCommand::List(v) => list::exec(env, v),
         SubCommand::New(v) => new::exec(env, v),
-        SubCommand::GetPrincipal(v) => principal::exec(env, v),
+        SubCommand::GetPrincipal(v) => principal::exec(env, v, opts.network),
         SubCommand::Import(v) => import::exec(env, v),
         SubCommand::Remove(v) => remove::exec(env, v),
         SubCommand::Rename(v) => rename::exec(env, v),
         SubCommand::SetWallet(v) => set_wallet::exec(env, v, opts.network),
         SubCommand::Use(v) => r#use::exec(env, v),
-        SubCommand::Whoami(v) => whoami::exec(env, v),
+        SubCommand::Whoami(v) => whoami::exec(env, v, opts.network),
     }
 }
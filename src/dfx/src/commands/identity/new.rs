@@ -1,12 +1,13 @@
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use crate::util::clap::parsers::hsm_key_id_parser;
-use IdentityCreationParameters::{Hardware, Pem};
+use IdentityCreationParameters::{External, Hardware, Pem};
 use anyhow::Context;
 use clap::Parser;
 use dfx_core::error::identity::CreateNewIdentityError::SwitchBackToIdentityFailed;
 use dfx_core::identity::identity_manager::{
-    HardwareIdentityConfiguration, IdentityCreationParameters, IdentityStorageMode,
+    ExternalIdentityConfiguration, HardwareIdentityConfiguration, IdentityCreationParameters,
+    IdentityStorageMode,
 };
 use regex::Regex;
 use slog::{Logger, info, warn};
@@ -34,6 +35,15 @@ pub struct NewIdentityOpts {
     #[arg(long, requires("hsm_pkcs11_lib_path"), value_parser = hsm_key_id_parser)]
     hsm_key_id: Option<String>,
 
+    /// A program that holds the key of the identity, e.g. a cloud KMS client or a remote signing service.
+    /// dfx asks it for the public key and for signatures with JSON messages over stdin and stdout.
+    #[arg(long, conflicts_with_all(["hsm_pkcs11_lib_path", "storage_mode", "disable_encryption"]))]
+    external_signer: Option<String>,
+
+    /// An argument passed to the external signer. Can be specified more than once.
+    #[arg(long, requires("external_signer"), allow_hyphen_values = true)]
+    external_signer_arg: Vec<String>,
+
     /// DEPRECATED: Please use --storage-mode=plaintext instead
     #[arg(long)]
     disable_encryption: bool,
@@ -70,13 +80,23 @@ pub fn exec(env: &dyn Environment, opts: NewIdentityOpts) -> DfxResult {
 
     let name = opts.new_identity.as_str();
 
-    let creation_parameters = match (opts.hsm_pkcs11_lib_path, opts.hsm_key_id) {
-        (Some(pkcs11_lib_path), Some(key_id)) => Hardware {
+    let creation_parameters = match (
+        opts.hsm_pkcs11_lib_path,
+        opts.hsm_key_id,
+        opts.external_signer,
+    ) {
+        (Some(pkcs11_lib_path), Some(key_id), _) => Hardware {
             hsm: HardwareIdentityConfiguration {
                 pkcs11_lib_path,
                 key_id,
             },
         },
+        (_, _, Some(command)) => External {
+            external: ExternalIdentityConfiguration {
+                command,
+                args: opts.external_signer_arg,
+            },
+        },
         _ => {
             let mode = if opts.disable_encryption {
                 IdentityStorageMode::Plaintext
@@ -1,16 +1,21 @@
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
+use crate::lib::network::network_opt::NetworkOpt;
 use anyhow::anyhow;
 use clap::Parser;
 use ic_agent::identity::Identity;
 
 /// Shows the textual representation of the Principal associated with the current identity.
+/// In a project that configures an identity for the network, that identity is used.
 #[derive(Parser)]
 pub struct GetPrincipalOpts {}
 
-pub fn exec(env: &dyn Environment, _opts: GetPrincipalOpts) -> DfxResult {
+pub fn exec(env: &dyn Environment, _opts: GetPrincipalOpts, network: NetworkOpt) -> DfxResult {
+    let network_name = network
+        .to_network_name()
+        .unwrap_or_else(|| "local".to_string());
     let identity = env
-        .new_identity_manager()?
+        .new_identity_manager_for_network(&network_name)?
         .instantiate_selected_identity(env.get_logger())?;
     let principal_id = identity.as_ref().sender().map_err(|err| anyhow!("{err}"))?;
     println!("{}", principal_id.to_text());
@@ -1,14 +1,36 @@
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
+use crate::lib::network::network_opt::NetworkOpt;
 use clap::Parser;
+use slog::info;
 
 /// Shows the name of the current identity.
+/// In a project that configures an identity for the network, shows that identity.
+/// For delegated identities, also shows the principal they act as and when the delegation expires.
 #[derive(Parser)]
 pub struct WhoAmIOpts {}
 
-pub fn exec(env: &dyn Environment, _opts: WhoAmIOpts) -> DfxResult {
-    let mgr = env.new_identity_manager()?;
+pub fn exec(env: &dyn Environment, _opts: WhoAmIOpts, network: NetworkOpt) -> DfxResult {
+    let network_name = network
+        .to_network_name()
+        .unwrap_or_else(|| "local".to_string());
+    let mgr = env.new_identity_manager_for_network(&network_name)?;
     let identity = mgr.get_selected_identity_name();
     println!("{identity}");
+    if let Ok(config) = mgr.get_identity_config_or_default(identity) {
+        if let Some(delegation) = config.delegation {
+            let expiry = if delegation.is_expired() {
+                "expired at"
+            } else {
+                "expires at"
+            };
+            info!(
+                env.get_logger(),
+                "Delegated by {}, {expiry} {}",
+                delegation.root_principal(),
+                delegation.expiration_text()
+            );
+        }
+    }
     Ok(())
 }
@@ -1,4 +1,5 @@
 mod pocketic_config_port;
+mod provider_health;
 mod webserver_port;
 
 use crate::Environment;
@@ -13,6 +14,7 @@ use anyhow::bail;
 use clap::{Parser, Subcommand};
 use dfx_core::config::model::dfinity::{NetworksConfig, ToolConfig};
 use pocketic_config_port::get_pocketic_config_port;
+use provider_health::get_provider_health;
 
 #[derive(Subcommand, Clone, Debug)]
 enum InfoType {
@@ -32,6 +34,8 @@ enum InfoType {
     ReplicaPort,
     /// Show the port that PocketIC is using, if it is running
     PocketicConfigPort,
+    /// Show the health and latency of each provider of the network
+    ProviderHealth,
     /// Show the path to the telemetry log file
     TelemetryLogPath,
     /// Show the effective canister ID that dfx will use for management canister calls that don't imply one
@@ -72,6 +76,7 @@ pub fn exec(env: &dyn Environment, opts: InfoOpts) -> DfxResult {
             "The 'native' replica (--replica) is no longer supported. If you intended to get the API port, use `--webserver-port`."
         ),
         InfoType::PocketicConfigPort => get_pocketic_config_port(env)?,
+        InfoType::ProviderHealth => get_provider_health(env, opts.network.to_network_name())?,
         InfoType::ReplicaRev => info::replica_rev().to_string(),
         InfoType::WebserverPort => get_webserver_port(env)?,
         InfoType::NetworksJsonPath => NetworksConfig::new()?.get_path().display().to_string(),
@@ -0,0 +1,24 @@
+use crate::Environment;
+use crate::lib::error::DfxResult;
+use dfx_core::network::health::{HEALTH_CHECK_TIMEOUT, check_providers_blocking};
+use dfx_core::network::provider::{LocalBindDetermination, create_network_descriptor};
+
+pub(crate) fn get_provider_health(
+    env: &dyn Environment,
+    network: Option<String>,
+) -> DfxResult<String> {
+    let network_descriptor = create_network_descriptor(
+        env.get_config()?,
+        env.get_networks_config(),
+        network,
+        None,
+        LocalBindDetermination::ApplyRunningWebserverPort,
+    )?;
+    network_descriptor.first_provider()?;
+    let health = check_providers_blocking(&network_descriptor.providers, HEALTH_CHECK_TIMEOUT);
+    Ok(health
+        .iter()
+        .map(|provider| provider.to_string())
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
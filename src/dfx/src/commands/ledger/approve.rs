@@ -2,12 +2,14 @@ use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use crate::lib::ledger_types::MAINNET_LEDGER_CANISTER_ID;
 use crate::lib::nns_types::icpts::ICPTs;
-use crate::lib::operations::ledger;
+use crate::lib::operations::{ICRC2_APPROVE_METHOD, ledger};
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::util::clap::parsers::icrc_subaccount_parser;
-use candid::Principal;
+use crate::util::clap::sign_only_opt::SignOnlyOpt;
+use candid::{Encode, Nat, Principal};
 use clap::Parser;
 use icrc_ledger_types::icrc1::account::Subaccount;
+use icrc_ledger_types::{icrc1, icrc2};
 use slog::{info, warn};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -56,17 +58,47 @@ pub struct ApproveOpts {
     /// Canister ID of the ledger canister.
     #[arg(long)]
     ledger_canister_id: Option<Principal>,
+
+    #[command(flatten)]
+    sign_only: SignOnlyOpt,
 }
 
 pub async fn exec(env: &dyn Environment, opts: ApproveOpts) -> DfxResult {
     let agent = env.get_agent();
 
-    fetch_root_key_if_needed(env).await?;
-
     let canister_id = opts
         .ledger_canister_id
         .unwrap_or(MAINNET_LEDGER_CANISTER_ID);
 
+    if let Some(file) = opts.sign_only.file() {
+        return opts.sign_only.sign_ledger_update(
+            env,
+            file,
+            opts.created_at_time,
+            canister_id,
+            ICRC2_APPROVE_METHOD,
+            |created_at_time| {
+                Encode!(&icrc2::approve::ApproveArgs {
+                    from_subaccount: opts.from_subaccount,
+                    spender: icrc1::account::Account {
+                        owner: opts.spender,
+                        subaccount: opts.spender_subaccount,
+                    },
+                    amount: Nat::from(opts.amount.get_e8s()),
+                    expected_allowance: opts
+                        .expected_allowance
+                        .map(|value| Nat::from(value.get_e8s())),
+                    expires_at: opts.expires_at,
+                    fee: opts.fee.map(|value| Nat::from(value.get_e8s())),
+                    memo: opts.memo.map(|v| v.into()),
+                    created_at_time: Some(created_at_time),
+                })
+            },
+        );
+    }
+
+    fetch_root_key_if_needed(env).await?;
+
     let created_at_time = opts.created_at_time.unwrap_or(
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -2,17 +2,20 @@ use crate::commands::ledger::get_icpts_from_args;
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use crate::lib::error::NotifyCreateCanisterError::Notify;
-use crate::lib::ledger_types::Memo;
 use crate::lib::ledger_types::NotifyError::Refunded;
+use crate::lib::ledger_types::{MAINNET_LEDGER_CANISTER_ID, Memo, TimeStamp, TransferArgs};
 use crate::lib::nns_types::account_identifier::Subaccount;
 use crate::lib::nns_types::icpts::{ICPTs, TRANSACTION_FEE};
-use crate::lib::operations::cmc::{notify_create, transfer_cmc};
+use crate::lib::operations::cmc::{cmc_account, notify_create, transfer_cmc};
+use crate::lib::operations::ledger::TRANSFER_METHOD;
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::util::clap::parsers::e8s_parser;
+use crate::util::clap::sign_only_opt::SignOnlyOpt;
 use crate::util::clap::subnet_selection_opt::SubnetSelectionOpt;
 use anyhow::{Context, anyhow, bail};
-use candid::Principal;
+use candid::{Encode, Principal};
 use clap::Parser;
+use slog::info;
 
 pub const MEMO_CREATE_CANISTER: u64 = 1095062083_u64;
 
@@ -52,6 +55,9 @@ pub struct CreateCanisterOpts {
     #[arg(long)]
     created_at_time: Option<u64>,
 
+    #[command(flatten)]
+    sign_only: SignOnlyOpt,
+
     #[command(flatten)]
     subnet_selection: SubnetSelectionOpt,
 }
@@ -72,6 +78,32 @@ pub async fn exec(env: &dyn Environment, opts: CreateCanisterOpts) -> DfxResult
     let agent = env.get_agent();
     let calling_identity = agent.get_principal().map_err(|err| anyhow!(err))?;
 
+    if let Some(file) = opts.sign_only.file() {
+        opts.sign_only.sign_ledger_update(
+            env,
+            file,
+            opts.created_at_time,
+            MAINNET_LEDGER_CANISTER_ID,
+            TRANSFER_METHOD,
+            |timestamp_nanos| {
+                Encode!(&TransferArgs {
+                    memo,
+                    amount,
+                    fee,
+                    from_subaccount: opts.from_subaccount,
+                    to: cmc_account(calling_identity),
+                    created_at_time: Some(TimeStamp { timestamp_nanos }),
+                })
+            },
+        )?;
+        info!(
+            env.get_logger(),
+            "After sending the transfer, sign the notification with `dfx ledger notify create-canister <block height> {} --sign-only <file>` using the same identity, and send it.",
+            controller
+        );
+        return Ok(());
+    }
+
     fetch_root_key_if_needed(env).await?;
 
     let subnet_selection = opts
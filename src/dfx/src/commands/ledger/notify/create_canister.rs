@@ -1,11 +1,15 @@
 use crate::lib::error::NotifyCreateCanisterError::Notify;
+use crate::lib::ledger_types::MAINNET_CYCLE_MINTER_CANISTER_ID;
 use crate::lib::ledger_types::NotifyError::Refunded;
-use crate::lib::operations::cmc::notify_create;
+use crate::lib::operations::cmc::{
+    NOTIFY_CREATE_CANISTER_METHOD, notify_create, notify_create_arg,
+};
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::lib::{environment::Environment, error::DfxResult};
+use crate::util::clap::sign_only_opt::SignOnlyOpt;
 use crate::util::clap::subnet_selection_opt::SubnetSelectionOpt;
-use anyhow::bail;
-use candid::Principal;
+use anyhow::{Context, anyhow, bail};
+use candid::{Encode, Principal};
 use clap::Parser;
 
 #[derive(Parser)]
@@ -18,6 +22,9 @@ pub struct NotifyCreateOpts {
 
     #[command(flatten)]
     subnet_selection: SubnetSelectionOpt,
+
+    #[command(flatten)]
+    sign_only: SignOnlyOpt,
 }
 
 pub async fn exec(env: &dyn Environment, opts: NotifyCreateOpts) -> DfxResult {
@@ -26,6 +33,28 @@ pub async fn exec(env: &dyn Environment, opts: NotifyCreateOpts) -> DfxResult {
 
     let agent = env.get_agent();
 
+    if let Some(file) = opts.sign_only.file() {
+        let caller = agent.get_principal().map_err(|err| anyhow!(err))?;
+        let subnet_selection = opts
+            .subnet_selection
+            .into_subnet_selection_type(env)
+            .await?;
+        let arg = Encode!(&notify_create_arg(
+            caller,
+            controller,
+            block_height,
+            subnet_selection
+        ))
+        .context("Failed to encode arguments.")?;
+        return opts.sign_only.sign_update(
+            env,
+            file,
+            MAINNET_CYCLE_MINTER_CANISTER_ID,
+            NOTIFY_CREATE_CANISTER_METHOD,
+            arg,
+        );
+    }
+
     fetch_root_key_if_needed(env).await?;
 
     let subnet_selection = opts
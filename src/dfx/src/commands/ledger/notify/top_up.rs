@@ -1,10 +1,12 @@
 use crate::lib::error::NotifyTopUpError::Notify;
 use crate::lib::ledger_types::NotifyError::Refunded;
-use crate::lib::operations::cmc::notify_top_up;
+use crate::lib::ledger_types::{MAINNET_CYCLE_MINTER_CANISTER_ID, NotifyTopUpArg};
+use crate::lib::operations::cmc::{NOTIFY_TOP_UP_METHOD, notify_top_up};
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::lib::{environment::Environment, error::DfxResult};
-use anyhow::bail;
-use candid::Principal;
+use crate::util::clap::sign_only_opt::SignOnlyOpt;
+use anyhow::{Context, bail};
+use candid::{Encode, Principal};
 use clap::Parser;
 
 #[derive(Parser)]
@@ -14,6 +16,9 @@ pub struct NotifyTopUpOpts {
 
     /// The principal of the canister to top up.
     canister: Principal,
+
+    #[command(flatten)]
+    sign_only: SignOnlyOpt,
 }
 
 pub async fn exec(env: &dyn Environment, opts: NotifyTopUpOpts) -> DfxResult {
@@ -21,6 +26,21 @@ pub async fn exec(env: &dyn Environment, opts: NotifyTopUpOpts) -> DfxResult {
     let block_height = opts.block_height;
     let canister = opts.canister;
 
+    if let Some(file) = opts.sign_only.file() {
+        let arg = Encode!(&NotifyTopUpArg {
+            block_index: block_height,
+            canister_id: canister,
+        })
+        .context("Failed to encode arguments.")?;
+        return opts.sign_only.sign_update(
+            env,
+            file,
+            MAINNET_CYCLE_MINTER_CANISTER_ID,
+            NOTIFY_TOP_UP_METHOD,
+            arg,
+        );
+    }
+
     let agent = env.get_agent();
 
     fetch_root_key_if_needed(env).await?;
@@ -1,18 +1,19 @@
 use crate::commands::ledger::get_icpts_from_args;
 use crate::lib::environment::Environment;
 use crate::lib::error::{DfxResult, NotifyTopUpError::Notify};
-use crate::lib::ledger_types::Memo;
 use crate::lib::ledger_types::NotifyError::Refunded;
+use crate::lib::ledger_types::{MAINNET_LEDGER_CANISTER_ID, Memo, TimeStamp, TransferArgs};
 use crate::lib::nns_types::account_identifier::Subaccount;
 use crate::lib::nns_types::icpts::{ICPTs, TRANSACTION_FEE};
-use crate::lib::operations::cmc::{notify_top_up, transfer_cmc};
+use crate::lib::operations::cmc::{MEMO_TOP_UP_CANISTER, cmc_account, notify_top_up, transfer_cmc};
+use crate::lib::operations::ledger::TRANSFER_METHOD;
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::util::clap::parsers::e8s_parser;
+use crate::util::clap::sign_only_opt::SignOnlyOpt;
 use anyhow::{Context, bail};
-use candid::Principal;
+use candid::{Encode, Principal};
 use clap::Parser;
-
-const MEMO_TOP_UP_CANISTER: u64 = 1347768404_u64;
+use slog::info;
 
 /// Top up a canister with cycles minted from ICP
 #[derive(Parser)]
@@ -49,6 +50,9 @@ pub struct TopUpOpts {
     /// Transaction timestamp, in nanoseconds, for use in controlling transaction-deduplication, default is system-time. // https://internetcomputer.org/docs/current/developer-docs/integrations/icrc-1/#transaction-deduplication-
     #[arg(long)]
     created_at_time: Option<u64>,
+
+    #[command(flatten)]
+    sign_only: SignOnlyOpt,
 }
 
 pub async fn exec(env: &dyn Environment, opts: TopUpOpts) -> DfxResult {
@@ -67,6 +71,32 @@ pub async fn exec(env: &dyn Environment, opts: TopUpOpts) -> DfxResult {
             )
         })?;
 
+    if let Some(file) = opts.sign_only.file() {
+        opts.sign_only.sign_ledger_update(
+            env,
+            file,
+            opts.created_at_time,
+            MAINNET_LEDGER_CANISTER_ID,
+            TRANSFER_METHOD,
+            |timestamp_nanos| {
+                Encode!(&TransferArgs {
+                    memo,
+                    amount,
+                    fee,
+                    from_subaccount: opts.from_subaccount,
+                    to: cmc_account(to),
+                    created_at_time: Some(TimeStamp { timestamp_nanos }),
+                })
+            },
+        )?;
+        info!(
+            env.get_logger(),
+            "After sending the transfer, notify the cycles minting canister with `dfx ledger notify top-up <block height> {}`. Any identity can send the notification.",
+            to
+        );
+        return Ok(());
+    }
+
     let agent = env.get_agent();
 
     fetch_root_key_if_needed(env).await?;
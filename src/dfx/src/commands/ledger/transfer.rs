@@ -1,14 +1,16 @@
 use crate::commands::ledger::get_icpts_from_args;
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
-use crate::lib::ledger_types::{MAINNET_LEDGER_CANISTER_ID, Memo};
+use crate::lib::ledger_types::{MAINNET_LEDGER_CANISTER_ID, Memo, TimeStamp, TransferArgs};
 use crate::lib::nns_types::account_identifier::{AccountIdentifier, Subaccount};
 use crate::lib::nns_types::icpts::{ICPTs, TRANSACTION_FEE};
-use crate::lib::operations::ledger::{icrc1_transfer, transfer};
+use crate::lib::operations::ICRC1_TRANSFER_METHOD;
+use crate::lib::operations::ledger::{TRANSFER_METHOD, icrc1_transfer, transfer};
 use crate::lib::root_key::fetch_root_key_if_needed;
 use crate::util::clap::parsers::{e8s_parser, icrc_subaccount_parser, memo_parser};
+use crate::util::clap::sign_only_opt::SignOnlyOpt;
 use anyhow::{Context, anyhow};
-use candid::Principal;
+use candid::{Encode, Nat, Principal};
 use clap::Parser;
 use icrc_ledger_types::icrc1;
 use std::str::FromStr;
@@ -61,6 +63,9 @@ pub struct TransferOpts {
     /// Transaction timestamp, in nanoseconds, for use in controlling transaction-deduplication, default is system-time. // https://internetcomputer.org/docs/current/developer-docs/integrations/icrc-1/#transaction-deduplication-
     #[arg(long)]
     created_at_time: Option<u64>,
+
+    #[command(flatten)]
+    sign_only: SignOnlyOpt,
 }
 
 pub async fn exec(env: &dyn Environment, opts: TransferOpts) -> DfxResult {
@@ -68,7 +73,10 @@ pub async fn exec(env: &dyn Environment, opts: TransferOpts) -> DfxResult {
 
     let agent = env.get_agent();
 
-    fetch_root_key_if_needed(env).await?;
+    // Signing only must work without a connection to the network.
+    if opts.sign_only.file().is_none() {
+        fetch_root_key_if_needed(env).await?;
+    }
 
     let canister_id = opts
         .ledger_canister_id
@@ -88,6 +96,26 @@ pub async fn exec(env: &dyn Environment, opts: TransferOpts) -> DfxResult {
             })?
             .to_address();
 
+        if let Some(file) = opts.sign_only.file() {
+            return opts.sign_only.sign_ledger_update(
+                env,
+                file,
+                opts.created_at_time,
+                canister_id,
+                TRANSFER_METHOD,
+                |timestamp_nanos| {
+                    Encode!(&TransferArgs {
+                        memo,
+                        amount,
+                        fee,
+                        from_subaccount: opts.from_subaccount,
+                        to,
+                        created_at_time: Some(TimeStamp { timestamp_nanos }),
+                    })
+                },
+            );
+        }
+
         let _block_height = transfer(
             agent,
             env.get_logger(),
@@ -106,6 +134,26 @@ pub async fn exec(env: &dyn Environment, opts: TransferOpts) -> DfxResult {
             subaccount: opts.to_subaccount,
         };
 
+        if let Some(file) = opts.sign_only.file() {
+            return opts.sign_only.sign_ledger_update(
+                env,
+                file,
+                opts.created_at_time,
+                canister_id,
+                ICRC1_TRANSFER_METHOD,
+                |created_at_time| {
+                    Encode!(&icrc1::transfer::TransferArg {
+                        from_subaccount: opts.from_subaccount.map(|s| s.0),
+                        to,
+                        fee: opts.fee.map(|value| Nat::from(value.get_e8s())),
+                        created_at_time: Some(created_at_time),
+                        memo: Some(opts.memo.into()),
+                        amount: Nat::from(amount.get_e8s()),
+                    })
+                },
+            );
+        }
+
         let created_at_time = opts.created_at_time.unwrap_or(
             SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -25,10 +25,12 @@ mod new;
 mod ping;
 mod quickstart;
 mod remote;
+mod replica;
 mod schema;
 mod send_telemetry;
 mod start;
 mod stop;
+mod telemetry;
 mod toolchain;
 mod upgrade;
 mod wallet;
@@ -57,11 +59,13 @@ pub enum DfxCommand {
     Ping(ping::PingOpts),
     Quickstart(quickstart::QuickstartOpts),
     Remote(remote::RemoteOpts),
+    Replica(replica::ReplicaOpts),
     Schema(schema::SchemaOpts),
     #[command(name = "_send-telemetry", hide = true)]
     SendTelemetry(send_telemetry::SendTelemetryOpts),
     Start(start::StartOpts),
     Stop(stop::StopOpts),
+    Telemetry(telemetry::TelemetryOpts),
     #[command(hide = true)]
     Toolchain(toolchain::ToolchainOpts),
     #[command(hide = true)]
@@ -92,16 +96,27 @@ pub fn exec(env: &dyn Environment, cmd: DfxCommand) -> DfxResult {
         DfxCommand::Ping(v) => ping::exec(env, v),
         DfxCommand::Quickstart(v) => quickstart::exec(env, v),
         DfxCommand::Remote(v) => remote::exec(env, v),
+        DfxCommand::Replica(v) => replica::exec(env, v),
         DfxCommand::Schema(v) => schema::exec(v),
         DfxCommand::SendTelemetry(v) => send_telemetry::exec(v),
         DfxCommand::Start(v) => start::exec(env, v),
         DfxCommand::Stop(v) => stop::exec(env, v),
+        DfxCommand::Telemetry(v) => telemetry::exec(env, v),
         DfxCommand::Toolchain(v) => toolchain::exec(env, v),
         DfxCommand::Upgrade(v) => upgrade::exec(env, v),
         DfxCommand::Wallet(v) => wallet::exec(env, v),
     }
 }
 
+/// Whether the command uses the extensions declared in dfx.json.
+pub fn uses_project_extensions(cmd: &DfxCommand) -> bool {
+    match cmd {
+        DfxCommand::Build(_) | DfxCommand::Deploy(_) => true,
+        DfxCommand::Extension(v) => v.runs_extension(),
+        _ => false,
+    }
+}
+
 pub fn exec_without_env(cmd: DfxCommand) -> DfxResult {
     match cmd {
         DfxCommand::Schema(v) => schema::exec(v),
@@ -3,12 +3,14 @@ use crate::lib::error::{DfxError, DfxResult};
 use anyhow::{Context, bail};
 use clap::Parser;
 use dfx_core::identity::Identity;
+use dfx_core::network::health::{HEALTH_CHECK_TIMEOUT, check_providers, preferred_providers};
 use dfx_core::network::provider::{
     LocalBindDetermination, command_line_provider_to_url, create_network_descriptor,
     get_network_context,
 };
 use dfx_core::util::expiry_duration;
 use slog::warn;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::runtime::Runtime;
 
@@ -29,7 +31,7 @@ pub struct PingOpts {
 pub fn exec(env: &dyn Environment, opts: PingOpts) -> DfxResult {
     // For ping, "provider" could either be a URL or a network name.
     // If not passed, we default to the "local" network.
-    let agent_url = create_network_descriptor(
+    let providers = create_network_descriptor(
         env.get_config()?,
         env.get_networks_config(),
         opts.network,
@@ -37,23 +39,33 @@ pub fn exec(env: &dyn Environment, opts: PingOpts) -> DfxResult {
         LocalBindDetermination::ApplyRunningWebserverPort,
     )
     .and_then(|network_descriptor| {
-        let url = network_descriptor.first_provider()?.to_string();
-        Ok(url)
+        network_descriptor.first_provider()?;
+        Ok(network_descriptor.providers)
     })
     .or_else::<DfxError, _>(|err| {
         let logger = env.get_logger();
         warn!(logger, "{:#}", err);
         let network_name = get_network_context()?;
         let url = command_line_provider_to_url(&network_name)?;
-        Ok(url)
+        Ok(vec![url])
     })?;
 
-    let timeout = expiry_duration();
-    let identity = Box::new(Identity::anonymous());
-    let agent = create_agent(env.get_logger().clone(), &agent_url, identity, timeout)?;
-
     let runtime = Runtime::new().expect("Unable to create a runtime");
     runtime.block_on(async {
+        // With several providers, report each of them, and route the pings to the healthy ones.
+        let providers = if providers.len() > 1 {
+            let health = check_providers(&providers, HEALTH_CHECK_TIMEOUT).await;
+            for provider in &health {
+                eprintln!("{provider}");
+            }
+            preferred_providers(&health)
+        } else {
+            providers
+        };
+        let timeout = expiry_duration();
+        let identity = Arc::new(Identity::anonymous());
+        let agent = create_agent(env.get_logger().clone(), &providers, identity, timeout)?;
+
         if opts.wait_healthy {
             let mut retries = 0;
 
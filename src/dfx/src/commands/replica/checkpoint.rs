@@ -0,0 +1,114 @@
+use crate::commands::start::check_previous_process_running;
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use anyhow::{Context, bail};
+use clap::{Parser, Subcommand};
+use dfx_core::network::provider::{LocalBindDetermination, create_network_descriptor};
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Saves and restores the state of the local network.
+/// Checkpoints are copies of the state directory of the local network on disk, because the PocketIC
+/// REST API has no endpoint to save or load the state of a whole instance.
+/// The local network must therefore be stopped with `dfx stop` while saving or restoring a checkpoint.
+#[derive(Parser)]
+pub struct CheckpointOpts {
+    #[command(subcommand)]
+    subcmd: CheckpointSubcommand,
+}
+
+#[derive(Subcommand)]
+enum CheckpointSubcommand {
+    /// Saves the state of the local network, and the canister IDs of the project on it.
+    Save {
+        /// The name of the checkpoint.
+        name: String,
+
+        /// Overwrites an existing checkpoint with the same name.
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Replaces the state of the local network, and the canister IDs of the project on it,
+    /// with a saved checkpoint.
+    Restore {
+        /// The name of the checkpoint.
+        name: String,
+    },
+}
+
+pub fn exec(env: &dyn Environment, opts: CheckpointOpts, network: Option<String>) -> DfxResult {
+    let network_descriptor = create_network_descriptor(
+        env.get_config()?,
+        env.get_networks_config(),
+        network,
+        None,
+        LocalBindDetermination::AsConfigured,
+    )?;
+    let mut local_server_descriptor = network_descriptor.local_server_descriptor()?.clone();
+    if check_previous_process_running(&local_server_descriptor).is_err() {
+        bail!("The local network is running. Stop it with `dfx stop` first.");
+    }
+    local_server_descriptor
+        .load_settings_digest()
+        .context("The local network has no state yet. Start it with `dfx start` first.")?;
+
+    let state_dir = local_server_descriptor.state_dir();
+    let project_network_dir = env
+        .get_project_temp_dir()?
+        .map(|temp_dir| temp_dir.join(&network_descriptor.name));
+
+    match opts.subcmd {
+        CheckpointSubcommand::Save { name, force } => {
+            let checkpoint_dir = local_server_descriptor.checkpoints_dir().join(&name);
+            if checkpoint_dir.exists() {
+                if !force {
+                    bail!("Checkpoint '{name}' already exists. Use --force to overwrite it.");
+                }
+                dfx_core::fs::remove_dir_all(&checkpoint_dir)?;
+            }
+            if !state_dir.is_dir() {
+                bail!("The local network has no state yet. Start it with `dfx start` first.");
+            }
+            copy_dir(&state_dir, &checkpoint_dir.join("state"))?;
+            if let Some(project_network_dir) = project_network_dir.filter(|dir| dir.is_dir()) {
+                copy_dir(&project_network_dir, &checkpoint_dir.join("project"))?;
+            }
+            println!("Saved checkpoint '{name}'.");
+        }
+        CheckpointSubcommand::Restore { name } => {
+            let checkpoint_dir = local_server_descriptor.checkpoints_dir().join(&name);
+            if !checkpoint_dir.is_dir() {
+                bail!("Checkpoint '{name}' does not exist.");
+            }
+            if state_dir.exists() {
+                dfx_core::fs::remove_dir_all(&state_dir)?;
+            }
+            copy_dir(&checkpoint_dir.join("state"), &state_dir)?;
+            let checkpoint_project_dir = checkpoint_dir.join("project");
+            if let Some(project_network_dir) = project_network_dir {
+                if checkpoint_project_dir.is_dir() {
+                    if project_network_dir.exists() {
+                        dfx_core::fs::remove_dir_all(&project_network_dir)?;
+                    }
+                    copy_dir(&checkpoint_project_dir, &project_network_dir)?;
+                }
+            }
+            println!("Restored checkpoint '{name}'. Start the local network with `dfx start`.");
+        }
+    }
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> DfxResult {
+    for entry in WalkDir::new(from) {
+        let entry = entry.with_context(|| format!("Failed to read {}.", from.display()))?;
+        let target = to.join(entry.path().strip_prefix(from)?);
+        if entry.file_type().is_dir() {
+            dfx_core::fs::create_dir_all(&target)?;
+        } else {
+            dfx_core::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::network::network_opt::NetworkOpt;
+use clap::{Parser, Subcommand};
+
+mod checkpoint;
+mod time;
+
+/// Controls the time and state of the local PocketIC instance started with `dfx start`.
+#[derive(Parser)]
+#[command(name = "replica")]
+pub struct ReplicaOpts {
+    #[command(flatten)]
+    network: NetworkOpt,
+
+    #[command(subcommand)]
+    subcmd: SubCommand,
+}
+
+#[derive(Subcommand)]
+pub enum SubCommand {
+    Checkpoint(checkpoint::CheckpointOpts),
+    Time(time::TimeOpts),
+}
+
+pub fn exec(env: &dyn Environment, opts: ReplicaOpts) -> DfxResult {
+    let network = opts.network.to_network_name();
+    match opts.subcmd {
+        SubCommand::Checkpoint(v) => checkpoint::exec(env, v, network),
+        SubCommand::Time(v) => time::exec(env, v, network),
+    }
+}
@@ -0,0 +1,108 @@
+use crate::lib::agent::create_anonymous_agent_environment;
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::util::clap::parsers::{duration_parser, timestamp_parser};
+use anyhow::{anyhow, bail};
+use clap::{Parser, Subcommand};
+use pocket_ic::Time;
+use pocket_ic::nonblocking::PocketIc;
+use std::time::Duration;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use tokio::runtime::Runtime;
+
+/// Controls the time of the local PocketIC instance.
+#[derive(Parser)]
+pub struct TimeOpts {
+    #[command(subcommand)]
+    subcmd: TimeSubcommand,
+}
+
+#[derive(Subcommand)]
+enum TimeSubcommand {
+    /// Prints the current time of the local PocketIC instance.
+    Get,
+
+    /// Sets the time of the local PocketIC instance. Time cannot go backwards.
+    Set {
+        /// The new time, either in RFC3339 format (e.g. '2030-05-06T19:17:10Z')
+        /// or in nanoseconds since the unix epoch.
+        #[arg(value_parser = timestamp_parser)]
+        time: u64,
+    },
+
+    /// Advances the time of the local PocketIC instance.
+    Advance {
+        /// The duration to advance by. Can include units, e.g. "8h" or "30d".
+        #[arg(value_parser = duration_parser)]
+        duration: u64,
+    },
+
+    /// Stops the time of the local PocketIC instance from progressing.
+    /// Blocks are only produced when the time is set or advanced.
+    Freeze,
+
+    /// Lets the time of the local PocketIC instance progress with the wall clock again.
+    Resume,
+}
+
+pub fn exec(env: &dyn Environment, opts: TimeOpts, network: Option<String>) -> DfxResult {
+    let env = create_anonymous_agent_environment(env, network)?;
+    let Some(pocketic) = env.get_pocketic() else {
+        bail!(
+            "No running local PocketIC instance found for network '{}'. Start one with `dfx start`.",
+            env.get_network_descriptor().name
+        );
+    };
+
+    let runtime = Runtime::new().expect("Unable to create a runtime");
+    runtime.block_on(async {
+        match opts.subcmd {
+            TimeSubcommand::Get => {}
+            TimeSubcommand::Set { time } => {
+                let current = pocketic.get_time().await.as_nanos_since_unix_epoch();
+                if time < current {
+                    bail!(
+                        "Cannot set the time to {}, which is before the current time {}.",
+                        format_time(time)?,
+                        format_time(current)?
+                    );
+                }
+                set_time(pocketic, time).await;
+            }
+            TimeSubcommand::Advance { duration } => {
+                let current = pocketic.get_time().await.as_nanos_since_unix_epoch();
+                let time = Duration::from_secs(duration)
+                    .as_nanos()
+                    .try_into()
+                    .ok()
+                    .and_then(|nanos: u64| current.checked_add(nanos))
+                    .ok_or_else(|| anyhow!("Cannot advance the time that far."))?;
+                set_time(pocketic, time).await;
+            }
+            TimeSubcommand::Freeze => {
+                pocketic.stop_progress().await;
+                println!("Time is frozen.");
+            }
+            TimeSubcommand::Resume => {
+                pocketic.auto_progress().await;
+                println!("Time progresses with the wall clock.");
+            }
+        }
+        let now = pocketic.get_time().await.as_nanos_since_unix_epoch();
+        println!("Current time: {}", format_time(now)?);
+        Ok(())
+    })
+}
+
+/// Sets the certified time, so that queries observe the new time
+/// without waiting for another round to be executed.
+async fn set_time(pocketic: &PocketIc, nanos: u64) {
+    pocketic
+        .set_certified_time(Time::from_nanos_since_unix_epoch(nanos))
+        .await;
+}
+
+fn format_time(nanos: u64) -> DfxResult<String> {
+    Ok(OffsetDateTime::from_unix_timestamp_nanos(nanos as i128)?.format(&Rfc3339)?)
+}
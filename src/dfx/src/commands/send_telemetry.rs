@@ -1,10 +1,8 @@
 use crate::lib::error::DfxResult;
-use crate::lib::telemetry::Telemetry;
+use crate::lib::telemetry::{DEFAULT_ENDPOINT, Telemetry};
 use clap::Parser;
 use url::Url;
 
-const DEFAULT_URL: &str = "https://sdk.dfinity.network/telemetry";
-
 #[derive(Parser)]
 #[command(hide = true)]
 pub struct SendTelemetryOpts {
@@ -13,7 +11,7 @@ pub struct SendTelemetryOpts {
 }
 
 pub fn exec(opts: SendTelemetryOpts) -> DfxResult {
-    let url = opts.url.unwrap_or_else(|| DEFAULT_URL.to_string());
+    let url = opts.url.unwrap_or_else(|| DEFAULT_ENDPOINT.to_string());
     let url = Url::parse(&url)?;
 
     Telemetry::send(&url)
@@ -533,7 +533,7 @@ fn frontend_address(
     Ok((frontend_url, address_and_port))
 }
 
-fn check_previous_process_running(
+pub(crate) fn check_previous_process_running(
     local_server_descriptor: &LocalServerDescriptor,
 ) -> DfxResult<()> {
     for pid_path in local_server_descriptor.dfx_pid_paths() {
@@ -0,0 +1,149 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::telemetry::{Telemetry, TelemetryBatch};
+use anyhow::Context;
+use chrono::Local;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use slog::info;
+use std::path::PathBuf;
+
+/// Inspects the telemetry that dfx collects.
+#[derive(Parser)]
+#[command(arg_required_else_help = true)]
+pub struct TelemetryOpts {
+    #[command(subcommand)]
+    subcmd: SubCommand,
+}
+
+#[derive(Subcommand)]
+enum SubCommand {
+    /// Prints the telemetry records that are waiting to be sent and the most recently sent ones.
+    Show,
+    /// Exports the telemetry records as JSON.
+    Export {
+        /// Writes the JSON to the specified file instead of stdout.
+        #[arg(long)]
+        outfile: Option<PathBuf>,
+    },
+    /// Deletes the telemetry records that are stored locally, including the ones waiting to be sent.
+    Clear,
+    /// Shows whether telemetry is enabled, where it is sent and when it is sent next.
+    Status,
+}
+
+#[derive(Serialize)]
+struct TelemetryExport {
+    log: Vec<serde_json::Value>,
+    pending_batches: Vec<TelemetryBatch>,
+    sent_batches: Vec<TelemetryBatch>,
+}
+
+impl TelemetryExport {
+    fn read() -> DfxResult<Self> {
+        Ok(Self {
+            log: Telemetry::read_log_records()?,
+            pending_batches: Telemetry::read_pending_batches()?,
+            sent_batches: Telemetry::read_sent_batches()?,
+        })
+    }
+}
+
+pub fn exec(env: &dyn Environment, opts: TelemetryOpts) -> DfxResult {
+    match opts.subcmd {
+        SubCommand::Show => show(),
+        SubCommand::Export { outfile } => {
+            let export = serde_json::to_string_pretty(&TelemetryExport::read()?)?;
+            match outfile {
+                Some(outfile) => {
+                    dfx_core::fs::write(&outfile, export)?;
+                    info!(
+                        env.get_logger(),
+                        "Exported telemetry to {}",
+                        outfile.display()
+                    );
+                }
+                None => println!("{export}"),
+            }
+            Ok(())
+        }
+        SubCommand::Clear => {
+            Telemetry::clear()?;
+            info!(env.get_logger(), "Deleted the stored telemetry records.");
+            Ok(())
+        }
+        SubCommand::Status => status(env),
+    }
+}
+
+fn show() -> DfxResult {
+    let export = TelemetryExport::read()?;
+    println!(
+        "Records not yet assigned to a batch ({}):",
+        Telemetry::get_log_path()?.display()
+    );
+    print_records(&export.log)?;
+    println!("Batches waiting to be sent:");
+    print_batches(&export.pending_batches)?;
+    println!("Most recently sent batches:");
+    print_batches(&export.sent_batches)?;
+    Ok(())
+}
+
+fn print_batches(batches: &[TelemetryBatch]) -> DfxResult {
+    if batches.is_empty() {
+        println!("  (none)");
+    }
+    for batch in batches {
+        println!("  Batch {}:", batch.id);
+        print_records(&batch.records)?;
+    }
+    Ok(())
+}
+
+fn print_records(records: &[serde_json::Value]) -> DfxResult {
+    if records.is_empty() {
+        println!("  (none)");
+    }
+    for record in records {
+        let record = serde_json::to_string_pretty(record)?;
+        for line in record.lines() {
+            println!("    {line}");
+        }
+    }
+    Ok(())
+}
+
+fn status(env: &dyn Environment) -> DfxResult {
+    let mode = env.telemetry_mode();
+    let export = TelemetryExport::read()?;
+    let pending_records: usize = export.log.len()
+        + export
+            .pending_batches
+            .iter()
+            .map(|batch| batch.records.len())
+            .sum::<usize>();
+
+    println!("Telemetry: {mode}");
+    println!("Endpoint: {}", Telemetry::get_endpoint(env));
+    println!("Directory: {}", Telemetry::get_telemetry_dir()?.display());
+    println!("Records waiting to be sent: {pending_records}");
+    println!(
+        "Batches waiting to be sent: {}",
+        export.pending_batches.len()
+    );
+    println!("Sent batches kept locally: {}", export.sent_batches.len());
+    let next_send_time = if !mode.should_publish() {
+        "never, telemetry is not sent".to_string()
+    } else {
+        match Telemetry::read_next_send_time().context("Failed to read the next send time.")? {
+            Some(send_time) if send_time <= Local::now().naive_local() => {
+                format!("{send_time} (due with the next dfx command)")
+            }
+            Some(send_time) => send_time.to_string(),
+            None => "not scheduled yet".to_string(),
+        }
+    };
+    println!("Next send time: {next_send_time}");
+    Ok(())
+}
@@ -506,6 +506,8 @@ pub struct BuildConfig {
     pub canisters_to_build: Option<Vec<String>>,
     /// If environment variables should be output to a `.env` file, `env_file` is set to its path.
     pub env_file: Option<PathBuf>,
+    /// If true, the hash of each wasm post-processing step is recorded, to find the step that makes a build differ.
+    pub record_post_process_steps: bool,
 }
 
 impl BuildConfig {
@@ -523,6 +525,7 @@ impl BuildConfig {
             lsp_root: network_root.join("lsp/"),
             canisters_to_build: None,
             env_file: config.get_output_env_file(None)?,
+            record_post_process_steps: false,
         })
     }
 
@@ -536,6 +539,13 @@ impl BuildConfig {
     pub fn with_env_file(self, env_file: Option<PathBuf>) -> Self {
         Self { env_file, ..self }
     }
+
+    pub fn with_record_post_process_steps(self, record_post_process_steps: bool) -> Self {
+        Self {
+            record_post_process_steps,
+            ..self
+        }
+    }
 }
 
 pub struct BuilderPool {
@@ -9,9 +9,11 @@ use crate::lib::models::canister::CanisterPool;
 use crate::util::with_suspend_all_spinners;
 use anyhow::{Context, anyhow, bail};
 use candid::Principal as CanisterId;
+use candid_parser::utils::CandidSource;
+use dfx_core::config::model::dfinity::RustTarget;
 use fn_error_context::context;
 use slog::{info, o};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::process::Stdio;
 
@@ -63,18 +65,34 @@ impl CanisterBuilder for RustBuilder {
         let rust_info = canister_info.as_info::<RustCanisterInfo>()?;
         let package = rust_info.get_package();
 
+        let mut args = vec![
+            "build".to_string(),
+            "--target".to_string(),
+            rust_info.get_target().as_str().to_string(),
+        ];
+        match rust_info.get_profile() {
+            "release" => args.push("--release".to_string()),
+            profile => args.extend(["--profile".to_string(), profile.to_string()]),
+        }
+        args.extend([
+            "-p".to_string(),
+            package.to_string(),
+            "--locked".to_string(),
+        ]);
+        if !rust_info.get_features().is_empty() {
+            args.extend(["--features".to_string(), rust_info.get_features().join(",")]);
+        }
+        if rust_info.get_no_default_features() {
+            args.push("--no-default-features".to_string());
+        }
+        args.extend(rust_info.get_cargo_args().iter().cloned());
+
         let mut cargo = Command::new("cargo");
         cargo
             .stdout(Stdio::inherit())
             .stderr(Stdio::inherit())
             .current_dir(canister_info.get_workspace_root())
-            .arg("build")
-            .arg("--target")
-            .arg("wasm32-unknown-unknown")
-            .arg("--release")
-            .arg("-p")
-            .arg(package)
-            .arg("--locked");
+            .args(&args);
 
         let dependencies = self
             .get_dependencies(env, pool, canister_info)
@@ -90,11 +108,7 @@ impl CanisterBuilder for RustBuilder {
             cargo.env(key.as_ref(), val);
         }
 
-        info!(
-            self.logger,
-            "Executing: cargo build --target wasm32-unknown-unknown --release -p {} --locked",
-            package
-        );
+        info!(self.logger, "Executing: cargo {}", args.join(" "));
 
         let output = with_suspend_all_spinners(env, || {
             cargo.output().context("Failed to run 'cargo build'. You might need to run `cargo update` (or a similar command like `cargo vendor`) if you have updated `Cargo.toml`, because `dfx build` uses the --locked flag with Cargo.")
@@ -104,6 +118,25 @@ impl CanisterBuilder for RustBuilder {
             bail!("Failed to compile the rust package: {package}");
         }
 
+        if rust_info.get_target() == RustTarget::Wasm32Wasip1 {
+            wasi2ic(
+                &self.logger,
+                rust_info.get_cargo_wasm_path(),
+                rust_info.get_output_wasm_path(),
+            )?;
+        }
+
+        if rust_info.get_extract_candid() {
+            extract_candid(
+                &self.logger,
+                rust_info.get_output_wasm_path(),
+                canister_info.get_output_idl_path(),
+            )?;
+            if let Some(candid_check_path) = rust_info.get_candid_check_path() {
+                check_candid_drift(canister_info.get_output_idl_path(), candid_check_path)?;
+            }
+        }
+
         Ok(BuildOutput {
             wasm: WasmBuildOutput::File(rust_info.get_output_wasm_path().to_path_buf()),
             idl: IdlBuildOutput::File(canister_info.get_output_idl_path().to_path_buf()),
@@ -120,3 +153,67 @@ impl CanisterBuilder for RustBuilder {
         Ok(info.get_output_idl_path().to_path_buf())
     }
 }
+
+/// Replaces the WASI imports of a module built for `wasm32-wasip1` with implementations
+/// that work on the Internet Computer.
+#[context("Failed to post-process {} with wasi2ic.", input.display())]
+fn wasi2ic(logger: &slog::Logger, input: &Path, output: &Path) -> DfxResult {
+    info!(
+        logger,
+        "Executing: wasi2ic {} {}",
+        input.display(),
+        output.display()
+    );
+    let status = Command::new("wasi2ic")
+        .arg(input)
+        .arg(output)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .context("Failed to run 'wasi2ic'. You can install it with `cargo install wasi2ic`.")?;
+    if !status.success() {
+        bail!("wasi2ic failed with {status}");
+    }
+    Ok(())
+}
+
+/// Extracts the candid interface exported by `get_candid_pointer` from the built Wasm module.
+/// The module is executed with `candid-extractor`, as the interface is only assembled at runtime.
+#[context("Failed to extract the candid interface from {}.", wasm.display())]
+fn extract_candid(logger: &slog::Logger, wasm: &Path, output: &Path) -> DfxResult {
+    info!(logger, "Executing: candid-extractor {}", wasm.display());
+    let extracted = Command::new("candid-extractor")
+        .arg(wasm)
+        .stderr(Stdio::inherit())
+        .output()
+        .context(
+            "Failed to run 'candid-extractor'. You can install it with `cargo install candid-extractor`.",
+        )?;
+    if !extracted.status.success() {
+        bail!(
+            "candid-extractor failed with {}. Make sure the canister exports its interface with `ic_cdk::export_candid!()`.",
+            extracted.status
+        );
+    }
+    dfx_core::fs::composite::ensure_parent_dir_exists(output)?;
+    dfx_core::fs::write(output, extracted.stdout)?;
+    Ok(())
+}
+
+/// Fails if the extracted interface differs from the checked-in one.
+/// Both files are normalized, so formatting and comments do not matter.
+#[context("Failed to compare {} with {}.", extracted.display(), checked_in.display())]
+fn check_candid_drift(extracted: &Path, checked_in: &Path) -> DfxResult {
+    let normalize = |path: &Path| -> DfxResult<String> {
+        let (env, actor) = CandidSource::File(path).load()?;
+        Ok(candid::pretty::candid::compile(&env, &actor))
+    };
+    if normalize(extracted)? != normalize(checked_in)? {
+        bail!(
+            "The candid interface of the built Wasm module differs from {}. If the change is intended, replace it with {}.",
+            checked_in.display(),
+            extracted.display()
+        );
+    }
+    Ok(())
+}
@@ -8,7 +8,7 @@ use candid::Principal;
 use core::panic;
 use dfx_core::config::model::dfinity::{
     CanisterDeclarationsConfig, CanisterMetadataSection, CanisterTypeProperties, Config, Pullable,
-    TechStack, WasmOptLevel,
+    RUST_CANDID_AUTO, TechStack, WasmOptLevel,
 };
 use dfx_core::fs::canonicalize;
 use dfx_core::network::provider::get_network_context;
@@ -137,12 +137,13 @@ impl CanisterInfo {
                 workspace_root.join(candid)
             } else {
                 match &canister_config.type_specific {
-                    CanisterTypeProperties::Rust {
-                        package: _,
-                        crate_name: _,
-                        candid,
-                        skip_cargo_audit: _,
-                    } => workspace_root.join(candid),
+                    CanisterTypeProperties::Rust { candid, .. } => {
+                        if candid.as_os_str() == RUST_CANDID_AUTO {
+                            output_root.join(name).with_extension("did")
+                        } else {
+                            workspace_root.join(candid)
+                        }
+                    }
                     CanisterTypeProperties::Assets { .. } => output_root.join("assetstorage.did"),
                     CanisterTypeProperties::Custom {
                         wasm: _,
@@ -325,6 +326,13 @@ impl CanisterInfo {
         self.output_root.join("init_args.txt")
     }
 
+    /// Path to the hashes of the wasm module after each post-processing step.
+    ///
+    /// Only written if the build config asks for it.
+    pub fn get_post_process_steps_path(&self) -> PathBuf {
+        self.output_root.join("post_process_steps.json")
+    }
+
     pub fn get_index_js_path(&self) -> PathBuf {
         self.output_root.join("index").with_extension("js")
     }
@@ -2,14 +2,27 @@ use crate::lib::canister_info::{CanisterInfo, CanisterInfoFactory};
 use crate::lib::error::DfxResult;
 use anyhow::{Context, bail, ensure};
 use cargo_metadata::Metadata;
-use dfx_core::config::model::dfinity::CanisterTypeProperties;
+use dfx_core::config::model::dfinity::{CanisterTypeProperties, RUST_CANDID_AUTO, RustTarget};
+use dfx_core::network::provider::get_network_context;
 use itertools::Itertools;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
 pub struct RustCanisterInfo {
     package: String,
+    features: Vec<String>,
+    no_default_features: bool,
+    profile: String,
+    target: RustTarget,
+    cargo_args: Vec<String>,
+    /// The wasm produced by cargo.
+    cargo_wasm_path: PathBuf,
+    /// The wasm of the canister, which differs from the cargo output if it needs post-processing.
     output_wasm_path: PathBuf,
+    /// Whether the candid interface is extracted from the built wasm.
+    extract_candid: bool,
+    /// The checked-in candid file that the extracted interface must match.
+    candid_check_path: Option<PathBuf>,
 }
 
 impl RustCanisterInfo {
@@ -17,9 +30,41 @@ impl RustCanisterInfo {
         &self.package
     }
 
+    pub fn get_features(&self) -> &[String] {
+        &self.features
+    }
+
+    pub fn get_no_default_features(&self) -> bool {
+        self.no_default_features
+    }
+
+    pub fn get_profile(&self) -> &str {
+        &self.profile
+    }
+
+    pub fn get_target(&self) -> RustTarget {
+        self.target
+    }
+
+    pub fn get_cargo_args(&self) -> &[String] {
+        &self.cargo_args
+    }
+
+    pub fn get_cargo_wasm_path(&self) -> &Path {
+        self.cargo_wasm_path.as_path()
+    }
+
     pub fn get_output_wasm_path(&self) -> &Path {
         self.output_wasm_path.as_path()
     }
+
+    pub fn get_extract_candid(&self) -> bool {
+        self.extract_candid
+    }
+
+    pub fn get_candid_check_path(&self) -> Option<&Path> {
+        self.candid_check_path.as_deref()
+    }
 }
 
 impl CanisterInfoFactory for RustCanisterInfo {
@@ -37,8 +82,15 @@ impl CanisterInfoFactory for RustCanisterInfo {
         let CanisterTypeProperties::Rust {
             package,
             crate_name,
-            candid: _,
-            skip_cargo_audit: _,
+            candid,
+            candid_check,
+            features,
+            no_default_features,
+            profile,
+            target: rust_target,
+            cargo_args,
+            network_overrides,
+            ..
         } = info.type_specific.clone()
         else {
             bail!(
@@ -89,14 +141,47 @@ impl CanisterInfoFactory for RustCanisterInfo {
         } else {
             target.name.replace('-', "_")
         };
-        let output_wasm_path = metadata
+
+        let overrides = network_overrides
+            .get(&get_network_context()?)
+            .cloned()
+            .unwrap_or_default();
+        let features = overrides.features.unwrap_or(features);
+        let no_default_features = overrides.no_default_features.unwrap_or(no_default_features);
+        let profile = overrides
+            .profile
+            .or(profile)
+            .unwrap_or_else(|| "release".to_string());
+        let rust_target = overrides.target.unwrap_or(rust_target);
+        let cargo_args = overrides.cargo_args.unwrap_or(cargo_args);
+
+        // Cargo places the output of the built-in `dev` profile in a directory named `debug`.
+        let profile_dir = if profile == "dev" {
+            "debug"
+        } else {
+            profile.as_str()
+        };
+        let output_dir = metadata
             .target_directory
-            .join(format!("wasm32-unknown-unknown/release/{wasm_name}.wasm"))
-            .into();
+            .join(rust_target.as_str())
+            .join(profile_dir);
+        let cargo_wasm_path: PathBuf = output_dir.join(format!("{wasm_name}.wasm")).into();
+        let output_wasm_path = match rust_target {
+            RustTarget::Wasm32UnknownUnknown => cargo_wasm_path.clone(),
+            RustTarget::Wasm32Wasip1 => output_dir.join(format!("{wasm_name}_nowasi.wasm")).into(),
+        };
 
         Ok(Self {
             package,
+            features,
+            no_default_features,
+            profile,
+            target: rust_target,
+            cargo_args,
+            cargo_wasm_path,
             output_wasm_path,
+            extract_candid: candid.as_os_str() == RUST_CANDID_AUTO,
+            candid_check_path: candid_check.map(|path| info.get_workspace_root().join(path)),
         })
     }
 }
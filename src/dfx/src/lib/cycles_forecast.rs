@@ -0,0 +1,228 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use candid::Principal;
+use dfx_core::json::{load_json_file, save_json_file};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const CYCLES_SAMPLES_FILE_NAME: &str = "cycles_samples.json";
+
+/// Only the most recent samples are kept for each canister.
+const MAX_SAMPLES_PER_CANISTER: usize = 500;
+
+/// Samples spanning less than this are too noisy to estimate a burn rate from.
+const MIN_SAMPLE_WINDOW_SECONDS: u64 = 60 * 60;
+
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A canister's cycles balance at a point in time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CyclesSample {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub balance: u128,
+}
+
+/// Cycles balance samples of the canisters on one network, stored in the project's temp directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CyclesSamples {
+    canisters: BTreeMap<String, Vec<CyclesSample>>,
+}
+
+impl CyclesSamples {
+    pub fn load(path: &Path) -> DfxResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(load_json_file(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> DfxResult {
+        if let Some(parent) = path.parent() {
+            dfx_core::fs::create_dir_all(parent)?;
+        }
+        Ok(save_json_file(path, self)?)
+    }
+
+    pub fn get(&self, canister_id: &Principal) -> &[CyclesSample] {
+        self.canisters
+            .get(&canister_id.to_text())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn record(&mut self, canister_id: &Principal, sample: CyclesSample) {
+        let samples = self.canisters.entry(canister_id.to_text()).or_default();
+        samples.push(sample);
+        samples.sort_by_key(|sample| sample.timestamp);
+        if samples.len() > MAX_SAMPLES_PER_CANISTER {
+            samples.drain(..samples.len() - MAX_SAMPLES_PER_CANISTER);
+        }
+    }
+}
+
+pub fn get_cycles_samples_path(env: &dyn Environment) -> DfxResult<PathBuf> {
+    let config = env.get_config_or_anyhow()?;
+    let network_name = &env.get_network_descriptor().name;
+    Ok(config
+        .get_temp_path()?
+        .join(network_name)
+        .join(CYCLES_SAMPLES_FILE_NAME))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BurnRateSource {
+    /// Measured from balance samples covering this many seconds.
+    Samples { count: usize, seconds: u64 },
+    /// Not enough samples; the idle burn reported by the canister status is used.
+    IdleBurn,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BurnRate {
+    pub cycles_per_day: u128,
+    pub source: BurnRateSource,
+}
+
+/// Estimates the daily cycles consumption of a canister.
+///
+/// Intervals in which the balance went up (because the canister was topped up) are ignored.
+/// The result is never lower than the idle burn, which the canister pays regardless of its load.
+pub fn estimate_burn_rate(samples: &[CyclesSample], idle_cycles_burned_per_day: u128) -> BurnRate {
+    let mut burned: u128 = 0;
+    let mut seconds: u64 = 0;
+    for pair in samples.windows(2) {
+        let (earlier, later) = (pair[0], pair[1]);
+        if later.timestamp <= earlier.timestamp || later.balance > earlier.balance {
+            continue;
+        }
+        burned += earlier.balance - later.balance;
+        seconds += later.timestamp - earlier.timestamp;
+    }
+
+    if seconds < MIN_SAMPLE_WINDOW_SECONDS {
+        return BurnRate {
+            cycles_per_day: idle_cycles_burned_per_day,
+            source: BurnRateSource::IdleBurn,
+        };
+    }
+    let measured = burned.saturating_mul(SECONDS_PER_DAY as u128) / seconds as u128;
+    BurnRate {
+        cycles_per_day: measured.max(idle_cycles_burned_per_day),
+        source: BurnRateSource::Samples {
+            count: samples.len(),
+            seconds,
+        },
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CyclesForecast {
+    pub balance: u128,
+    pub burn_rate: BurnRate,
+    /// Balance below which the canister is frozen.
+    pub freezing_balance: u128,
+    /// `None` if the canister does not burn any cycles.
+    pub days_until_frozen: Option<u128>,
+    /// Cycles to add so that the canister runs for the target runway before it is frozen.
+    pub top_up: u128,
+}
+
+pub fn forecast(
+    balance: u128,
+    burn_rate: BurnRate,
+    idle_cycles_burned_per_day: u128,
+    freezing_threshold_seconds: u64,
+    target_runway_seconds: u64,
+) -> CyclesForecast {
+    let per_day = burn_rate.cycles_per_day;
+    let freezing_balance = idle_cycles_burned_per_day
+        .saturating_mul(freezing_threshold_seconds as u128)
+        / SECONDS_PER_DAY as u128;
+    let days_until_frozen =
+        (per_day > 0).then(|| balance.saturating_sub(freezing_balance) / per_day);
+    let target_balance = freezing_balance.saturating_add(
+        per_day.saturating_mul(target_runway_seconds as u128) / SECONDS_PER_DAY as u128,
+    );
+    CyclesForecast {
+        balance,
+        burn_rate,
+        freezing_balance,
+        days_until_frozen,
+        top_up: target_balance.saturating_sub(balance),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const T: u128 = 1_000_000_000_000;
+
+    fn sample(timestamp: u64, balance: u128) -> CyclesSample {
+        CyclesSample { timestamp, balance }
+    }
+
+    #[test]
+    fn burn_rate_falls_back_to_idle_burn() {
+        let rate = estimate_burn_rate(&[sample(0, 5 * T), sample(60, 5 * T - 1)], 1000);
+        assert_eq!(rate.cycles_per_day, 1000);
+        assert_eq!(rate.source, BurnRateSource::IdleBurn);
+    }
+
+    #[test]
+    fn burn_rate_ignores_top_ups() {
+        let day = SECONDS_PER_DAY;
+        let samples = [
+            sample(0, 5 * T),
+            sample(day, 4 * T),
+            // topped up by 10T
+            sample(day + 10, 14 * T),
+            sample(2 * day + 10, 13 * T),
+        ];
+        let rate = estimate_burn_rate(&samples, 0);
+        assert_eq!(rate.cycles_per_day, T);
+        assert_eq!(
+            rate.source,
+            BurnRateSource::Samples {
+                count: 4,
+                seconds: 2 * day
+            }
+        );
+    }
+
+    #[test]
+    fn burn_rate_is_at_least_idle_burn() {
+        let samples = [sample(0, 5 * T), sample(SECONDS_PER_DAY, 5 * T)];
+        assert_eq!(estimate_burn_rate(&samples, 42).cycles_per_day, 42);
+    }
+
+    #[test]
+    fn forecast_runway_and_top_up() {
+        let burn_rate = BurnRate {
+            cycles_per_day: T,
+            source: BurnRateSource::IdleBurn,
+        };
+        // 30 days freezing threshold at 1T per day idle burn
+        let result = forecast(
+            40 * T,
+            burn_rate,
+            T,
+            30 * SECONDS_PER_DAY,
+            90 * SECONDS_PER_DAY,
+        );
+        assert_eq!(result.freezing_balance, 30 * T);
+        assert_eq!(result.days_until_frozen, Some(10));
+        assert_eq!(result.top_up, 80 * T);
+
+        let result = forecast(
+            200 * T,
+            burn_rate,
+            T,
+            30 * SECONDS_PER_DAY,
+            90 * SECONDS_PER_DAY,
+        );
+        assert_eq!(result.top_up, 0);
+    }
+}
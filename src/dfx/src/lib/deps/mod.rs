@@ -78,12 +78,12 @@ impl PulledJson {
     }
 }
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default, Clone)]
 pub struct InitJson {
     canisters: BTreeMap<Principal, InitItem>,
 }
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default, Clone)]
 pub struct InitItem {
     /// Init argument in IDL string
     arg_str: Option<String>,
@@ -120,6 +120,18 @@ impl InitJson {
         self.canisters.contains_key(canister_id)
     }
 
+    /// The init argument in IDL string, if it was not set as raw bytes.
+    pub fn get_arg_str(&self, canister_id: &Principal) -> Option<&str> {
+        self.canisters
+            .get(canister_id)
+            .and_then(|init_item| init_item.arg_str.as_deref())
+    }
+
+    /// Unset the init argument of a pull dependency.
+    pub fn remove(&mut self, canister_id: &Principal) {
+        self.canisters.remove(canister_id);
+    }
+
     pub fn get_arg_raw(&self, canister_id: &Principal) -> DfxResult<Vec<u8>> {
         let init_item = self.canisters.get(canister_id).ok_or_else(|| {
             anyhow!(
@@ -197,6 +209,13 @@ fn get_deps_dir(project_root: &Path) -> PathBuf {
     project_root.join("deps")
 }
 
+/// The default directory of `dfx deps vendor`.
+///
+/// `deps/vendor`.
+pub fn get_vendor_dir(project_root: &Path) -> PathBuf {
+    get_deps_dir(project_root).join("vendor")
+}
+
 /// The path of the candid file of a direct dependency.
 ///
 /// `deps/candid/<PRINCIPAL>.did`.
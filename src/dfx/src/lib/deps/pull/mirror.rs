@@ -0,0 +1,147 @@
+use super::write_to_tempfile_then_rename;
+use crate::lib::deps::{
+    PulledCanister, PulledJson, get_pulled_canister_dir, get_pulled_json_path,
+    get_pulled_service_candid_path, get_pulled_wasm_path, load_pulled_json,
+};
+use crate::lib::error::DfxResult;
+use anyhow::{anyhow, bail};
+use candid::Principal;
+use dfx_core::json::{load_json_file, save_json_file};
+use fn_error_context::context;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// A directory of pulled canisters written by `dfx deps vendor`.
+///
+/// It contains a `pulled.json`, and the wasm module and service candid of each canister
+/// in a directory named after its principal.
+pub struct Mirror {
+    dir: PathBuf,
+    pulled_json: PulledJson,
+    /// The `pulled.json` of the project, if it pulled its dependencies before.
+    project_pulled_json: Option<PulledJson>,
+}
+
+impl Mirror {
+    #[context("Failed to load the mirror at {}.", dir.display())]
+    pub fn load(dir: &Path, project_root: &Path) -> DfxResult<Self> {
+        if !dir.exists() {
+            bail!(
+                "{} does not exist. Run `dfx deps vendor` while online to create it.",
+                dir.display()
+            );
+        }
+        let project_pulled_json = match get_pulled_json_path(project_root).exists() {
+            true => Some(load_pulled_json(project_root)?),
+            false => None,
+        };
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            pulled_json: load_json_file(&dir.join("pulled.json"))?,
+            project_pulled_json,
+        })
+    }
+
+    /// Writes the pulled canisters of a project into `dir`.
+    /// The canisters that are no longer pulled are removed from it.
+    #[context("Failed to write the mirror at {}.", dir.display())]
+    pub fn write(dir: &Path, pulled_json: &PulledJson) -> DfxResult {
+        if dir.exists() {
+            for entry in dfx_core::fs::read_dir(dir)? {
+                let entry = entry?;
+                let stale = entry.file_type()?.is_dir()
+                    && Principal::from_text(entry.file_name().to_string_lossy())
+                        .is_ok_and(|canister_id| !pulled_json.canisters.contains_key(&canister_id));
+                if stale {
+                    dfx_core::fs::remove_dir_all(&entry.path())?;
+                }
+            }
+        }
+        for (canister_id, pulled_canister) in &pulled_json.canisters {
+            let canister_dir = dir.join(canister_id.to_text());
+            if canister_dir.exists() {
+                dfx_core::fs::remove_dir_all(&canister_dir)?;
+            }
+            dfx_core::fs::create_dir_all(&canister_dir)?;
+            dfx_core::fs::copy(
+                &get_pulled_wasm_path(canister_id, pulled_canister.gzip)?,
+                &wasm_path(dir, canister_id, pulled_canister.gzip),
+            )?;
+            dfx_core::fs::copy(
+                &get_pulled_service_candid_path(canister_id)?,
+                &service_candid_path(dir, canister_id),
+            )?;
+        }
+        save_json_file(&dir.join("pulled.json"), pulled_json)?;
+        Ok(())
+    }
+
+    pub fn get_dependencies(&self, canister_id: &Principal) -> DfxResult<Vec<Principal>> {
+        Ok(self.get(canister_id)?.dependencies.clone())
+    }
+
+    /// Copies the canister from the mirror into the pulled cache, after checking the hash of its wasm module.
+    ///
+    /// The hash is checked against the project's `pulled.json`, so that a mirror cannot replace a
+    /// canister that the project pulled before. Canisters that the project did not pull before are
+    /// checked against the mirror's `pulled.json`.
+    pub fn restore(&self, canister_id: &Principal) -> DfxResult<PulledCanister> {
+        let mut pulled_canister = self.get(canister_id)?.clone();
+        // The name of a direct dependency is set by the project that pulls it.
+        pulled_canister.name = None;
+
+        let (expected_hash, expected_by) = match self
+            .project_pulled_json
+            .as_ref()
+            .and_then(|pulled_json| pulled_json.canisters.get(canister_id))
+        {
+            Some(project_canister) => (&project_canister.wasm_hash_download, "project's"),
+            None => (&pulled_canister.wasm_hash_download, "mirror's"),
+        };
+        let mirrored_wasm_path = wasm_path(&self.dir, canister_id, pulled_canister.gzip);
+        let wasm = dfx_core::fs::read(&mirrored_wasm_path)?;
+        let hash = hex::encode(Sha256::digest(&wasm));
+        if hash != *expected_hash {
+            bail!(
+                "The wasm of {canister_id} at {} has hash {hash}, but the {expected_by} pulled.json expects {expected_hash}.",
+                mirrored_wasm_path.display(),
+            );
+        }
+        let service_candid = dfx_core::fs::read(&service_candid_path(&self.dir, canister_id))?;
+
+        let pulled_canister_dir = get_pulled_canister_dir(canister_id)?;
+        if pulled_canister_dir.exists() {
+            dfx_core::fs::remove_dir_all(&pulled_canister_dir)?;
+        }
+        write_to_tempfile_then_rename(
+            &wasm,
+            &get_pulled_wasm_path(canister_id, pulled_canister.gzip)?,
+        )?;
+        write_to_tempfile_then_rename(
+            &service_candid,
+            &get_pulled_service_candid_path(canister_id)?,
+        )?;
+        Ok(pulled_canister)
+    }
+
+    fn get(&self, canister_id: &Principal) -> DfxResult<&PulledCanister> {
+        self.pulled_json.canisters.get(canister_id).ok_or_else(|| {
+            anyhow!(
+                "Canister {canister_id} is not in the mirror at {}. Run `dfx deps vendor` to update it.",
+                self.dir.display()
+            )
+        })
+    }
+}
+
+fn wasm_path(dir: &Path, canister_id: &Principal, gzip: bool) -> PathBuf {
+    let path = dir.join(canister_id.to_text()).join("canister");
+    match gzip {
+        true => path.with_extension("wasm.gz"),
+        false => path.with_extension("wasm"),
+    }
+}
+
+fn service_candid_path(dir: &Path, canister_id: &Principal) -> PathBuf {
+    dir.join(canister_id.to_text()).join("service.did")
+}
@@ -5,7 +5,9 @@ use super::{
 use crate::lib::error::DfxResult;
 use crate::lib::metadata::dfx::DfxMetadata;
 use crate::lib::metadata::names::{CANDID_ARGS, CANDID_SERVICE, DFX};
-use crate::lib::state_tree::canister_info::read_state_tree_canister_module_hash;
+use crate::lib::state_tree::canister_info::{
+    read_state_tree_canister_metadata, read_state_tree_canister_module_hash,
+};
 use crate::lib::wasm::file::{decompress_bytes, read_wasm_module};
 use crate::util::download_file;
 use anyhow::{Context, bail};
@@ -13,7 +15,7 @@ use candid::Principal;
 use dfx_core::config::model::dfinity::Pullable;
 use dfx_core::fs::composite::{ensure_dir_exists, ensure_parent_dir_exists};
 use fn_error_context::context;
-use ic_agent::{Agent, AgentError};
+use ic_agent::Agent;
 use ic_wasm::metadata::get_metadata;
 use sha2::{Digest, Sha256};
 use slog::{Logger, error, info, trace, warn};
@@ -22,10 +24,20 @@ use std::io::Write;
 use std::path::Path;
 
 mod facade;
+mod mirror;
 use facade::{facade_dependencies, facade_download};
+pub use mirror::Mirror;
+
+/// Where `dfx deps pull` gets the canisters from.
+pub enum PullSource<'a> {
+    /// The network that the canisters are deployed on.
+    Network(&'a Agent),
+    /// A directory written by `dfx deps vendor`. The network is not used.
+    Mirror(&'a Mirror),
+}
 
 pub async fn resolve_all_dependencies(
-    agent: &Agent,
+    source: &PullSource<'_>,
     logger: &Logger,
     pull_canisters_in_config: &BTreeMap<String, Principal>,
 ) -> DfxResult<Vec<Principal>> {
@@ -35,14 +47,19 @@ pub async fn resolve_all_dependencies(
     while let Some(canister_id) = canisters_to_resolve.pop_front() {
         if !checked.contains(&canister_id) {
             checked.insert(canister_id);
-            let dependencies = if let Some(deps) = facade_dependencies(&canister_id) {
-                info!(
-                    logger,
-                    "Using facade dependencies for canister {canister_id}."
-                );
-                deps
-            } else {
-                get_dependencies(agent, logger, &canister_id).await?
+            let dependencies = match source {
+                PullSource::Mirror(mirror) => mirror.get_dependencies(&canister_id)?,
+                PullSource::Network(agent) => {
+                    if let Some(deps) = facade_dependencies(&canister_id) {
+                        info!(
+                            logger,
+                            "Using facade dependencies for canister {canister_id}."
+                        );
+                        deps
+                    } else {
+                        get_dependencies(agent, logger, &canister_id).await?
+                    }
+                }
             };
             canisters_to_resolve.extend(dependencies.iter());
         }
@@ -70,15 +87,53 @@ async fn get_dependencies(
     Ok(dependencies)
 }
 
-pub async fn download_all_and_generate_pulled_json(
+/// The version of a pull dependency that is currently deployed on the network.
+pub struct UpstreamCanister {
+    /// The expected module hash, see [`PulledCanister::wasm_hash`].
+    pub wasm_hash: String,
+    pub dependencies: Vec<Principal>,
+}
+
+/// Fetches the `dfx` metadata and the expected module hash of a pull dependency
+/// without downloading its wasm module.
+///
+/// Returns `None` for canisters that are pulled through a facade, which are not checked for updates.
+#[context("Failed to check canister {canister_id} for updates.")]
+pub async fn fetch_upstream_canister(
     agent: &Agent,
     logger: &Logger,
+    canister_id: Principal,
+) -> DfxResult<Option<UpstreamCanister>> {
+    if facade_dependencies(&canister_id).is_some() {
+        return Ok(None);
+    }
+    let dfx_metadata = fetch_dfx_metadata(agent, &canister_id).await?;
+    let pullable = dfx_metadata.get_pullable()?;
+    let hash_on_chain = get_hash_on_chain(agent, logger, canister_id, pullable).await?;
+    Ok(Some(UpstreamCanister {
+        wasm_hash: hex::encode(hash_on_chain),
+        dependencies: pullable.dependencies.clone(),
+    }))
+}
+
+pub async fn download_all_and_generate_pulled_json(
+    source: &PullSource<'_>,
+    logger: &Logger,
     all_dependencies: &[Principal],
 ) -> DfxResult<PulledJson> {
     let mut any_download_fail = false;
     let mut pulled_json = PulledJson::default();
     for canister_id in all_dependencies {
-        match download_and_generate_pulled_canister(agent, logger, *canister_id).await {
+        let pulled_canister = match source {
+            PullSource::Network(agent) => {
+                download_and_generate_pulled_canister(agent, logger, *canister_id).await
+            }
+            PullSource::Mirror(mirror) => {
+                info!(logger, "Pulling canister {canister_id} from the mirror...");
+                mirror.restore(canister_id)
+            }
+        };
+        match pulled_canister {
             Ok(pulled_canister) => {
                 pulled_json.canisters.insert(*canister_id, pulled_canister);
             }
@@ -183,7 +238,7 @@ async fn download_and_generate_pulled_canister(
 }
 
 async fn fetch_dfx_metadata(agent: &Agent, canister_id: &Principal) -> DfxResult<DfxMetadata> {
-    match fetch_metadata(agent, canister_id, DFX).await? {
+    match read_state_tree_canister_metadata(agent, *canister_id, DFX).await? {
         Some(dfx_metadata_raw) => {
             let dfx_metadata_str = String::from_utf8(dfx_metadata_raw)?;
             let dfx_metadata: DfxMetadata = serde_json::from_str(&dfx_metadata_str)?;
@@ -195,39 +250,6 @@ async fn fetch_dfx_metadata(agent: &Agent, canister_id: &Principal) -> DfxResult
     }
 }
 
-#[context("Failed to fetch metadata {metadata} of canister {canister_id}.")]
-async fn fetch_metadata(
-    agent: &Agent,
-    canister_id: &Principal,
-    metadata: &str,
-) -> DfxResult<Option<Vec<u8>>> {
-    match agent
-        .read_state_canister_metadata(*canister_id, metadata)
-        .await
-    {
-        Ok(data) => Ok(Some(data)),
-        Err(agent_error) => match agent_error {
-            // replica returns such error
-            AgentError::HttpError(ref e) => {
-                let status = e.status;
-                let content = String::from_utf8(e.content.clone())?;
-                if status == 404
-                    && content.starts_with(&format!("Custom section {metadata} not found"))
-                {
-                    Ok(None)
-                } else {
-                    bail!(agent_error);
-                }
-            }
-            // ic-ref returns such error when the canister doesn't define the metadata
-            AgentError::LookupPathAbsent(_) => Ok(None),
-            _ => {
-                bail!(agent_error)
-            }
-        },
-    }
-}
-
 // Get expected hash of the canister wasm.
 // If `wasm_hash` is specified in dfx metadata, use it.
 // If `wasm_hash_url` is specified in dfx metadata, download the hash from the url.
@@ -15,17 +15,22 @@ use dfx_core::error::load_dfx_config::LoadDfxConfigError;
 use dfx_core::error::uri::UriError;
 use dfx_core::extension::manager::ExtensionManager;
 use dfx_core::identity::identity_manager::{IdentityManager, InitializeIdentity};
+use dfx_core::network::health::dynamic_route_provider;
 use fn_error_context::context;
+use ic_agent::agent::AgentBuilder;
+use ic_agent::agent::route_provider::RoundRobinRouteProvider;
 use ic_agent::{Agent, Identity};
 use indicatif::MultiProgress;
 use once_cell::sync::OnceCell;
 use pocket_ic::nonblocking::PocketIc;
 use semver::Version;
-use slog::{Logger, Record};
+use slog::{Logger, Record, info};
 use std::borrow::Cow;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tokio::task::block_in_place;
 use url::Url;
 
 #[allow(unused)]
@@ -43,8 +48,9 @@ pub trait Environment: Send + Sync {
 
     fn get_version(&self) -> &Version;
 
-    /// This is value of the name passed to dfx `--identity <name>`
-    /// Notably, it is _not_ the name of the default identity or selected identity
+    /// This is value of the name passed to dfx `--identity <name>`,
+    /// or within an AgentEnvironment, the identity that the project uses on the network.
+    /// Notably, it is _not_ the name of the default identity
     fn get_identity_override(&self) -> Option<&str>;
 
     // Explicit lifetimes are actually needed for mockall to work properly.
@@ -70,6 +76,31 @@ pub trait Environment: Send + Sync {
         )
     }
 
+    /// Like `new_identity_manager`, but selects the identity that the project uses on the network,
+    /// unless `--identity` or DFX_IDENTITY is set.
+    fn new_identity_manager_for_network(&self, network_name: &str) -> DfxResult<IdentityManager> {
+        let project_identity = match (self.get_identity_override(), self.get_config()?) {
+            (None, Some(config)) => config.get_project_identity(network_name)?,
+            _ => None,
+        };
+        let Some(project_identity) = project_identity else {
+            return Ok(self.new_identity_manager()?);
+        };
+        let identity_manager = IdentityManager::new(
+            self.get_logger(),
+            Some(&project_identity),
+            InitializeIdentity::Allow,
+        )?;
+        let global_identity = identity_manager.get_default_identity_name();
+        if project_identity != global_identity {
+            info!(
+                self.get_logger(),
+                "Using identity '{project_identity}' for network '{network_name}' as configured by the project, instead of the global identity '{global_identity}'."
+            );
+        }
+        Ok(identity_manager)
+    }
+
     // Explicit lifetimes are actually needed for mockall to work properly.
     #[allow(clippy::needless_lifetimes, unused)]
     fn log<'a>(&self, record: &Record<'a>) {
@@ -328,9 +359,15 @@ impl Environment for EnvironmentImpl {
 pub struct AgentEnvironment<'a> {
     backend: &'a dyn Environment,
     agent: Agent,
+    /// The agent that routes requests by the health of the providers of the network.
+    /// It is created on first use, so that commands that don't call the network don't wait for the checks.
+    routed_agent: OnceCell<Agent>,
+    identity: Arc<dyn Identity>,
+    timeout: Duration,
     pocketic: Option<PocketIc>,
     network_descriptor: NetworkDescriptor,
     identity_manager: IdentityManager,
+    project_identity: Option<String>,
     effective_canister_id: Option<Principal>,
     canister_id_store: OnceCell<CanisterIdStore>,
 }
@@ -344,7 +381,16 @@ impl<'a> AgentEnvironment<'a> {
         use_identity: Option<&str>,
     ) -> DfxResult<Self> {
         let logger = backend.get_logger().clone();
-        let mut identity_manager = backend.new_identity_manager()?;
+        let (mut identity_manager, project_identity) =
+            if use_identity.is_some() || backend.get_identity_override().is_some() {
+                (backend.new_identity_manager()?, None)
+            } else {
+                let identity_manager =
+                    backend.new_identity_manager_for_network(&network_descriptor.name)?;
+                let project_identity = Some(identity_manager.get_selected_identity_name().clone())
+                    .filter(|name| name != identity_manager.get_default_identity_name());
+                (identity_manager, project_identity)
+            };
         let identity = if let Some(identity_name) = use_identity {
             identity_manager.instantiate_identity_from_name(identity_name, &logger)?
         } else {
@@ -371,7 +417,8 @@ impl<'a> AgentEnvironment<'a> {
                 identity.name()
             );
         }
-        let url = network_descriptor.first_provider()?;
+        network_descriptor.first_provider()?;
+        let identity: Arc<dyn Identity> = Arc::from(identity as Box<dyn Identity>);
         let effective_canister_id = if let Some(d) = &network_descriptor.local_server_descriptor {
             d.effective_config()?
                 .and_then(|c| c.get_effective_canister_id())
@@ -398,10 +445,19 @@ impl<'a> AgentEnvironment<'a> {
 
         Ok(AgentEnvironment {
             backend,
-            agent: create_agent(logger, url, identity, timeout)?,
+            agent: create_agent(
+                logger,
+                &network_descriptor.providers,
+                identity.clone(),
+                timeout,
+            )?,
+            routed_agent: OnceCell::new(),
+            identity,
+            timeout,
             pocketic,
             network_descriptor: network_descriptor.clone(),
             identity_manager,
+            project_identity,
             effective_canister_id,
             canister_id_store: OnceCell::new(),
         })
@@ -455,11 +511,20 @@ impl<'a> Environment for AgentEnvironment<'a> {
     }
 
     fn get_identity_override(&self) -> Option<&str> {
-        self.backend.get_identity_override()
+        self.project_identity
+            .as_deref()
+            .or_else(|| self.backend.get_identity_override())
     }
 
     fn get_agent(&self) -> &Agent {
-        &self.agent
+        let providers = &self.network_descriptor.providers;
+        if providers.len() < 2 {
+            return &self.agent;
+        }
+        self.routed_agent.get_or_init(|| {
+            create_routed_agent(providers, self.identity.clone(), self.timeout)
+                .unwrap_or_else(|| self.agent.clone())
+        })
     }
 
     fn get_pocketic(&self) -> Option<&PocketIc> {
@@ -516,22 +581,52 @@ impl<'a> Environment for AgentEnvironment<'a> {
     }
 }
 
-#[context("Failed to create agent with url {}.", url)]
+/// Creates an agent that routes requests to the `providers` in turn.
+/// Requests that fail to connect are retried with the next provider.
+#[context("Failed to create agent with providers {}.", providers.join(", "))]
 pub fn create_agent(
     _logger: Logger,
-    url: &str,
-    identity: Box<dyn Identity + Send + Sync>,
+    providers: &[String],
+    identity: Arc<dyn Identity>,
     timeout: Duration,
 ) -> DfxResult<Agent> {
+    let route_provider = RoundRobinRouteProvider::new(providers.to_vec())?;
+    let agent = agent_builder(identity, timeout)
+        .with_route_provider(route_provider)
+        .with_max_tcp_error_retries(providers.len().saturating_sub(1))
+        .build()?;
+    Ok(agent)
+}
+
+/// Creates an agent that routes requests by the health and latency of the `providers`.
+/// The route provider checks the providers in tasks of the current runtime, so this returns `None`
+/// outside of a multi-threaded runtime, as well as for providers that can't be routed this way.
+fn create_routed_agent(
+    providers: &[String],
+    identity: Arc<dyn Identity>,
+    timeout: Duration,
+) -> Option<Agent> {
+    let runtime = Handle::try_current()
+        .ok()
+        .filter(|runtime| runtime.runtime_flavor() == RuntimeFlavor::MultiThread)?;
+    let client = reqwest::Client::new();
+    let route_provider =
+        block_in_place(|| runtime.block_on(dynamic_route_provider(providers, client.clone())))?;
+    agent_builder(identity, timeout)
+        .with_http_client(client)
+        .with_arc_route_provider(route_provider)
+        .with_max_tcp_error_retries(providers.len().saturating_sub(1))
+        .build()
+        .ok()
+}
+
+fn agent_builder(identity: Arc<dyn Identity>, timeout: Duration) -> AgentBuilder {
     let disable_query_verification =
         std::env::var("DFX_DISABLE_QUERY_VERIFICATION").is_ok_and(|x| !x.trim().is_empty());
-    let agent = Agent::builder()
-        .with_url(url)
-        .with_boxed_identity(identity)
+    Agent::builder()
+        .with_arc_identity(identity)
         .with_verify_query_signatures(!disable_query_verification)
         .with_ingress_expiry(timeout)
-        .build()?;
-    Ok(agent)
 }
 
 pub fn create_pocketic(url: &Url) -> PocketIc {
@@ -0,0 +1,248 @@
+use crate::config::cache::VersionCache;
+use crate::lib::canister_info::CanisterInfo;
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use dfx_core::config::model::dfinity::{Config, ConfigExtension};
+use dfx_core::extension::lock::{EXTENSIONS_LOCK_FILE_NAME, ExtensionsLock};
+use dfx_core::extension::manager::{ExtensionManager, InstallOutcome};
+use dfx_core::extension::manifest::ExtensionManifest;
+use dfx_core::extension::manifest::extension::{ExtensionHook, ExtensionHookKind};
+use serde::Serialize;
+use slog::{info, warn};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::runtime::Runtime;
+
+/// The extensions declared in the `extensions` field of dfx.json.
+pub struct ProjectExtensions {
+    pub project_root: PathBuf,
+    pub extensions: BTreeMap<String, ConfigExtension>,
+}
+
+impl ProjectExtensions {
+    pub fn from_current_dir() -> DfxResult<Option<Self>> {
+        let working_dir = std::env::current_dir()?;
+        let project =
+            Config::load_project_extensions(&working_dir)?.map(|(project_root, extensions)| {
+                ProjectExtensions {
+                    project_root,
+                    extensions,
+                }
+            });
+        Ok(project)
+    }
+
+    pub fn lock_path(&self) -> PathBuf {
+        self.project_root.join(EXTENSIONS_LOCK_FILE_NAME)
+    }
+
+    /// Whether all declared extensions are installed at the declared version.
+    pub fn is_satisfied(&self, mgr: &ExtensionManager) -> bool {
+        self.extensions.iter().all(|(name, extension)| {
+            mgr.is_extension_installed(name)
+                && ExtensionManifest::load(name, &mgr.dir)
+                    .is_ok_and(|installed| *installed.version == *extension.version)
+        })
+    }
+
+    /// Installs the declared extensions that are missing, and warns about the ones installed at another version.
+    /// The installed versions and archive hashes are pinned in the project's lock file.
+    pub fn install(&self, env: &dyn Environment) -> DfxResult {
+        let logger = env.get_logger();
+        let mgr = env.get_extension_manager();
+        // see `dfx extension install`
+        VersionCache::install(env, &env.get_cache().version_str())?;
+        let lock_path = self.lock_path();
+        let mut lock = ExtensionsLock::load(&lock_path)?;
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+
+        for (name, extension) in &self.extensions {
+            if mgr.is_extension_installed(name) {
+                let installed = ExtensionManifest::load(name, &mgr.dir)?;
+                if *installed.version != *extension.version {
+                    warn!(
+                        logger,
+                        "Extension '{name}' version {} is installed, but dfx.json requires version {}. Run 'dfx extension update {name}' to install it.",
+                        *installed.version,
+                        *extension.version
+                    );
+                }
+                continue;
+            }
+
+            let spinner = env.new_spinner(
+                format!(
+                    "Installing extension '{name}' version {} required by dfx.json",
+                    *extension.version
+                )
+                .into(),
+            );
+            let name_or_url = match &extension.url {
+                Some(url) => url.as_str(),
+                None => name.as_str(),
+            };
+            let outcome = runtime.block_on(mgr.install_extension(
+                name_or_url,
+                None,
+                Some(name.as_str()),
+                Some(&extension.version),
+                Some(&mut lock),
+            ));
+            spinner.finish_and_clear();
+            if let InstallOutcome::Installed(name, version, verification) = outcome? {
+                verification.warn_if_unverified(logger, &name);
+                info!(logger, "Installed extension '{name}' version {version}");
+            }
+            lock.save(&lock_path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Installs the extensions declared in dfx.json that are missing, and warns about the ones installed at another version.
+/// Called before the commands that use project extensions load dfx.json, so that other commands never reach the network for them.
+pub fn install_project_extensions(env: &dyn Environment) -> DfxResult {
+    let Some(project) = ProjectExtensions::from_current_dir()? else {
+        return Ok(());
+    };
+    if project.is_satisfied(env.get_extension_manager()) {
+        return Ok(());
+    }
+    project.install(env)
+}
+
+/// The canister that an extension hook runs for.
+#[derive(Serialize)]
+pub struct HookCanister {
+    pub name: String,
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub canister_type: &'static str,
+    pub wasm_path: Option<PathBuf>,
+    pub candid_path: Option<PathBuf>,
+}
+
+impl HookCanister {
+    /// The paths of the build outputs are only included if the canister is built.
+    pub fn new(info: &CanisterInfo, built: bool) -> Self {
+        Self {
+            name: info.get_name().to_string(),
+            id: info.get_canister_id().ok().map(|id| id.to_text()),
+            canister_type: info.get_type_specific_properties().name(),
+            wasm_path: built.then(|| info.get_build_wasm_path()),
+            candid_path: built.then(|| info.get_service_idl_path()),
+        }
+    }
+}
+
+/// The JSON passed to extension hooks on stdin.
+#[derive(Serialize)]
+struct HookInput<'a> {
+    hook: ExtensionHookKind,
+    network: &'a str,
+    project_root: &'a Path,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    canister: Option<&'a HookCanister>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    canisters: Option<&'a [HookCanister]>,
+}
+
+/// The hooks of the extensions declared in dfx.json.
+/// They are loaded once per command, and run for every canister.
+#[derive(Default)]
+pub struct ExtensionHooks {
+    extensions: Vec<(String, BTreeMap<ExtensionHookKind, ExtensionHook>)>,
+}
+
+impl ExtensionHooks {
+    pub fn load(env: &dyn Environment) -> DfxResult<Self> {
+        let Some(config) = env.get_config()? else {
+            return Ok(Self::default());
+        };
+        let Some(extensions) = &config.get_config().extensions else {
+            return Ok(Self::default());
+        };
+        let mgr = env.get_extension_manager();
+        let mut loaded = vec![];
+        for name in extensions.keys() {
+            if !mgr.is_extension_installed(name) {
+                continue;
+            }
+            let manifest = ExtensionManifest::load(name, &mgr.dir)?;
+            if !manifest.hooks.is_empty() {
+                loaded.push((name.clone(), manifest.hooks));
+            }
+        }
+        Ok(Self { extensions: loaded })
+    }
+
+    /// Runs a per-canister hook of the extensions declared in dfx.json.
+    pub fn run_canister_hooks(
+        &self,
+        env: &dyn Environment,
+        kind: ExtensionHookKind,
+        canister: &HookCanister,
+    ) -> DfxResult {
+        self.run(env, kind, Some(canister), None)
+    }
+
+    /// Runs the `post-deploy` hook of the extensions declared in dfx.json.
+    pub fn run_post_deploy_hooks(
+        &self,
+        env: &dyn Environment,
+        canisters: &[HookCanister],
+    ) -> DfxResult {
+        self.run(env, ExtensionHookKind::PostDeploy, None, Some(canisters))
+    }
+
+    fn run(
+        &self,
+        env: &dyn Environment,
+        kind: ExtensionHookKind,
+        canister: Option<&HookCanister>,
+        canisters: Option<&[HookCanister]>,
+    ) -> DfxResult {
+        if !self
+            .extensions
+            .iter()
+            .any(|(_, hooks)| hooks.contains_key(&kind))
+        {
+            return Ok(());
+        }
+        let config = env.get_config_or_anyhow()?;
+        let mgr = env.get_extension_manager();
+        let network = &env.get_network_descriptor().name;
+        let input = HookInput {
+            hook: kind,
+            network,
+            project_root: config.get_project_root(),
+            canister,
+            canisters,
+        };
+        let mut vars = vec![("DFX_NETWORK", network.clone())];
+        if let Some(canister) = canister {
+            vars.push(("DFX_CANISTER_NAME", canister.name.clone()));
+            if let Some(id) = &canister.id {
+                vars.push(("DFX_CANISTER_ID", id.clone()));
+            }
+            if let Some(wasm_path) = &canister.wasm_path {
+                vars.push(("DFX_WASM_PATH", wasm_path.display().to_string()));
+            }
+            if let Some(candid_path) = &canister.candid_path {
+                vars.push(("DFX_CANDID_PATH", candid_path.display().to_string()));
+            }
+        }
+
+        for (name, hooks) in &self.extensions {
+            if let Some(hook) = hooks.get(&kind) {
+                info!(
+                    env.get_logger(),
+                    "Running the {} hook of extension '{name}'.",
+                    kind.name()
+                );
+                mgr.run_extension_hook(name, kind, hook, &input, &vars, config.get_project_root())?;
+            }
+        }
+        Ok(())
+    }
+}
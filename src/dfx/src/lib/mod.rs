@@ -2,6 +2,7 @@ pub mod agent;
 pub mod builders;
 pub mod canister_info;
 pub mod canister_logs;
+pub mod cycles_forecast;
 pub mod cycles_ledger_types;
 pub mod deps;
 pub mod dfxvm;
@@ -9,6 +10,7 @@ pub mod diagnosis;
 pub mod environment;
 pub mod error;
 pub mod error_code;
+pub mod extension;
 pub mod ic_attributes;
 pub mod identity;
 pub mod info;
@@ -32,6 +34,7 @@ pub mod project;
 pub mod replica;
 pub mod retryable;
 pub mod root_key;
+pub mod scenario;
 pub mod sign;
 pub mod state_tree;
 pub mod subnet;
@@ -5,6 +5,7 @@ use crate::lib::builders::{
 use crate::lib::canister_info::CanisterInfo;
 use crate::lib::environment::Environment;
 use crate::lib::error::{BuildError, DfxError, DfxResult};
+use crate::lib::extension::{ExtensionHooks, HookCanister};
 use crate::lib::metadata::dfx::DfxMetadata;
 use crate::lib::metadata::names::{CANDID_ARGS, CANDID_SERVICE, DFX};
 use crate::lib::wasm::file::{compress_bytes, read_wasm_module};
@@ -16,12 +17,16 @@ use dfx_core::config::model::canister_id_store::CanisterIdStore;
 use dfx_core::config::model::dfinity::{
     CanisterMetadataSection, Config, MetadataVisibility, TechStack, WasmOptLevel,
 };
+use dfx_core::extension::manifest::extension::ExtensionHookKind;
+use dfx_core::json::save_json_file;
 use fn_error_context::context;
 use ic_wasm::metadata::{Kind, add_metadata, remove_metadata};
 use ic_wasm::optimize::OptLevel;
 use itertools::Itertools;
 use petgraph::graph::{DiGraph, NodeIndex};
 use rand::{RngCore, thread_rng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use slog::{Logger, error, info, trace, warn};
 use std::cell::RefCell;
 use std::collections::{BTreeMap, HashSet};
@@ -131,6 +136,7 @@ impl Canister {
         &self,
         env: &dyn Environment,
         logger: &Logger,
+        build_config: &BuildConfig,
         build_output: &BuildOutput,
     ) -> DfxResult {
         let build_output_wasm_path = match &build_output.wasm {
@@ -150,6 +156,9 @@ impl Canister {
         let mut m = read_wasm_module(build_output_wasm_path)?;
         let mut modified = false;
 
+        let mut steps = PostProcessSteps::new(build_config.record_post_process_steps);
+        steps.record("build", || Ok(dfx_core::fs::read(build_output_wasm_path)?))?;
+
         // optimize or shrink
         if let Some(level) = info.get_optimize() {
             trace!(logger, "Optimizing Wasm at level {}", level);
@@ -162,6 +171,7 @@ impl Canister {
             ) {
                 Ok(()) => {
                     modified = true;
+                    steps.record("optimize", || Ok(m.emit_wasm()))?;
                 }
                 Err(e) => {
                     warn!(logger, "Failed to optimize the Wasm module: {}", e);
@@ -173,6 +183,7 @@ impl Canister {
             trace!(logger, "Shrinking Wasm");
             ic_wasm::shrink::shrink(&mut m);
             modified = true;
+            steps.record("shrink", || Ok(m.emit_wasm()))?;
         }
 
         // metadata
@@ -308,12 +319,15 @@ impl Canister {
             add_metadata(&mut m, visibility, name, data);
             modified = true;
         }
+        if !metadata_sections.is_empty() {
+            steps.record("metadata", || Ok(m.emit_wasm()))?;
+        }
 
         // If not modified and not set "gzip" explicitly, copy the wasm file directly so that hash match.
         if !modified && !info.get_gzip() {
             dfx_core::fs::copy(build_output_wasm_path, &wasm_path)?;
             dfx_core::fs::set_permissions_readwrite(&wasm_path)?;
-            return Ok(());
+            return steps.save(&info.get_post_process_steps_path());
         }
 
         let new_bytes = if wasm_path.extension() == Some(OsStr::new("gz")) {
@@ -321,13 +335,15 @@ impl Canister {
             // Unlike using gzip CLI, the compression below only takes the wasm bytes
             // So as long as the wasm bytes are the same, the gzip file will be the same on different platforms.
             trace!(logger, "Compressing Wasm");
-            compress_bytes(&m.emit_wasm())?
+            let compressed = compress_bytes(&m.emit_wasm())?;
+            steps.record("gzip", || Ok(compressed.clone()))?;
+            compressed
         } else {
             m.emit_wasm()
         };
         dfx_core::fs::write(&wasm_path, new_bytes)?;
 
-        Ok(())
+        steps.save(&info.get_post_process_steps_path())
     }
 
     pub(crate) fn candid_post_process(
@@ -402,6 +418,44 @@ fn wasm_opt_level_convert(opt_level: WasmOptLevel) -> OptLevel {
     }
 }
 
+/// The hash of the wasm module after a post-processing step.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PostProcessStep {
+    pub name: String,
+    /// Hex-encoded sha256 of the module.
+    pub hash: String,
+}
+
+/// Records the hashes of the wasm module after each post-processing step, if enabled.
+struct PostProcessSteps {
+    steps: Option<Vec<PostProcessStep>>,
+}
+
+impl PostProcessSteps {
+    fn new(enabled: bool) -> Self {
+        Self {
+            steps: enabled.then(Vec::new),
+        }
+    }
+
+    fn record(&mut self, name: &str, module: impl FnOnce() -> DfxResult<Vec<u8>>) -> DfxResult {
+        if let Some(steps) = &mut self.steps {
+            steps.push(PostProcessStep {
+                name: name.to_string(),
+                hash: hex::encode(Sha256::digest(module()?)),
+            });
+        }
+        Ok(())
+    }
+
+    fn save(self, path: &Path) -> DfxResult {
+        match self.steps {
+            Some(steps) => Ok(save_json_file(path, &steps)?),
+            None => Ok(()),
+        }
+    }
+}
+
 fn separate_candid(path: &Path) -> DfxResult<(String, String, String)> {
     use candid::pretty::candid::{compile, pp_args};
     use candid::types::internal::TypeInner;
@@ -461,6 +515,7 @@ fn check_valid_subtype(compiled_idl_path: &Path, specified_idl_path: &Path) -> D
 pub struct CanisterPool {
     canisters: Vec<Arc<Canister>>,
     logger: Logger,
+    extension_hooks: ExtensionHooks,
 }
 
 struct PoolConstructHelper<'a> {
@@ -515,9 +570,14 @@ impl CanisterPool {
         Ok(CanisterPool {
             canisters: canisters_map,
             logger,
+            extension_hooks: ExtensionHooks::load(env)?,
         })
     }
 
+    pub fn get_extension_hooks(&self) -> &ExtensionHooks {
+        &self.extension_hooks
+    }
+
     pub fn get_canister(&self, canister_id: &CanisterId) -> Option<&Canister> {
         for c in &self.canisters {
             let info = &c.info;
@@ -714,6 +774,11 @@ impl CanisterPool {
         build_config: &BuildConfig,
         canister: &Canister,
     ) -> DfxResult<()> {
+        self.extension_hooks.run_canister_hooks(
+            env,
+            ExtensionHookKind::PreBuild,
+            &HookCanister::new(canister.get_info(), false),
+        )?;
         canister.prebuild(env, self, build_config)
     }
 
@@ -735,11 +800,17 @@ impl CanisterPool {
     ) -> DfxResult<()> {
         canister.candid_post_process(self.get_logger(), build_config, build_output)?;
 
-        canister.wasm_post_process(env, self.get_logger(), build_output)?;
+        canister.wasm_post_process(env, self.get_logger(), build_config, build_output)?;
 
         build_canister_js(&canister.canister_id(), &canister.info)?;
 
-        canister.postbuild(env, self, build_config)
+        canister.postbuild(env, self, build_config)?;
+
+        self.extension_hooks.run_canister_hooks(
+            env,
+            ExtensionHookKind::PostBuild,
+            &HookCanister::new(canister.get_info(), true),
+        )
     }
 
     fn step_postbuild_all(
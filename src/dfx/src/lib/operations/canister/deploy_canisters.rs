@@ -3,6 +3,7 @@ use crate::lib::canister_info::CanisterInfo;
 use crate::lib::canister_info::assets::AssetsCanisterInfo;
 use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
+use crate::lib::extension::HookCanister;
 use crate::lib::ic_attributes::CanisterSettings;
 use crate::lib::installers::assets::prepare_assets_for_proposal;
 use crate::lib::models::canister::CanisterPool;
@@ -19,6 +20,7 @@ use anyhow::{Context, anyhow, bail};
 use candid::Principal;
 use dfx_core::config::model::canister_id_store::CanisterIdStore;
 use dfx_core::config::model::dfinity::Config;
+use dfx_core::extension::manifest::extension::ExtensionHookKind;
 use dfx_core::identity::CallSender;
 use fn_error_context::context;
 use ic_utils::interfaces::management_canister::attributes::{
@@ -153,13 +155,24 @@ pub async fn deploy_canisters(
                 mode_hint,
                 upgrade_unchanged,
                 call_sender,
-                pool,
+                &pool,
                 skip_consent,
                 env_file.as_deref(),
                 no_asset_upgrade,
                 always_assist,
             )
             .await?;
+            let canister_id_store = env.get_canister_id_store()?;
+            let deployed = canisters_to_install
+                .iter()
+                .map(|name| {
+                    let canister_id = canister_id_store.get(name)?;
+                    let info = CanisterInfo::load(&config, name, Some(canister_id))?;
+                    Ok(HookCanister::new(&info, true))
+                })
+                .collect::<DfxResult<Vec<_>>>()?;
+            pool.get_extension_hooks()
+                .run_post_deploy_hooks(env, &deployed)?;
             info!(log, "Deployed canisters.");
         }
         PrepareForProposal(canister_name) => {
@@ -323,7 +336,7 @@ async fn install_canisters(
     mode_hint: &InstallModeHint,
     upgrade_unchanged: bool,
     call_sender: &CallSender,
-    pool: CanisterPool,
+    pool: &CanisterPool,
     skip_consent: bool,
     env_file: Option<&Path>,
     no_asset_upgrade: bool,
@@ -336,6 +349,11 @@ async fn install_canisters(
     for canister_name in canister_names {
         let canister_id = canister_id_store.get(canister_name)?;
         let canister_info = CanisterInfo::load(config, canister_name, Some(canister_id))?;
+        pool.get_extension_hooks().run_canister_hooks(
+            env,
+            ExtensionHookKind::PreInstall,
+            &HookCanister::new(&canister_info, true),
+        )?;
 
         install_canister(
             env,
@@ -348,7 +366,7 @@ async fn install_canisters(
             mode_hint,
             call_sender,
             upgrade_unchanged,
-            Some(&pool),
+            Some(pool),
             skip_consent,
             env_file,
             no_asset_upgrade,
@@ -195,9 +195,9 @@ pub async fn install_canister(
                 (Some(a_cli), Some(a_json)) => {
                     // We want to warn the user when the argument from CLI and json are different.
                     // There are two cases to consider:
-                    // 1. The argument from CLI is in raw format, while the argument from json is always in Candid format.
+                    // 1. The argument from CLI is in raw or JSON format, while the argument from json is always in Candid format.
                     // 2. Both arguments are in Candid format, but they are different.
-                    if argument_type_from_cli == Some("raw") || a_cli != a_json {
+                    if matches!(argument_type_from_cli, Some("raw" | "json")) || a_cli != a_json {
                         warn!(
                             log,
                             "Canister '{0}' has init_arg/init_arg_file in dfx.json: {1},
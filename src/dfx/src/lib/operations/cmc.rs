@@ -2,9 +2,10 @@ use crate::lib::error::{
     DfxResult, NotifyCreateCanisterError, NotifyMintCyclesError, NotifyTopUpError,
 };
 use crate::lib::ledger_types::{
-    BlockHeight, BlockIndex, MAINNET_CYCLE_MINTER_CANISTER_ID, MAINNET_LEDGER_CANISTER_ID, Memo,
-    NotifyCreateCanisterArg, NotifyCreateCanisterResult, NotifyMintCyclesArg,
-    NotifyMintCyclesResult, NotifyMintCyclesSuccess, NotifyTopUpArg, NotifyTopUpResult,
+    AccountIdBlob, BlockHeight, BlockIndex, MAINNET_CYCLE_MINTER_CANISTER_ID,
+    MAINNET_LEDGER_CANISTER_ID, Memo, NotifyCreateCanisterArg, NotifyCreateCanisterResult,
+    NotifyMintCyclesArg, NotifyMintCyclesResult, NotifyMintCyclesSuccess, NotifyTopUpArg,
+    NotifyTopUpResult,
 };
 use crate::lib::nns_types::account_identifier::{AccountIdentifier, Subaccount};
 use crate::lib::nns_types::icpts::ICPTs;
@@ -17,10 +18,12 @@ use icrc_ledger_types::icrc1::account::Subaccount as ICRCSubaccount;
 use icrc_ledger_types::icrc1::transfer::Memo as ICRCMemo;
 use slog::Logger;
 
-const NOTIFY_CREATE_CANISTER_METHOD: &str = "notify_create_canister";
-const NOTIFY_TOP_UP_METHOD: &str = "notify_top_up";
+pub(crate) const NOTIFY_CREATE_CANISTER_METHOD: &str = "notify_create_canister";
+pub(crate) const NOTIFY_TOP_UP_METHOD: &str = "notify_top_up";
 const NOTIFY_MINT_CYCLES_METHOD: &str = "notify_mint_cycles";
 
+pub const MEMO_TOP_UP_CANISTER: u64 = 1347768404_u64;
+
 pub async fn transfer_cmc(
     agent: &Agent,
     logger: &Logger,
@@ -31,9 +34,6 @@ pub async fn transfer_cmc(
     to_principal: Principal,
     created_at_time: Option<u64>,
 ) -> DfxResult<BlockHeight> {
-    let to_subaccount = Subaccount::from(&to_principal);
-    let to =
-        AccountIdentifier::new(MAINNET_CYCLE_MINTER_CANISTER_ID, Some(to_subaccount)).to_address();
     transfer(
         agent,
         logger,
@@ -42,19 +42,24 @@ pub async fn transfer_cmc(
         amount,
         fee,
         from_subaccount,
-        to,
+        cmc_account(to_principal),
         created_at_time,
     )
     .await
 }
 
+/// The account of the cycles minting canister that receives the ICP for `to_principal`.
+pub fn cmc_account(to_principal: Principal) -> AccountIdBlob {
+    let to_subaccount = Subaccount::from(&to_principal);
+    AccountIdentifier::new(MAINNET_CYCLE_MINTER_CANISTER_ID, Some(to_subaccount)).to_address()
+}
+
 pub async fn notify_create(
     agent: &Agent,
     controller: Principal,
     block_height: BlockHeight,
     subnet_selection: SubnetSelectionType,
 ) -> Result<Principal, NotifyCreateCanisterError> {
-    let user_subnet_selection = subnet_selection.get_user_choice();
     let caller = agent
         .get_principal()
         .map_err(NotifyCreateCanisterError::GetCallerPrincipal)?;
@@ -64,23 +69,12 @@ pub async fn notify_create(
             NOTIFY_CREATE_CANISTER_METHOD,
         )
         .with_arg(
-            Encode!(&NotifyCreateCanisterArg {
-                block_index: block_height,
-                controller: caller, // Must be the caller since https://forum.dfinity.org/t/nns-update-2024-05-15-cycles-minting-canister-hotfix-proposal-129728/30807
-                subnet_selection: user_subnet_selection,
-                settings: Some(CanisterSettings {
-                    controllers: Some(vec![controller]),
-                    compute_allocation: None,
-                    memory_allocation: None,
-                    freezing_threshold: None,
-                    reserved_cycles_limit: None,
-                    wasm_memory_limit: None,
-                    wasm_memory_threshold: None,
-                    log_visibility: None,
-                    log_memory_limit: None,
-                    environment_variables: None,
-                })
-            })
+            Encode!(&notify_create_arg(
+                caller,
+                controller,
+                block_height,
+                subnet_selection
+            ))
             .map_err(NotifyCreateCanisterError::EncodeArguments)?,
         )
         .await
@@ -90,6 +84,31 @@ pub async fn notify_create(
         .map_err(NotifyCreateCanisterError::Notify)
 }
 
+pub fn notify_create_arg(
+    caller: Principal,
+    controller: Principal,
+    block_height: BlockHeight,
+    subnet_selection: SubnetSelectionType,
+) -> NotifyCreateCanisterArg {
+    NotifyCreateCanisterArg {
+        block_index: block_height,
+        controller: caller, // Must be the caller since https://forum.dfinity.org/t/nns-update-2024-05-15-cycles-minting-canister-hotfix-proposal-129728/30807
+        subnet_selection: subnet_selection.get_user_choice(),
+        settings: Some(CanisterSettings {
+            controllers: Some(vec![controller]),
+            compute_allocation: None,
+            memory_allocation: None,
+            freezing_threshold: None,
+            reserved_cycles_limit: None,
+            wasm_memory_limit: None,
+            wasm_memory_threshold: None,
+            log_visibility: None,
+            log_memory_limit: None,
+            environment_variables: None,
+        }),
+    }
+}
+
 pub async fn notify_top_up(
     agent: &Agent,
     canister: Principal,
@@ -37,9 +37,11 @@ use icrc_ledger_types::icrc2::transfer_from::TransferFromError;
 use slog::{Logger, info};
 
 const WITHDRAW_METHOD: &str = "withdraw";
+/// The fee the cycles ledger charges for a withdrawal, in cycles.
+pub const WITHDRAW_FEE: u128 = 100_000_000;
 const CREATE_CANISTER_METHOD: &str = "create_canister";
 const CYCLES_LEDGER_DEPOSIT_METHOD: &str = "deposit";
-const CYCLES_LEDGER_CANISTER_ID: Principal =
+pub(crate) const CYCLES_LEDGER_CANISTER_ID: Principal =
     Principal::from_slice(&[0x00, 0x00, 0x00, 0x00, 0x02, 0x10, 0x00, 0x02, 0x01, 0x01]);
 
 pub async fn balance(
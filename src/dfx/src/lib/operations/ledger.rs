@@ -39,7 +39,7 @@ use slog::{Logger, info};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 const ACCOUNT_BALANCE_METHOD: &str = "account_balance";
-const TRANSFER_METHOD: &str = "transfer";
+pub(crate) const TRANSFER_METHOD: &str = "transfer";
 
 pub async fn balance(
     agent: &Agent,
@@ -0,0 +1,400 @@
+//! Scenarios for `dfx canister run-script`.
+//!
+//! A scenario is a YAML or JSON file with a sequence of canister calls.
+//! Each step can check the result of its call and capture values from it,
+//! which later steps refer to as `${name}`.
+use crate::lib::error::DfxResult;
+use anyhow::{Context, bail};
+use fn_error_context::context;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// The name of the test suite in the report. Defaults to the file name.
+    pub name: Option<String>,
+
+    /// The initial values of the variables.
+    #[serde(default)]
+    pub variables: BTreeMap<String, Value>,
+
+    pub steps: Vec<Step>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    /// Defaults to `<canister>.<method>`.
+    pub name: Option<String>,
+
+    /// The name or id of the canister to call.
+    pub canister: String,
+
+    pub method: String,
+
+    /// The argument in the format selected with `type`.
+    /// A structured value, rather than a string, is passed as a JSON argument.
+    pub args: Option<Value>,
+
+    pub r#type: Option<ArgumentType>,
+
+    /// Sends a query call, even if the method is not a query method.
+    #[serde(default)]
+    pub query: bool,
+
+    /// Calls with this identity instead of the selected one.
+    pub identity: Option<String>,
+
+    /// Calls on behalf of this principal. Only works for a local PocketIC instance.
+    pub impersonate: Option<String>,
+
+    /// The .did file with which to encode the argument and decode the result.
+    pub candid: Option<PathBuf>,
+
+    /// The Candid values that the call must return.
+    pub expect: Option<String>,
+
+    /// Text that the error of the call must contain. The step fails if the call succeeds.
+    pub expect_error: Option<String>,
+
+    /// JSON pointers into the JSON result and the values they must point to.
+    #[serde(default)]
+    pub assert: BTreeMap<String, Value>,
+
+    /// Variable names and the JSON pointers into the JSON result whose values they capture.
+    #[serde(default)]
+    pub capture: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgumentType {
+    Idl,
+    Raw,
+    Json,
+}
+
+impl ArgumentType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArgumentType::Idl => "idl",
+            ArgumentType::Raw => "raw",
+            ArgumentType::Json => "json",
+        }
+    }
+}
+
+impl Scenario {
+    /// Files with the `.json` extension are read as JSON, all others as YAML.
+    #[context("Failed to load scenario {}.", path.display())]
+    pub fn load(path: &Path) -> DfxResult<Self> {
+        let content = dfx_core::fs::read_to_string(path)?;
+        let scenario = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&content)?
+        } else {
+            serde_yaml_ng::from_str(&content)?
+        };
+        Ok(scenario)
+    }
+}
+
+impl Step {
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("{}.{}", self.canister, self.method))
+    }
+}
+
+/// The variables of a scenario and the values that steps captured.
+pub struct Variables(BTreeMap<String, Value>);
+
+impl Variables {
+    pub fn new(initial: BTreeMap<String, Value>) -> Self {
+        Self(initial)
+    }
+
+    /// Replaces `${name}` with the value of the variable.
+    /// Strings are inserted without quotes, other values as JSON.
+    pub fn substitute_text(&self, text: &str) -> DfxResult<String> {
+        let mut result = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("${") {
+            result.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find('}') else {
+                bail!("Unterminated variable reference in '{text}'.");
+            };
+            let name = &rest[start + 2..start + end];
+            match self.get(name)? {
+                Value::String(value) => result.push_str(value),
+                value => result.push_str(&value.to_string()),
+            }
+            rest = &rest[start + end + 1..];
+        }
+        result.push_str(rest);
+        Ok(result)
+    }
+
+    /// Substitutes the variables in all strings of a structured argument.
+    /// A string that consists of a single reference is replaced by the value itself, keeping its JSON type.
+    pub fn substitute_value(&self, value: &Value) -> DfxResult<Value> {
+        Ok(match value {
+            Value::String(text) => {
+                match text.strip_prefix("${").and_then(|t| t.strip_suffix('}')) {
+                    Some(name) if !name.contains('}') => self.get(name)?.clone(),
+                    _ => Value::String(self.substitute_text(text)?),
+                }
+            }
+            Value::Array(values) => Value::Array(
+                values
+                    .iter()
+                    .map(|value| self.substitute_value(value))
+                    .collect::<DfxResult<_>>()?,
+            ),
+            Value::Object(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, value)| Ok((key.clone(), self.substitute_value(value)?)))
+                    .collect::<DfxResult<_>>()?,
+            ),
+            value => value.clone(),
+        })
+    }
+
+    pub fn capture(&mut self, captures: &BTreeMap<String, String>, result: &Value) -> DfxResult {
+        for (name, pointer) in captures {
+            let value = result.pointer(pointer).with_context(|| {
+                format!("Cannot capture '{name}', because the result has no value at '{pointer}'.")
+            })?;
+            self.0.insert(name.clone(), value.clone());
+        }
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> DfxResult<&Value> {
+        self.0
+            .get(name)
+            .with_context(|| format!("Unknown variable '{name}'."))
+    }
+}
+
+/// Checks the values that the JSON pointers point to.
+/// Numbers also match strings with the same digits, because large integers are strings in the JSON result.
+pub fn check_assertions(assertions: &BTreeMap<String, Value>, result: &Value) -> DfxResult {
+    for (pointer, expected) in assertions {
+        let Some(actual) = result.pointer(pointer) else {
+            bail!("Expected {expected} at '{pointer}', but the result has no value there.");
+        };
+        if !json_matches(expected, actual) {
+            bail!("Expected {expected} at '{pointer}', got {actual}.");
+        }
+    }
+    Ok(())
+}
+
+fn json_matches(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::Number(expected), Value::String(actual)) => expected.to_string() == *actual,
+        (Value::Array(expected), Value::Array(actual)) => {
+            expected.len() == actual.len()
+                && expected
+                    .iter()
+                    .zip(actual)
+                    .all(|(expected, actual)| json_matches(expected, actual))
+        }
+        (Value::Object(expected), Value::Object(actual)) => {
+            expected.len() == actual.len()
+                && expected.iter().all(|(key, expected)| {
+                    actual
+                        .get(key)
+                        .is_some_and(|actual| json_matches(expected, actual))
+                })
+        }
+        (expected, actual) => expected == actual,
+    }
+}
+
+pub enum Outcome {
+    Passed,
+    Failed(String),
+    Skipped,
+}
+
+struct TestCase {
+    name: String,
+    duration: Duration,
+    outcome: Outcome,
+}
+
+/// The results of the steps, written in the JUnit XML format that CI systems understand.
+pub struct TestReport {
+    suite: String,
+    cases: Vec<TestCase>,
+}
+
+impl TestReport {
+    pub fn new(suite: String) -> Self {
+        Self {
+            suite,
+            cases: vec![],
+        }
+    }
+
+    pub fn push(&mut self, name: String, duration: Duration, outcome: Outcome) {
+        self.cases.push(TestCase {
+            name,
+            duration,
+            outcome,
+        });
+    }
+
+    pub fn failures(&self) -> usize {
+        self.count(|outcome| matches!(outcome, Outcome::Failed(_)))
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.count(|outcome| matches!(outcome, Outcome::Skipped))
+    }
+
+    fn count(&self, predicate: impl Fn(&Outcome) -> bool) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| predicate(&case.outcome))
+            .count()
+    }
+
+    pub fn to_junit_xml(&self) -> String {
+        let time: Duration = self.cases.iter().map(|case| case.duration).sum();
+        let suite = escape_xml(&self.suite);
+        let (tests, failures, skipped) = (self.cases.len(), self.failures(), self.skipped());
+        let time = time.as_secs_f64();
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            r#"<testsuites tests="{tests}" failures="{failures}" skipped="{skipped}" time="{time:.3}">"#
+        );
+        let _ = writeln!(
+            xml,
+            r#"  <testsuite name="{suite}" tests="{tests}" failures="{failures}" skipped="{skipped}" time="{time:.3}">"#
+        );
+        for case in &self.cases {
+            let _ = write!(
+                xml,
+                r#"    <testcase name="{}" classname="{suite}" time="{:.3}""#,
+                escape_xml(&case.name),
+                case.duration.as_secs_f64()
+            );
+            match &case.outcome {
+                Outcome::Passed => xml.push_str("/>\n"),
+                Outcome::Failed(message) => {
+                    let message = escape_xml(message);
+                    let _ = writeln!(
+                        xml,
+                        ">\n      <failure message=\"{message}\">{message}</failure>\n    </testcase>"
+                    );
+                }
+                Outcome::Skipped => xml.push_str(">\n      <skipped/>\n    </testcase>\n"),
+            }
+        }
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn substitutes_variables() {
+        let variables = Variables::new(BTreeMap::from([
+            ("owner".to_string(), json!("aaaaa-aa")),
+            ("amount".to_string(), json!(5)),
+        ]));
+        assert_eq!(
+            variables
+                .substitute_text(r#"(principal "${owner}", ${amount})"#)
+                .unwrap(),
+            r#"(principal "aaaaa-aa", 5)"#
+        );
+        assert_eq!(
+            variables
+                .substitute_value(
+                    &json!({"to": "${owner}", "amount": "${amount}", "memo": "x${amount}"})
+                )
+                .unwrap(),
+            json!({"to": "aaaaa-aa", "amount": 5, "memo": "x5"})
+        );
+        assert!(variables.substitute_text("${missing}").is_err());
+    }
+
+    #[test]
+    fn checks_assertions() {
+        let result = json!([{"balance": "100", "owner": "aaaaa-aa"}]);
+        let assertions = BTreeMap::from([
+            ("/0/balance".to_string(), json!(100)),
+            ("/0/owner".to_string(), json!("aaaaa-aa")),
+        ]);
+        assert!(check_assertions(&assertions, &result).is_ok());
+        let assertions = BTreeMap::from([("/0/balance".to_string(), json!(99))]);
+        assert_eq!(
+            check_assertions(&assertions, &result)
+                .unwrap_err()
+                .to_string(),
+            r#"Expected 99 at '/0/balance', got "100"."#
+        );
+    }
+
+    #[test]
+    fn writes_junit_xml() {
+        let mut report = TestReport::new("smoke".to_string());
+        report.push(
+            "greet".to_string(),
+            Duration::from_millis(1500),
+            Outcome::Passed,
+        );
+        report.push(
+            "transfer <1>".to_string(),
+            Duration::from_millis(250),
+            Outcome::Failed("Expected \"ok\"".to_string()),
+        );
+        report.push("refund".to_string(), Duration::ZERO, Outcome::Skipped);
+        assert_eq!(
+            report.to_junit_xml(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="3" failures="1" skipped="1" time="1.750">
+  <testsuite name="smoke" tests="3" failures="1" skipped="1" time="1.750">
+    <testcase name="greet" classname="smoke" time="1.500"/>
+    <testcase name="transfer &lt;1&gt;" classname="smoke" time="0.250">
+      <failure message="Expected &quot;ok&quot;">Expected &quot;ok&quot;</failure>
+    </testcase>
+    <testcase name="refund" classname="smoke" time="0.000">
+      <skipped/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+}
@@ -1,12 +1,15 @@
+use crate::lib::environment::Environment;
 use crate::lib::error::DfxResult;
 use anyhow::{Context, anyhow, bail};
 use candid::Principal;
 use fn_error_context::context;
 use ic_agent::RequestId;
+use ic_agent::agent::Envelope;
 use serde::{Deserialize, Serialize};
 use serde_cbor::Value;
-use std::convert::TryFrom;
-use time::{Duration, OffsetDateTime};
+use std::convert::{TryFrom, TryInto};
+use std::time::SystemTime;
+use time::{Duration, OffsetDateTime, UtcOffset};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub(crate) struct SignedMessageV1 {
@@ -77,6 +80,16 @@ impl SignedMessageV1 {
 
     #[context("Failed to validate signed message.")]
     pub fn validate(&self) -> DfxResult {
+        self.validate_content()?;
+        if OffsetDateTime::now_utc() > self.expiration {
+            bail!("The message has been expired at: {}", self.expiration);
+        }
+        Ok(())
+    }
+
+    /// Checks that the signed content matches the other fields of the message.
+    /// Unlike `validate`, this accepts expired messages.
+    pub fn validate_content(&self) -> DfxResult {
         if self.version != 1 {
             bail!("Invalid message: version must be 1");
         }
@@ -108,9 +121,6 @@ impl SignedMessageV1 {
                             expiration_from_cbor
                         )
                     }
-                    if OffsetDateTime::now_utc() > expiration_from_cbor {
-                        bail!("The message has been expired at: {expiration_from_cbor}");
-                    }
                 }
                 let sender = m
                     .get(&Value::Text("sender".to_string()))
@@ -175,24 +185,100 @@ impl SignedMessageV1 {
         }
         Ok(())
     }
+
+    /// Decodes the signed envelope of the call.
+    pub fn envelope(&self) -> DfxResult<Envelope<'static>> {
+        decode_envelope(&self.content)
+    }
+
+    /// Decodes the signed envelope of the request_status call, which accompanies update calls.
+    pub fn request_status_envelope(&self) -> DfxResult<Option<Envelope<'static>>> {
+        self.signed_request_status
+            .as_deref()
+            .map(decode_envelope)
+            .transpose()
+    }
+}
+
+/// Signs an update call with the selected identity, together with the request_status call
+/// that reads its result. The message is valid for `timeout`.
+pub(crate) fn sign_update(
+    env: &dyn Environment,
+    canister_id: Principal,
+    effective_canister_id: Principal,
+    method_name: &str,
+    arg: Vec<u8>,
+    timeout: std::time::Duration,
+) -> DfxResult<SignedMessageV1> {
+    let agent = env.get_agent();
+    let network_descriptor = env.get_network_descriptor();
+    let network = network_descriptor
+        .providers
+        .first()
+        .expect("Cannot get network provider (url).")
+        .to_string();
+    let sender = env
+        .get_selected_identity_principal()
+        .expect("Selected identity not instantiated.");
+
+    let expiration_system_time = SystemTime::now()
+        .checked_add(timeout)
+        .ok_or_else(|| anyhow!("Time wrapped around."))?;
+    let creation = OffsetDateTime::now_utc();
+    let expiration = creation
+        .checked_add(timeout.try_into()?)
+        .ok_or_else(|| anyhow!("Expiration datetime overflow."))?;
+
+    let signed_update = agent
+        .update(&canister_id, method_name)
+        .with_effective_canister_id(effective_canister_id)
+        .with_arg(arg.clone())
+        .expire_at(expiration_system_time)
+        .sign()?;
+    let request_id = signed_update.request_id;
+    let signed_request_status = agent.sign_request_status(canister_id, request_id)?;
+    Ok(SignedMessageV1::new(
+        creation,
+        expiration,
+        network,
+        network_descriptor.is_ic,
+        sender,
+        canister_id,
+        method_name.to_string(),
+        arg,
+    )
+    .with_call_type("update".to_string())
+    .with_request_id(request_id)
+    .with_content(hex::encode(&signed_update.signed_update))
+    .with_signed_request_status(hex::encode(signed_request_status.signed_request_status)))
+}
+
+fn decode_envelope(content: &str) -> DfxResult<Envelope<'static>> {
+    let content = hex::decode(content).context("Failed to decode content.")?;
+    serde_cbor::from_slice(&content).context("Invalid cbor data in the content of the message.")
+}
+
+/// Formats a date in the format of the message file.
+pub fn format_utc(datetime: OffsetDateTime) -> String {
+    let utc = datetime.to_offset(UtcOffset::UTC);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        utc.year(),
+        u8::from(utc.month()),
+        utc.day(),
+        utc.hour(),
+        utc.minute(),
+        utc.second()
+    )
 }
 
 mod date_time_utc {
+    use super::format_utc;
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
     use time::{OffsetDateTime, PrimitiveDateTime, UtcOffset};
 
     pub fn serialize<S: Serializer>(datetime: &OffsetDateTime, s: S) -> Result<S::Ok, S::Error> {
-        let utc = datetime.to_offset(UtcOffset::UTC);
-        let formatted = format!(
-            "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
-            utc.year(),
-            u8::from(utc.month()),
-            utc.day(),
-            utc.hour(),
-            utc.minute(),
-            utc.second()
-        );
-        formatted.serialize(s)
+        format_utc(*datetime).serialize(s)
     }
 
     pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<OffsetDateTime, D::Error> {
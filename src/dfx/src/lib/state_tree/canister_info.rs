@@ -1,6 +1,7 @@
 use crate::lib::error::DfxResult;
 use anyhow::{Context, anyhow, bail};
 use candid::Principal;
+use fn_error_context::context;
 use ic_agent::{Agent, AgentError};
 
 pub async fn read_state_tree_canister_controllers(
@@ -35,3 +36,37 @@ pub async fn read_state_tree_canister_module_hash(
 
     Ok(module_hash)
 }
+
+/// None if the module of the canister has no metadata section with this name.
+#[context("Failed to fetch metadata {metadata} of canister {canister_id}.")]
+pub async fn read_state_tree_canister_metadata(
+    agent: &Agent,
+    canister_id: Principal,
+    metadata: &str,
+) -> DfxResult<Option<Vec<u8>>> {
+    match agent
+        .read_state_canister_metadata(canister_id, metadata)
+        .await
+    {
+        Ok(data) => Ok(Some(data)),
+        Err(agent_error) => match agent_error {
+            // replica returns such error
+            AgentError::HttpError(ref e) => {
+                let status = e.status;
+                let content = String::from_utf8(e.content.clone())?;
+                if status == 404
+                    && content.starts_with(&format!("Custom section {metadata} not found"))
+                {
+                    Ok(None)
+                } else {
+                    bail!(agent_error);
+                }
+            }
+            // ic-ref returns such error when the canister doesn't define the metadata
+            AgentError::LookupPathAbsent(_) => Ok(None),
+            _ => {
+                bail!(agent_error)
+            }
+        },
+    }
+}
@@ -43,6 +43,11 @@ static TELEMETRY: OnceLock<Option<Mutex<Telemetry>>> = OnceLock::new();
 
 const SEND_SIZE_THRESHOLD_BYTES: u64 = 256 * 1024;
 
+/// The number of sent batches that are kept so that they can be inspected.
+const SENT_BATCHES_TO_KEEP: usize = 10;
+
+pub const DEFAULT_ENDPOINT: &str = "https://sdk.dfinity.network/telemetry";
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "kebab-case")]
 enum ArgumentSource {
@@ -72,15 +77,23 @@ pub struct Telemetry {
     allowlisted_canisters: BTreeSet<Principal>,
     week: Option<String>,
     publish: bool,
+    endpoint: Option<Url>,
+}
+
+/// A batch of telemetry records that is assigned for sending.
+#[derive(Serialize)]
+pub struct TelemetryBatch {
+    pub id: String,
+    pub records: Vec<serde_json::Value>,
 }
 
 impl Telemetry {
-    pub fn init(mode: TelemetryState) {
+    pub fn init(mode: TelemetryState, endpoint: Option<Url>) {
         if mode.should_collect() {
+            let mut telemetry = Telemetry::default().with_publish(mode.should_publish());
+            telemetry.endpoint = endpoint;
             TELEMETRY
-                .set(Some(Mutex::new(
-                    Telemetry::default().with_publish(mode.should_publish()),
-                )))
+                .set(Some(Mutex::new(telemetry)))
                 .expect("Telemetry already initialized");
         } else {
             TELEMETRY.set(None).expect("Telemetry already initialized");
@@ -127,6 +140,24 @@ impl Telemetry {
         Ok(Self::get_telemetry_dir()?.join("send"))
     }
 
+    pub fn get_sent_dir() -> DfxResult<PathBuf> {
+        Ok(Self::get_telemetry_dir()?.join("sent"))
+    }
+
+    /// The URL that telemetry is sent to.
+    pub fn get_endpoint(env: &dyn Environment) -> String {
+        match &env
+            .get_tool_config()
+            .lock()
+            .unwrap()
+            .interface()
+            .telemetry_endpoint
+        {
+            Some(endpoint) => endpoint.to_string(),
+            None => DEFAULT_ENDPOINT.to_string(),
+        }
+    }
+
     pub fn set_platform() {
         with_telemetry(|telemetry| {
             telemetry.platform =
@@ -275,7 +306,7 @@ impl Telemetry {
     pub fn maybe_publish() -> DfxResult {
         try_with_telemetry(|telemetry| {
             if telemetry.publish && (Self::check_send_time()? || Self::check_file_size()?) {
-                Self::launch_publisher()?;
+                Self::launch_publisher(telemetry.endpoint.as_ref())?;
             }
 
             Ok(())
@@ -283,11 +314,14 @@ impl Telemetry {
     }
 
     #[context("failed to launch publisher")]
-    pub fn launch_publisher() -> DfxResult {
+    pub fn launch_publisher(endpoint: Option<&Url>) -> DfxResult {
         let mut exe = std::env::current_exe()?;
         let mut cmd = std::process::Command::new(exe);
-        cmd.arg("_send-telemetry")
-            .stdin(Stdio::null())
+        cmd.arg("_send-telemetry");
+        if let Some(endpoint) = endpoint {
+            cmd.args(["--url", endpoint.as_str()]);
+        }
+        cmd.stdin(Stdio::null())
             .stdout(Stdio::null())
             .stderr(Stdio::null());
 
@@ -490,11 +524,27 @@ impl Telemetry {
 
         backoff::retry_notify(policy, op, notify)?;
 
+        // Keep what was sent, so that it can be inspected with `dfx telemetry show`.
+        let sent_dir = Self::get_sent_dir()?;
+        fs::create_dir_all(&sent_dir)?;
+        fs::write(&sent_dir.join(batch.to_string()), &final_payload)?;
         fs::remove_file(&batch_path)?;
+        Self::prune_sent_batches()?;
 
         Ok(())
     }
 
+    fn prune_sent_batches() -> DfxResult {
+        let sent_dir = Self::get_sent_dir()?;
+        let mut batches = Self::list_batches_in(&sent_dir)?;
+        if batches.len() > SENT_BATCHES_TO_KEEP {
+            for batch in batches.drain(..batches.len() - SENT_BATCHES_TO_KEEP) {
+                fs::remove_file(&sent_dir.join(batch.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+
     fn add_batch_and_sequence_to_batch(content: String, batch: &Uuid) -> DfxResult<String> {
         // Process each line, adding batch ID and sequence number
         let modified_json_docs: Vec<String> = content
@@ -517,25 +567,98 @@ impl Telemetry {
     }
 
     fn list_batches() -> DfxResult<Vec<Uuid>> {
-        let send_dir = Self::get_send_dir()?;
-        if !send_dir.exists() {
+        Self::list_batches_in(&Self::get_send_dir()?)
+    }
+
+    /// Lists the batches in a directory, oldest first.
+    fn list_batches_in(dir: &Path) -> DfxResult<Vec<Uuid>> {
+        if !dir.exists() {
             return Ok(vec![]);
         }
-        let send_dir = Self::get_send_dir()?;
-        let dir_content = dfx_core::fs::read_dir(&send_dir)?;
+        let dir_content = dfx_core::fs::read_dir(dir)?;
 
-        let batches = dir_content
+        let mut batches: Vec<_> = dir_content
             .filter_map(|v| {
                 let dir_entry = v.ok()?;
                 if dir_entry.file_type().is_ok_and(|e| e.is_file()) {
-                    Uuid::parse_str(&dir_entry.file_name().to_string_lossy()).ok()
+                    let batch = Uuid::parse_str(&dir_entry.file_name().to_string_lossy()).ok()?;
+                    let modified = dir_entry.metadata().and_then(|m| m.modified()).ok();
+                    Some((modified, batch))
                 } else {
                     None
                 }
             })
             .collect();
-        Ok(batches)
+        batches.sort();
+        Ok(batches.into_iter().map(|(_, batch)| batch).collect())
+    }
+
+    /// The records in the active log, which are not yet assigned to a batch.
+    pub fn read_log_records() -> DfxResult<Vec<serde_json::Value>> {
+        let log_path = Self::get_log_path()?;
+        if !log_path.exists() {
+            return Ok(vec![]);
+        }
+        read_records(&log_path)
+    }
+
+    /// The batches that are waiting to be sent, oldest first.
+    pub fn read_pending_batches() -> DfxResult<Vec<TelemetryBatch>> {
+        read_batches(&Self::get_send_dir()?)
+    }
+
+    /// The most recently sent batches, oldest first.
+    pub fn read_sent_batches() -> DfxResult<Vec<TelemetryBatch>> {
+        read_batches(&Self::get_sent_dir()?)
     }
+
+    /// When the pending records are sent next, if a send is scheduled.
+    pub fn read_next_send_time() -> DfxResult<Option<NaiveDateTime>> {
+        let send_time_path = Self::get_send_time_path()?;
+        if !send_time_path.exists() {
+            return Ok(None);
+        }
+        Self::read_send_time(&send_time_path).map(Some)
+    }
+
+    /// Deletes the active log and all pending and sent batches.
+    pub fn clear() -> DfxResult {
+        let log_path = Self::get_log_path()?;
+        if log_path.exists() {
+            let mut file = FdRwLock::new(OpenOptions::new().append(true).open(&log_path)?);
+            let lock = file.write()?;
+            fs::remove_file(&log_path)?;
+        }
+        for dir in [Self::get_send_dir()?, Self::get_sent_dir()?] {
+            if dir.exists() {
+                fs::remove_dir_all(&dir)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_records(path: &Path) -> DfxResult<Vec<serde_json::Value>> {
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .with_context(|| format!("failed to parse telemetry record in {}", path.display()))
+        })
+        .collect()
+}
+
+fn read_batches(dir: &Path) -> DfxResult<Vec<TelemetryBatch>> {
+    Telemetry::list_batches_in(dir)?
+        .into_iter()
+        .map(|batch| {
+            Ok(TelemetryBatch {
+                id: batch.to_string(),
+                records: read_records(&dir.join(batch.to_string()))?,
+            })
+        })
+        .collect()
 }
 
 fn try_with_telemetry(f: impl FnOnce(&mut Telemetry) -> DfxResult) -> DfxResult {
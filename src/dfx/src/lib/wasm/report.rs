@@ -0,0 +1,447 @@
+use crate::lib::error::DfxResult;
+use crate::lib::wasm::file::{bytes_to_module, compress_bytes, decompress_bytes};
+use anyhow::bail;
+use byte_unit::Byte;
+use clap::ValueEnum;
+use fn_error_context::context;
+use serde::Serialize;
+use std::path::Path;
+use walrus::ExportItem;
+
+const KIB: u64 = 1024;
+const MIB: u64 = 1024 * KIB;
+
+/// Modules larger than this (gzipped) can not be installed in a single message
+/// and are uploaded in chunks.
+const MAX_INSTALL_MESSAGE_SIZE: u64 = 2 * MIB;
+/// The largest module that can be installed with a chunked upload.
+const MAX_CHUNKED_WASM_SIZE: u64 = 100 * MIB;
+/// The largest module after decompression.
+const MAX_DECOMPRESSED_WASM_SIZE: u64 = 100 * MIB;
+/// The total size of all `icp:` custom sections.
+const MAX_CUSTOM_SECTIONS_SIZE: u64 = 2 * MIB;
+
+const CUSTOM_SECTION_ID: u8 = 0;
+const CODE_SECTION_ID: u8 = 10;
+
+/// Number of functions and data segments listed in the report.
+const LARGEST_ENTRIES: usize = 10;
+
+#[derive(ValueEnum, Clone, Copy, Default)]
+pub enum WasmReportFormat {
+    #[default]
+    Table,
+    Json,
+}
+
+#[derive(Serialize)]
+pub struct WasmReport {
+    pub canister: String,
+    pub wasm_size: u64,
+    pub gzip_size: u64,
+    pub sections: Vec<SectionSize>,
+    pub largest_functions: Vec<FunctionSize>,
+    pub data_segment_count: usize,
+    pub largest_data_segments: Vec<DataSegmentSize>,
+    pub metadata_sections: Vec<MetadataSection>,
+    pub exported_methods: Vec<ExportedMethod>,
+    pub limits: Vec<Limit>,
+}
+
+#[derive(Serialize)]
+pub struct SectionSize {
+    pub name: String,
+    pub size: u64,
+}
+
+#[derive(Serialize)]
+pub struct FunctionSize {
+    pub index: u32,
+    pub name: Option<String>,
+    pub size: u64,
+}
+
+#[derive(Serialize)]
+pub struct DataSegmentSize {
+    pub index: u32,
+    pub size: u64,
+}
+
+#[derive(Serialize)]
+pub struct MetadataSection {
+    pub name: String,
+    pub visibility: String,
+    pub size: u64,
+}
+
+#[derive(Serialize)]
+pub struct ExportedMethod {
+    pub name: String,
+    /// `query`, `update`, `composite_query` or `system`.
+    pub kind: String,
+}
+
+#[derive(Serialize)]
+pub struct Limit {
+    pub name: String,
+    pub size: u64,
+    pub limit: u64,
+}
+
+impl Limit {
+    pub fn exceeded(&self) -> bool {
+        self.size > self.limit
+    }
+}
+
+impl WasmReport {
+    /// Creates the report for the wasm module at `path`, which may be gzipped.
+    #[context("Failed to create a report for {}.", path.display())]
+    pub fn from_file(canister: &str, path: &Path) -> DfxResult<Self> {
+        let bytes = dfx_core::fs::read(path)?;
+        let (wasm, gzip_size) = if path.extension().is_some_and(|ext| ext == "gz") {
+            let gzip_size = bytes.len() as u64;
+            (decompress_bytes(&bytes)?, gzip_size)
+        } else {
+            let gzip_size = compress_bytes(&bytes)?.len() as u64;
+            (bytes, gzip_size)
+        };
+        Self::from_bytes(canister, &wasm, gzip_size)
+    }
+
+    fn from_bytes(canister: &str, wasm: &[u8], gzip_size: u64) -> DfxResult<Self> {
+        let mut sections: Vec<SectionSize> = vec![];
+        let mut function_sizes = vec![];
+        let mut metadata_sections = vec![];
+        for (id, contents) in read_sections(wasm)? {
+            let name = section_name(id);
+            let size = contents.len() as u64;
+            match sections.iter_mut().find(|section| section.name == name) {
+                Some(section) => section.size += size,
+                None => sections.push(SectionSize {
+                    name: name.to_string(),
+                    size,
+                }),
+            }
+            match id {
+                CODE_SECTION_ID => function_sizes = read_function_sizes(contents)?,
+                CUSTOM_SECTION_ID => {
+                    let (section_name, data) = read_custom_section(contents)?;
+                    let visibility_and_name = section_name
+                        .strip_prefix("icp:public ")
+                        .map(|name| ("public", name))
+                        .or_else(|| {
+                            section_name
+                                .strip_prefix("icp:private ")
+                                .map(|name| ("private", name))
+                        });
+                    if let Some((visibility, name)) = visibility_and_name {
+                        metadata_sections.push(MetadataSection {
+                            name: name.to_string(),
+                            visibility: visibility.to_string(),
+                            size: data.len() as u64,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // walrus keeps the local functions in the order of the code section.
+        let module = bytes_to_module(wasm)?;
+        let imported_functions = module.funcs.iter().count() - module.funcs.iter_local().count();
+        let mut largest_functions: Vec<FunctionSize> = module
+            .funcs
+            .iter_local()
+            .zip(function_sizes)
+            .enumerate()
+            .map(|(i, ((id, _), size))| FunctionSize {
+                index: (imported_functions + i) as u32,
+                name: module.funcs.get(id).name.clone(),
+                size,
+            })
+            .collect();
+        largest_functions.sort_by(|a, b| b.size.cmp(&a.size));
+        largest_functions.truncate(LARGEST_ENTRIES);
+
+        let data_segment_count = module.data.iter().count();
+        let mut largest_data_segments: Vec<DataSegmentSize> = module
+            .data
+            .iter()
+            .enumerate()
+            .map(|(index, data)| DataSegmentSize {
+                index: index as u32,
+                size: data.value.len() as u64,
+            })
+            .collect();
+        largest_data_segments.sort_by(|a, b| b.size.cmp(&a.size));
+        largest_data_segments.truncate(LARGEST_ENTRIES);
+
+        let exported_methods = module
+            .exports
+            .iter()
+            .filter(|export| matches!(export.item, ExportItem::Function(_)))
+            .filter_map(|export| exported_method(&export.name))
+            .collect();
+
+        let wasm_size = wasm.len() as u64;
+        let custom_sections_size = metadata_sections.iter().map(|section| section.size).sum();
+        let limits = vec![
+            Limit {
+                name: "Gzipped module (single message install)".to_string(),
+                size: gzip_size,
+                limit: MAX_INSTALL_MESSAGE_SIZE,
+            },
+            Limit {
+                name: "Gzipped module (chunked install)".to_string(),
+                size: gzip_size,
+                limit: MAX_CHUNKED_WASM_SIZE,
+            },
+            Limit {
+                name: "Decompressed module".to_string(),
+                size: wasm_size,
+                limit: MAX_DECOMPRESSED_WASM_SIZE,
+            },
+            Limit {
+                name: "Metadata sections".to_string(),
+                size: custom_sections_size,
+                limit: MAX_CUSTOM_SECTIONS_SIZE,
+            },
+        ];
+
+        Ok(Self {
+            canister: canister.to_string(),
+            wasm_size,
+            gzip_size,
+            sections,
+            largest_functions,
+            data_segment_count,
+            largest_data_segments,
+            metadata_sections,
+            exported_methods,
+            limits,
+        })
+    }
+
+    /// Prints the reports. In JSON, they are printed as a single array, also for a single report,
+    /// so that the output has the same shape for `dfx build --report` and `dfx canister wasm-report`.
+    pub fn print_all(reports: &[WasmReport], format: WasmReportFormat) -> DfxResult {
+        match format {
+            WasmReportFormat::Json => println!("{}", serde_json::to_string_pretty(reports)?),
+            WasmReportFormat::Table => {
+                for report in reports {
+                    report.print_table();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn print_table(&self) {
+        println!("Wasm report for canister '{}':", self.canister);
+        println!("  Size: {}", bytes(self.wasm_size));
+        println!("  Gzipped size: {}", bytes(self.gzip_size));
+
+        println!("Sections:");
+        for section in &self.sections {
+            println!("  {:<12} {:>12}", section.name, bytes(section.size));
+        }
+
+        println!("Largest functions:");
+        for function in &self.largest_functions {
+            let name = match &function.name {
+                Some(name) => name.clone(),
+                None => format!("func[{}]", function.index),
+            };
+            println!("  {:>12}  {name}", bytes(function.size));
+        }
+
+        println!("Data segments ({}):", self.data_segment_count);
+        for segment in &self.largest_data_segments {
+            println!("  {:>12}  segment[{}]", bytes(segment.size), segment.index);
+        }
+
+        println!("Metadata sections:");
+        for section in &self.metadata_sections {
+            println!(
+                "  {:>12}  {} ({})",
+                bytes(section.size),
+                section.name,
+                section.visibility
+            );
+        }
+
+        println!("Exported methods:");
+        for method in &self.exported_methods {
+            println!("  {:<16} {}", method.kind, method.name);
+        }
+
+        println!("Limits:");
+        for limit in &self.limits {
+            println!(
+                "  {:<40} {:>12} of {:>12}{}",
+                limit.name,
+                bytes(limit.size),
+                bytes(limit.limit),
+                if limit.exceeded() { "  EXCEEDED" } else { "" }
+            );
+        }
+    }
+
+    /// Fails if the gzipped module is larger than the budget.
+    pub fn check_budget(&self, budget: Byte) -> DfxResult {
+        let budget = budget.get_bytes() as u64;
+        if self.gzip_size > budget {
+            bail!(
+                "The gzipped wasm module of canister '{}' is {}, which exceeds the budget of {}.",
+                self.canister,
+                bytes(self.gzip_size),
+                bytes(budget)
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Splits a wasm module into the ids and contents of its sections.
+fn read_sections(wasm: &[u8]) -> DfxResult<Vec<(u8, &[u8])>> {
+    if wasm.len() < 8 || &wasm[..4] != b"\0asm" {
+        bail!("Not a wasm module.");
+    }
+    let mut sections = vec![];
+    let mut pos = 8;
+    while pos < wasm.len() {
+        let id = wasm[pos];
+        pos += 1;
+        let size = read_u32(wasm, &mut pos)? as usize;
+        sections.push((id, read_bytes(wasm, &mut pos, size)?));
+    }
+    Ok(sections)
+}
+
+/// The sizes of the function bodies in the code section, in order.
+fn read_function_sizes(code_section: &[u8]) -> DfxResult<Vec<u64>> {
+    let mut pos = 0;
+    let count = read_u32(code_section, &mut pos)?;
+    let mut sizes = vec![];
+    for _ in 0..count {
+        let size = read_u32(code_section, &mut pos)? as usize;
+        read_bytes(code_section, &mut pos, size)?;
+        sizes.push(size as u64);
+    }
+    Ok(sizes)
+}
+
+/// The name and data of a custom section.
+fn read_custom_section(custom_section: &[u8]) -> DfxResult<(&str, &[u8])> {
+    let mut pos = 0;
+    let name_len = read_u32(custom_section, &mut pos)? as usize;
+    let name = std::str::from_utf8(read_bytes(custom_section, &mut pos, name_len)?)?;
+    Ok((name, &custom_section[pos..]))
+}
+
+/// Reads an unsigned LEB128 encoded u32.
+fn read_u32(bytes: &[u8], pos: &mut usize) -> DfxResult<u32> {
+    let mut result: u32 = 0;
+    for shift in (0..35).step_by(7) {
+        let Some(&byte) = bytes.get(*pos) else {
+            bail!("Unexpected end of the wasm module.");
+        };
+        *pos += 1;
+        result |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+    bail!("Invalid integer in the wasm module.")
+}
+
+fn read_bytes<'a>(bytes: &'a [u8], pos: &mut usize, len: usize) -> DfxResult<&'a [u8]> {
+    let Some(slice) = pos.checked_add(len).and_then(|end| bytes.get(*pos..end)) else {
+        bail!("Unexpected end of the wasm module.");
+    };
+    *pos += len;
+    Ok(slice)
+}
+
+fn section_name(id: u8) -> &'static str {
+    match id {
+        0 => "custom",
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "datacount",
+        13 => "tag",
+        _ => "unknown",
+    }
+}
+
+fn exported_method(export_name: &str) -> Option<ExportedMethod> {
+    let method = |kind: &str, name: &str| ExportedMethod {
+        name: name.to_string(),
+        kind: kind.to_string(),
+    };
+    if let Some(name) = export_name.strip_prefix("canister_query ") {
+        Some(method("query", name))
+    } else if let Some(name) = export_name.strip_prefix("canister_update ") {
+        Some(method("update", name))
+    } else if let Some(name) = export_name.strip_prefix("canister_composite_query ") {
+        Some(method("composite_query", name))
+    } else if export_name.starts_with("canister_") {
+        Some(method("system", export_name))
+    } else {
+        None
+    }
+}
+
+fn bytes(size: u64) -> String {
+    Byte::from_bytes(size as u128)
+        .get_appropriate_unit(true)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_exported_methods() {
+        let query = exported_method("canister_query greet").unwrap();
+        assert_eq!(
+            (query.kind.as_str(), query.name.as_str()),
+            ("query", "greet")
+        );
+        let system = exported_method("canister_init").unwrap();
+        assert_eq!(
+            (system.kind.as_str(), system.name.as_str()),
+            ("system", "canister_init")
+        );
+        assert!(exported_method("memory").is_none());
+    }
+
+    #[test]
+    fn reads_sections() {
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+            0x0a, 0x07, 0x02, 0x02, 0x00, 0x0b, 0x02, 0x00, 0x0b, // code: 2 functions
+            0x00, 0x05, 0x02, 0x69, 0x64, 0x01, 0x02, // custom section "id"
+        ];
+        let sections = read_sections(&wasm).unwrap();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].0, CODE_SECTION_ID);
+        assert_eq!(read_function_sizes(sections[0].1).unwrap(), vec![2, 2]);
+        assert_eq!(
+            read_custom_section(sections[1].1).unwrap(),
+            ("id", &[0x01, 0x02][..])
+        );
+        assert!(read_sections(&wasm[..12]).is_err());
+    }
+}
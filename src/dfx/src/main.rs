@@ -3,6 +3,7 @@ use crate::config::{dfx_version, dfx_version_str};
 use crate::lib::diagnosis::{DiagnosedError, diagnose};
 use crate::lib::environment::{Environment, EnvironmentImpl};
 use crate::lib::error::DfxResult;
+use crate::lib::extension::{ProjectExtensions, install_project_extensions};
 use crate::lib::logger::{LoggingMode, create_root_logger};
 use crate::lib::project::templates::builtin_templates;
 use crate::lib::telemetry::Telemetry;
@@ -147,15 +148,35 @@ fn get_args_altered_for_extension_run(
 ) -> DfxResult<Vec<OsString>> {
     let mut args = std::env::args_os().collect::<Vec<OsString>>();
 
-    let installed_extensions = installed.as_clap_commands()?;
-    if !installed_extensions.is_empty() {
-        let mut app = CliOpts::command_for_update().subcommands(&installed_extensions);
+    let mut extension_commands = installed.as_clap_commands()?;
+    // Extensions declared in dfx.json but not installed yet are installed by `dfx extension run`.
+    // An invalid dfx.json is reported by the commands that load it.
+    let declared: Vec<String> = match ProjectExtensions::from_current_dir() {
+        Ok(Some(project)) => project
+            .extensions
+            .into_keys()
+            .filter(|name| !installed.contains(name))
+            .collect(),
+        _ => vec![],
+    };
+    extension_commands.extend(declared.iter().map(|name| {
+        clap::Command::new(name)
+            .about("Extension declared in dfx.json. It is installed on first use.")
+            .arg(
+                clap::Arg::new("params")
+                    .num_args(0..)
+                    .trailing_var_arg(true)
+                    .allow_hyphen_values(true),
+            )
+    }));
+    if !extension_commands.is_empty() {
+        let mut app = CliOpts::command_for_update().subcommands(&extension_commands);
         sort_clap_commands(&mut app);
         // here clap will display the help message if no subcommand was provided...
         let app = app.get_matches();
         // ...therefore we can safely unwrap here because we know a subcommand was provided
         let subcmd = app.subcommand().unwrap().0;
-        if installed.contains(subcmd) {
+        if installed.contains(subcmd) || declared.iter().any(|name| name == subcmd) {
             let idx = args.iter().position(|arg| arg == subcmd).unwrap();
             args.splice(idx..idx, ["extension", "run"].iter().map(OsString::from));
         }
@@ -165,7 +186,14 @@ fn get_args_altered_for_extension_run(
 
 fn inner_main(log_level: &mut Option<i64>) -> DfxResult {
     let tool_config = ToolConfig::new()?;
-    Telemetry::init(tool_config.interface().telemetry);
+    Telemetry::init(
+        tool_config.interface().telemetry,
+        tool_config
+            .interface()
+            .telemetry_endpoint
+            .as_ref()
+            .map(|endpoint| endpoint.0.clone()),
+    );
     Telemetry::allowlist_canisters(default_allowlisted_canisters());
 
     let em = ExtensionManager::new(dfx_version())?;
@@ -219,6 +247,11 @@ fn inner_main(log_level: &mut Option<i64>) -> DfxResult {
         eprintln!("{DEPRECATION_NOTICE}");
     }
 
+    // Installed before the command loads dfx.json, which can use canister types of the declared extensions.
+    if commands::uses_project_extensions(&cli_opts.command) {
+        install_project_extensions(&env)?;
+    }
+
     commands::exec(&env, cli_opts.command)
 }
 
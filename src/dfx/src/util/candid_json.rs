@@ -0,0 +1,469 @@
+//! Conversions between JSON and Candid values, directed by the Candid types of a method.
+//!
+//! Arguments are converted from JSON as follows, and results are converted to JSON the same way,
+//! except for `opt` values:
+//! - Numbers can be JSON numbers or strings, so that nat64 and bigger values keep their precision.
+//!   In results, `nat`, `int`, `nat64` and `int64` are always strings.
+//! - Blobs are hex-encoded strings, or arrays of numbers. Results can encode blobs as base64 instead.
+//! - Principals and services are strings in their textual representation.
+//! - `opt` values are `null` or the value itself. Missing record fields of `opt` type are `null`.
+//!   Results encode `opt` values as an empty array or an array of the value, like `idl2json` did,
+//!   so that `opt null` and `opt opt` values stay distinguishable.
+//! - Variants are objects with a single field, or strings for cases without a value.
+//! - Tuples are arrays.
+use crate::lib::error::DfxResult;
+use anyhow::{Context, anyhow, bail};
+use candid::types::value::{IDLField, IDLValue, VariantValue};
+use candid::types::{Field, Label, Type, TypeEnv, TypeInner};
+use candid::{IDLArgs, Int, Nat, Principal};
+use clap::ValueEnum;
+use serde_json::{Map, Number, Value, json};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Parses a JSON argument and converts it to the argument types of a method.
+/// A method with a single argument takes the value itself, other methods take an array of values.
+pub fn json_to_idl_args(arguments: &str, env: &TypeEnv, types: &[Type]) -> DfxResult<IDLArgs> {
+    let json: Value = serde_json::from_str(arguments).context("Invalid JSON argument.")?;
+    let args = match (types, &json) {
+        ([ty], _) => vec![json_to_idl_value(&json, env, ty, "$")?],
+        ([], Value::Null) => vec![],
+        (_, Value::Array(values)) if values.len() == types.len() => values
+            .iter()
+            .zip(types)
+            .enumerate()
+            .map(|(index, (value, ty))| json_to_idl_value(value, env, ty, &format!("$[{index}]")))
+            .collect::<DfxResult<Vec<_>>>()?,
+        _ => bail!(
+            "The method takes {} arguments, so the JSON argument must be an array of {} values.",
+            types.len(),
+            types.len()
+        ),
+    };
+    Ok(IDLArgs::new(&args))
+}
+
+fn json_to_idl_value(json: &Value, env: &TypeEnv, ty: &Type, path: &str) -> DfxResult<IDLValue> {
+    let ty = env.trace_type(ty)?;
+    let value = match (ty.as_ref(), json) {
+        (TypeInner::Null, Value::Null) => IDLValue::Null,
+        (TypeInner::Reserved, _) => IDLValue::Reserved,
+        (TypeInner::Bool, Value::Bool(b)) => IDLValue::Bool(*b),
+        (TypeInner::Text, Value::String(s)) => IDLValue::Text(s.clone()),
+        (TypeInner::Nat, _) => IDLValue::Nat(parse_number::<Nat>(json, path, &ty)?),
+        (TypeInner::Nat8, _) => IDLValue::Nat8(parse_number(json, path, &ty)?),
+        (TypeInner::Nat16, _) => IDLValue::Nat16(parse_number(json, path, &ty)?),
+        (TypeInner::Nat32, _) => IDLValue::Nat32(parse_number(json, path, &ty)?),
+        (TypeInner::Nat64, _) => IDLValue::Nat64(parse_number(json, path, &ty)?),
+        (TypeInner::Int, _) => IDLValue::Int(parse_number::<Int>(json, path, &ty)?),
+        (TypeInner::Int8, _) => IDLValue::Int8(parse_number(json, path, &ty)?),
+        (TypeInner::Int16, _) => IDLValue::Int16(parse_number(json, path, &ty)?),
+        (TypeInner::Int32, _) => IDLValue::Int32(parse_number(json, path, &ty)?),
+        (TypeInner::Int64, _) => IDLValue::Int64(parse_number(json, path, &ty)?),
+        (TypeInner::Float32, _) => IDLValue::Float32(parse_number(json, path, &ty)?),
+        (TypeInner::Float64, _) => IDLValue::Float64(parse_number(json, path, &ty)?),
+        (TypeInner::Principal, Value::String(s)) => IDLValue::Principal(parse_principal(s, path)?),
+        (TypeInner::Service(_), Value::String(s)) => IDLValue::Service(parse_principal(s, path)?),
+        (TypeInner::Opt(_), Value::Null) => IDLValue::None,
+        (TypeInner::Opt(inner), _) => {
+            IDLValue::Opt(Box::new(json_to_idl_value(json, env, inner, path)?))
+        }
+        (TypeInner::Vec(inner), Value::String(s)) if is_blob(env, inner)? => IDLValue::Blob(
+            hex::decode(s).with_context(|| format!("Expected a hex-encoded blob at {path}."))?,
+        ),
+        (TypeInner::Vec(inner), Value::Array(values)) => IDLValue::Vec(
+            values
+                .iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    json_to_idl_value(value, env, inner, &format!("{path}[{index}]"))
+                })
+                .collect::<DfxResult<Vec<_>>>()?,
+        ),
+        (TypeInner::Record(fields), Value::Array(values)) if is_tuple(fields) => {
+            if values.len() != fields.len() {
+                bail!(
+                    "Expected a tuple of {} values at {path}, but found {} values.",
+                    fields.len(),
+                    values.len()
+                );
+            }
+            IDLValue::Record(
+                fields
+                    .iter()
+                    .zip(values)
+                    .map(|(field, value)| {
+                        let path = format!("{path}[{}]", field.id.get_id());
+                        let val = json_to_idl_value(value, env, &field.ty, &path)?;
+                        Ok(IDLField {
+                            id: field.id.as_ref().clone(),
+                            val,
+                        })
+                    })
+                    .collect::<DfxResult<Vec<_>>>()?,
+            )
+        }
+        (TypeInner::Record(fields), Value::Object(object)) => {
+            if let Some(unknown) = object
+                .keys()
+                .find(|key| !fields.iter().any(|field| field_name(&field.id) == **key))
+            {
+                bail!("Unknown field '{unknown}' at {path}. Expected a value of type {ty}.");
+            }
+            IDLValue::Record(
+                fields
+                    .iter()
+                    .map(|field| {
+                        let name = field_name(&field.id);
+                        let path = format!("{path}.{name}");
+                        let val = match object.get(&name) {
+                            Some(value) => json_to_idl_value(value, env, &field.ty, &path)?,
+                            None => missing_field_value(env, &field.ty)?
+                                .ok_or_else(|| anyhow!("Missing field {path}."))?,
+                        };
+                        Ok(IDLField {
+                            id: field.id.as_ref().clone(),
+                            val,
+                        })
+                    })
+                    .collect::<DfxResult<Vec<_>>>()?,
+            )
+        }
+        (TypeInner::Variant(fields), Value::String(_) | Value::Object(_)) => {
+            let null = Value::Null;
+            let (name, value) = match json {
+                Value::Object(object) if object.len() == 1 => object.iter().next().unwrap(),
+                Value::String(name) => (name, &null),
+                _ => bail!("Expected an object with a single field at {path} for type {ty}."),
+            };
+            let (index, field) = fields
+                .iter()
+                .enumerate()
+                .find(|(_, field)| field_name(&field.id) == *name)
+                .ok_or_else(|| anyhow!("Unknown variant case '{name}' at {path} for type {ty}."))?;
+            let val = json_to_idl_value(value, env, &field.ty, &format!("{path}.{name}"))?;
+            IDLValue::Variant(VariantValue(
+                Box::new(IDLField {
+                    id: field.id.as_ref().clone(),
+                    val,
+                }),
+                index as u64,
+            ))
+        }
+        _ => bail!("Expected a value of type {ty} at {path}, but found {json}."),
+    };
+    Ok(value)
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum BlobFormat {
+    #[default]
+    Hex,
+    Base64,
+}
+
+/// Converts the results of a method to JSON, one value per result.
+/// With the result types of the method, hashed field names are replaced by the names from the types.
+pub fn idl_args_to_json(
+    args: &IDLArgs,
+    method_type: Option<(&TypeEnv, &[Type])>,
+    blob_format: BlobFormat,
+) -> Vec<Value> {
+    let empty_env = TypeEnv::new();
+    let env = method_type.map_or(&empty_env, |(env, _)| env);
+    args.args
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let ty = method_type.and_then(|(_, types)| types.get(index));
+            idl_to_json(value, ty, env, blob_format)
+        })
+        .collect()
+}
+
+fn idl_to_json(value: &IDLValue, ty: Option<&Type>, env: &TypeEnv, format: BlobFormat) -> Value {
+    let ty = ty.and_then(|ty| env.trace_type(ty).ok());
+    let inner = ty.as_ref().map(|ty| ty.as_ref());
+    match value {
+        IDLValue::Null | IDLValue::Reserved => Value::Null,
+        IDLValue::None => Value::Array(vec![]),
+        IDLValue::Bool(b) => Value::Bool(*b),
+        IDLValue::Text(text) | IDLValue::Number(text) => Value::String(text.clone()),
+        IDLValue::Nat(n) => Value::String(n.0.to_string()),
+        IDLValue::Int(i) => Value::String(i.0.to_string()),
+        IDLValue::Nat8(n) => json!(n),
+        IDLValue::Nat16(n) => json!(n),
+        IDLValue::Nat32(n) => json!(n),
+        IDLValue::Nat64(n) => Value::String(n.to_string()),
+        IDLValue::Int8(i) => json!(i),
+        IDLValue::Int16(i) => json!(i),
+        IDLValue::Int32(i) => json!(i),
+        IDLValue::Int64(i) => Value::String(i.to_string()),
+        IDLValue::Float32(f) => float_to_json(f64::from(*f)),
+        IDLValue::Float64(f) => float_to_json(*f),
+        IDLValue::Principal(principal) | IDLValue::Service(principal) => {
+            Value::String(principal.to_text())
+        }
+        IDLValue::Func(principal, method) => {
+            json!({ "principal": principal.to_text(), "method": method })
+        }
+        IDLValue::Opt(value) => {
+            let inner_ty = match inner {
+                Some(TypeInner::Opt(inner_ty)) => Some(inner_ty),
+                _ => None,
+            };
+            Value::Array(vec![idl_to_json(value, inner_ty, env, format)])
+        }
+        IDLValue::Blob(bytes) => blob_to_json(bytes, format),
+        IDLValue::Vec(values) => {
+            let element_ty = match inner {
+                Some(TypeInner::Vec(element_ty)) => Some(element_ty),
+                _ => None,
+            };
+            // Without a type, an empty vector could be a blob or not,
+            // so only vectors typed as `vec nat8` are blobs, whatever their length.
+            let is_blob = element_ty.is_some_and(|element_ty| {
+                env.trace_type(element_ty)
+                    .is_ok_and(|element_ty| matches!(element_ty.as_ref(), TypeInner::Nat8))
+            });
+            if is_blob {
+                let bytes = values
+                    .iter()
+                    .filter_map(|v| match v {
+                        IDLValue::Nat8(byte) => Some(*byte),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                blob_to_json(&bytes, format)
+            } else {
+                Value::Array(
+                    values
+                        .iter()
+                        .map(|value| idl_to_json(value, element_ty, env, format))
+                        .collect(),
+                )
+            }
+        }
+        IDLValue::Record(fields) => {
+            let type_fields = match inner {
+                Some(TypeInner::Record(type_fields)) => type_fields.as_slice(),
+                _ => &[],
+            };
+            let is_tuple = !fields.is_empty()
+                && fields.iter().enumerate().all(|(index, field)| {
+                    !matches!(field.id, Label::Named(_)) && field.id.get_id() == index as u32
+                })
+                && (type_fields.is_empty() || is_tuple(type_fields));
+            if is_tuple {
+                Value::Array(
+                    fields
+                        .iter()
+                        .map(|field| {
+                            let (_, ty) = field_name_and_type(&field.id, type_fields);
+                            idl_to_json(&field.val, ty, env, format)
+                        })
+                        .collect(),
+                )
+            } else {
+                let mut object = Map::new();
+                for field in fields {
+                    let (name, ty) = field_name_and_type(&field.id, type_fields);
+                    object.insert(name, idl_to_json(&field.val, ty, env, format));
+                }
+                Value::Object(object)
+            }
+        }
+        IDLValue::Variant(VariantValue(field, _)) => {
+            let type_fields = match inner {
+                Some(TypeInner::Variant(type_fields)) => type_fields.as_slice(),
+                _ => &[],
+            };
+            let (name, ty) = field_name_and_type(&field.id, type_fields);
+            let mut object = Map::new();
+            object.insert(name, idl_to_json(&field.val, ty, env, format));
+            Value::Object(object)
+        }
+    }
+}
+
+/// Field names from the types take precedence, because values decoded without types only have hashes.
+fn field_name_and_type<'a>(label: &Label, type_fields: &'a [Field]) -> (String, Option<&'a Type>) {
+    match type_fields
+        .iter()
+        .find(|field| field.id.get_id() == label.get_id())
+    {
+        Some(field) => (field_name(&field.id), Some(&field.ty)),
+        None => (field_name(label), None),
+    }
+}
+
+fn blob_to_json(bytes: &[u8], format: BlobFormat) -> Value {
+    Value::String(match format {
+        BlobFormat::Hex => hex::encode(bytes),
+        BlobFormat::Base64 => base64::encode(bytes),
+    })
+}
+
+fn float_to_json(f: f64) -> Value {
+    Number::from_f64(f)
+        .map(Value::Number)
+        .unwrap_or_else(|| Value::String(f.to_string()))
+}
+
+fn parse_number<T>(json: &Value, path: &str, ty: &Type) -> DfxResult<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let text = match json {
+        Value::Number(number) => number.to_string(),
+        Value::String(text) => text.replace('_', ""),
+        _ => bail!("Expected a number or a string of type {ty} at {path}, but found {json}."),
+    };
+    text.parse()
+        .map_err(|e| anyhow!("Invalid {ty} '{text}' at {path}: {e}"))
+}
+
+fn parse_principal(text: &str, path: &str) -> DfxResult<Principal> {
+    Principal::from_text(text).with_context(|| format!("Invalid principal '{text}' at {path}."))
+}
+
+fn is_blob(env: &TypeEnv, inner: &Type) -> DfxResult<bool> {
+    Ok(matches!(env.trace_type(inner)?.as_ref(), TypeInner::Nat8))
+}
+
+fn is_tuple(fields: &[Field]) -> bool {
+    fields
+        .iter()
+        .all(|field| matches!(field.id.as_ref(), Label::Unnamed(_)))
+}
+
+/// Record fields that can be left out of the JSON object.
+fn missing_field_value(env: &TypeEnv, ty: &Type) -> DfxResult<Option<IDLValue>> {
+    Ok(match env.trace_type(ty)?.as_ref() {
+        TypeInner::Opt(_) => Some(IDLValue::None),
+        TypeInner::Null => Some(IDLValue::Null),
+        TypeInner::Reserved => Some(IDLValue::Reserved),
+        _ => None,
+    })
+}
+
+fn field_name(label: &Label) -> String {
+    match label {
+        Label::Named(name) => name.clone(),
+        Label::Id(id) | Label::Unnamed(id) => id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid_parser::utils::CandidSource;
+
+    const DID: &str = r#"
+        type Status = variant { Active; Suspended : record { reason : text } };
+        type Account = record { owner : principal; subaccount : opt blob };
+        service : {
+            transfer : (record { to : Account; amount : nat; memo : opt nat64; status : Status; pair : record { nat8; text } }) -> ();
+            two : (int, vec int16) -> ();
+        }
+    "#;
+
+    /// Converts the JSON argument and checks that it encodes like the Candid argument.
+    fn assert_converts(method: &str, json: &str, candid: &str) {
+        let (env, func) = crate::util::get_candid_type(CandidSource::Text(DID), method).unwrap();
+        let expected = candid_parser::parse_idl_args(candid)
+            .unwrap()
+            .to_bytes_with_types(&env, &func.args)
+            .unwrap();
+        let actual = json_to_idl_args(json, &env, &func.args)
+            .unwrap()
+            .to_bytes_with_types(&env, &func.args)
+            .unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    fn assert_rejects(method: &str, json: &str) {
+        let (env, func) = crate::util::get_candid_type(CandidSource::Text(DID), method).unwrap();
+        assert!(json_to_idl_args(json, &env, &func.args).is_err());
+    }
+
+    #[test]
+    fn converts_by_type() {
+        assert_converts(
+            "transfer",
+            r#"{
+                "to": { "owner": "aaaaa-aa", "subaccount": "0102" },
+                "amount": "100000000000000000000",
+                "memo": "18446744073709551615",
+                "status": { "Suspended": { "reason": "audit" } },
+                "pair": [1, "one"]
+            }"#,
+            r#"(record {
+                to = record { owner = principal "aaaaa-aa"; subaccount = opt blob "\01\02" };
+                amount = 100_000_000_000_000_000_000;
+                memo = opt 18_446_744_073_709_551_615;
+                status = variant { Suspended = record { reason = "audit" } };
+                pair = record { 1; "one" };
+            })"#,
+        );
+        assert_converts(
+            "transfer",
+            r#"{ "to": { "owner": "aaaaa-aa" }, "amount": 1, "status": "Active", "pair": [1, "one"] }"#,
+            r#"(record {
+                to = record { owner = principal "aaaaa-aa"; subaccount = null };
+                amount = 1;
+                memo = null;
+                status = variant { Active };
+                pair = record { 1; "one" };
+            })"#,
+        );
+        assert_converts("two", r#"[-5, [1, "2"]]"#, "(-5, vec { 1; 2 })");
+
+        assert_rejects("two", "[-5]");
+        assert_rejects("transfer", r#"{ "amount": 1, "extra": 2 }"#);
+        assert_rejects("two", r#"[-5, [1, 100000]]"#);
+    }
+
+    #[test]
+    fn converts_results_to_json() {
+        let (env, func) =
+            crate::util::get_candid_type(CandidSource::Text(DID), "transfer").unwrap();
+        let args = json_to_idl_args(
+            r#"{ "to": { "owner": "aaaaa-aa", "subaccount": "0102" }, "amount": "100000000000000000000", "memo": "5", "status": "Active", "pair": [1, "one"] }"#,
+            &env,
+            &func.args,
+        )
+        .unwrap();
+        // Decoding without types leaves only the hashes of the field names.
+        let untyped =
+            IDLArgs::from_bytes(&args.to_bytes_with_types(&env, &func.args).unwrap()).unwrap();
+        let expected = json!({
+            "to": { "owner": "aaaaa-aa", "subaccount": ["AQI="] },
+            "amount": "100000000000000000000",
+            "memo": ["5"],
+            "status": { "Active": null },
+            "pair": [1, "one"]
+        });
+        assert_eq!(
+            idl_args_to_json(&untyped, Some((&env, &func.args)), BlobFormat::Base64),
+            vec![expected]
+        );
+        let none = json_to_idl_args(
+            r#"{ "to": { "owner": "aaaaa-aa" }, "amount": 1, "status": "Active", "pair": [1, "one"] }"#,
+            &env,
+            &func.args,
+        )
+        .unwrap();
+        assert_eq!(
+            idl_args_to_json(&none, Some((&env, &func.args)), BlobFormat::Hex)[0]["memo"],
+            json!([])
+        );
+        let without_types = idl_args_to_json(&untyped, None, BlobFormat::Hex);
+        let amount_hash = Label::Named("amount".to_string()).get_id().to_string();
+        assert_eq!(
+            without_types[0][amount_hash],
+            json!("100000000000000000000")
+        );
+    }
+}
@@ -19,6 +19,7 @@
 //! Beyond that, the name of the field for the argument type is also different:
 //!   - In [ArgumentFromCliLongOpt], it is [argument_type](ArgumentFromCliLongOpt::argument_type).
 //!   - In [ArgumentFromCliPositionalOpt], it is [type](ArgumentFromCliPositionalOpt::type).
+use std::ffi::OsStr;
 use std::path::PathBuf;
 
 use clap::Args;
@@ -38,10 +39,12 @@ pub struct ArgumentFromCliLongOpt {
     argument: Option<String>,
 
     /// Specifies the data type for the argument when making the call using an argument.
-    #[arg(long, requires("argument"), value_parser = ["idl", "raw"])]
+    /// JSON arguments are converted using the Candid types of the method.
+    #[arg(long, requires("argument"), value_parser = ["idl", "raw", "json"])]
     argument_type: Option<String>,
 
     /// Specifies the file from which to read the argument to pass to the method.
+    /// Files with the extension .json contain JSON arguments.
     #[arg(long, value_parser = file_or_stdin_parser, conflicts_with("argument"))]
     argument_file: Option<PathBuf>,
 }
@@ -63,10 +66,12 @@ pub struct ArgumentFromCliPositionalOpt {
     argument: Option<String>,
 
     /// Specifies the data type for the argument when making the call using an argument.
-    #[arg(long, requires("argument"), value_parser = ["idl", "raw"])]
+    /// JSON arguments are converted using the Candid types of the method.
+    #[arg(long, requires("argument"), value_parser = ["idl", "raw", "json"])]
     r#type: Option<String>,
 
     /// Specifies the file from which to read the argument to pass to the method.
+    /// Files with the extension .json contain JSON arguments.
     #[arg(long, value_parser = file_or_stdin_parser, conflicts_with("argument"))]
     argument_file: Option<PathBuf>,
 }
@@ -88,5 +93,11 @@ fn get_argument_from_cli(
         .transpose()?;
     let arguments = argument.clone();
     let argument_from_cli = arguments_from_file.or(arguments);
-    Ok((argument_from_cli, argument_type.clone()))
+    let argument_type = argument_type.clone().or_else(|| {
+        argument_file
+            .as_deref()
+            .filter(|file| file.extension() == Some(OsStr::new("json")))
+            .map(|_| "json".to_string())
+    });
+    Ok((argument_from_cli, argument_type))
 }
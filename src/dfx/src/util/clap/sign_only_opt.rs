@@ -0,0 +1,88 @@
+use crate::lib::environment::Environment;
+use crate::lib::error::DfxResult;
+use crate::lib::sign::signed_message::{format_utc, sign_update};
+use anyhow::{Context, anyhow, bail};
+use candid::Principal;
+use clap::Args;
+use dfx_core::json::save_json_file;
+use slog::info;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Args, Clone, Debug)]
+pub struct SignOnlyOpt {
+    /// Signs the call and writes it to the specified file instead of sending it.
+    /// This does not need a connection to the network, so the identity can stay on an air-gapped machine.
+    /// Send the message with `dfx canister send <file>`.
+    #[arg(long)]
+    sign_only: Option<PathBuf>,
+
+    /// Specifies how long the message signed with `--sign-only` is valid.
+    /// The IC rejects messages that expire more than 5 minutes in the future.
+    #[arg(long, default_value = "5m", requires("sign_only"))]
+    expire_after: String,
+}
+
+impl SignOnlyOpt {
+    /// The file to write the signed message to, if the call should only be signed.
+    pub fn file(&self) -> Option<&Path> {
+        self.sign_only.as_deref()
+    }
+
+    /// Signs an update call with the selected identity and writes it to `file`.
+    pub fn sign_update(
+        &self,
+        env: &dyn Environment,
+        file: &Path,
+        canister_id: Principal,
+        method_name: &str,
+        arg: Vec<u8>,
+    ) -> DfxResult {
+        if file.exists() {
+            bail!(
+                "[{}] already exists, please specify a different output file name.",
+                file.display()
+            );
+        }
+        let timeout = humantime::parse_duration(&self.expire_after).map_err(|_| {
+            anyhow!("Cannot parse expire_after as a duration (e.g. `1h`, `1h 30m`)")
+        })?;
+        let message = sign_update(env, canister_id, canister_id, method_name, arg, timeout)?;
+        save_json_file(file, &message)?;
+        info!(
+            env.get_logger(),
+            "Signed {method_name} message generated at [{}], valid until {}. Send it with `dfx canister send {}`.",
+            file.display(),
+            format_utc(message.expiration),
+            file.display()
+        );
+        Ok(())
+    }
+
+    /// Signs a ledger update call whose argument contains the transaction timestamp, and writes it to `file`.
+    /// `created_at_time` defaults to the current time. It is logged, because signing the operation again
+    /// with the same timestamp lets the ledger deduplicate the transactions.
+    pub fn sign_ledger_update(
+        &self,
+        env: &dyn Environment,
+        file: &Path,
+        created_at_time: Option<u64>,
+        canister_id: Principal,
+        method_name: &str,
+        arg: impl FnOnce(u64) -> Result<Vec<u8>, candid::Error>,
+    ) -> DfxResult {
+        let created_at_time = created_at_time.unwrap_or(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos() as u64,
+        );
+        let arg = arg(created_at_time).context("Failed to encode arguments.")?;
+        self.sign_update(env, file, canister_id, method_name, arg)?;
+        info!(
+            env.get_logger(),
+            "If you sign this operation again, use --created-at-time {}", created_at_time
+        );
+        Ok(())
+    }
+}
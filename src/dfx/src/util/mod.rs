@@ -3,6 +3,7 @@ use crate::lib::error::DfxResult;
 use crate::lib::integrations::bitcoin::MAINNET_BITCOIN_CANISTER_ID;
 use crate::lib::ledger_types::{MAINNET_CYCLE_MINTER_CANISTER_ID, MAINNET_LEDGER_CANISTER_ID};
 use crate::lib::subnet::MAINNET_REGISTRY_CANISTER_ID;
+use crate::util::candid_json::{BlobFormat, idl_args_to_json};
 use crate::{error_invalid_argument, error_invalid_data, error_unknown};
 use anyhow::{Context, anyhow, bail};
 use backoff::ExponentialBackoff;
@@ -16,7 +17,6 @@ use dfx_core::config::model::network_descriptor::MAINNET_MOTOKO_PLAYGROUND_CANIS
 use dfx_core::error::cli::UserConsent;
 use dfx_core::fs::create_dir_all;
 use fn_error_context::context;
-use idl2json::{Idl2JsonOptions, idl2json};
 use num_traits::FromPrimitive;
 use reqwest::{Client, StatusCode, Url};
 use rust_decimal::Decimal;
@@ -28,6 +28,7 @@ use std::path::Path;
 use std::time::Duration;
 
 pub mod assets;
+pub mod candid_json;
 pub mod clap;
 pub mod command;
 pub mod currency_conversion;
@@ -47,11 +48,13 @@ pub fn get_reusable_socket_addr(ip: IpAddr, port: u16) -> DfxResult<SocketAddr>
 }
 
 /// Deserialize and print return values from canister method.
+/// JSON output uses the result types of the method for field names and encodings.
 #[context("Failed to deserialize idl blob: Invalid data.")]
 pub fn print_idl_blob(
     blob: &[u8],
     output_type: Option<&str>,
     method_type: &Option<(TypeEnv, Function)>,
+    blob_format: BlobFormat,
 ) -> DfxResult<()> {
     let output_type = output_type.unwrap_or("pp");
     match output_type {
@@ -71,7 +74,10 @@ pub fn print_idl_blob(
             if output_type == "idl" {
                 println!("{:?}", result?);
             } else if output_type == "json" {
-                let json = convert_all(&result?)?;
+                let method_type = method_type
+                    .as_ref()
+                    .map(|(env, func)| (env, func.rets.as_slice()));
+                let json = convert_all(&result?, method_type, blob_format)?;
                 println!("{json}");
             } else {
                 println!("{}", result?);
@@ -82,17 +88,18 @@ pub fn print_idl_blob(
     Ok(())
 }
 
-/// Candid typically comes as a tuple of values.  This converts a single value in such a tuple.
-fn convert_one(idl_value: &IDLValue) -> DfxResult<String> {
-    let json_value = idl2json(idl_value, &Idl2JsonOptions::default());
-    serde_json::to_string_pretty(&json_value).with_context(|| anyhow!("Cannot pretty-print json"))
-}
-
-fn convert_all(idl_args: &IDLArgs) -> DfxResult<String> {
-    let json_structures = idl_args
-        .args
+/// Candid typically comes as a tuple of values. Each value of the tuple is printed on its own.
+fn convert_all(
+    idl_args: &IDLArgs,
+    method_type: Option<(&TypeEnv, &[Type])>,
+    blob_format: BlobFormat,
+) -> DfxResult<String> {
+    let json_structures = idl_args_to_json(idl_args, method_type, blob_format)
         .iter()
-        .map(convert_one)
+        .map(|json_value| {
+            serde_json::to_string_pretty(json_value)
+                .with_context(|| anyhow!("Cannot pretty-print json"))
+        })
         .collect::<Result<Vec<_>, _>>()?;
     Ok(json_structures.join("\n"))
 }
@@ -278,6 +285,16 @@ pub fn blob_from_arguments(
             .map_err(|e| error_invalid_data!("Unable to serialize Candid values: {}", e))?;
             Ok(typed_args)
         }
+        "json" => {
+            let Some((env, func)) = method_type else {
+                bail!(
+                    "Cannot convert the JSON argument, because the Candid types of the method are unknown."
+                );
+            };
+            let args = candid_json::json_to_idl_args(arguments.unwrap_or("null"), env, &func.args)?;
+            args.to_bytes_with_types(env, &func.args)
+                .map_err(|e| error_invalid_data!("Unable to serialize Candid values: {}", e))
+        }
         v => Err(error_unknown!("Invalid type: {}", v)),
     }
 }